@@ -2,6 +2,7 @@ pub mod macros;
 pub use macros::*;
 
 use near_sdk::env;
+use types::{require_err, BridgeError};
 
 pub type Mask = u64;
 
@@ -25,11 +26,11 @@ pub trait AdminControlled {
 
     /// Asserts if the contract is paused for the current flag and user
     fn assert_not_paused(&self, flag: Mask) {
-        assert!(!self.is_paused(flag));
+        require_err!(!self.is_paused(flag), BridgeError::Paused);
     }
 
     /// Asserts if the contract is paused for the current flag
     fn assert_not_paused_flags(&self, flag: Mask) {
-        assert!((self.get_paused() & flag) == 0);
+        require_err!((self.get_paused() & flag) == 0, BridgeError::Paused);
     }
 }
@@ -0,0 +1,43 @@
+use rpc_types::errors::ValidationError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("not a connector event: {0}")]
+    UnknownEvent(String),
+    #[error("{event} has {found} fields, the connectors expect {expected}")]
+    FieldCount {
+        event: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("field {field} of {event} is not valid: {value}")]
+    InvalidField {
+        event: &'static str,
+        field: &'static str,
+        value: String,
+    },
+    #[error("not a hex or base58 encoded hash: {0}")]
+    InvalidHash(String),
+    #[error("not an outcome proof, expected an EXPERIMENTAL_light_client_proof response, a FullOutcomeProof or connector call arguments")]
+    ProofFormat,
+    #[error(transparent)]
+    InvalidProof(#[from] ValidationError),
+    #[error("unknown contract {contract}, expected one of: {expected}")]
+    UnknownContract { contract: String, expected: String },
+    #[error("{contract} has no pause flag {flag}, expected one of: {expected}")]
+    UnknownPauseFlag {
+        contract: String,
+        flag: String,
+        expected: String,
+    },
+    #[error("not a view_state dump, expected a list of base64 encoded keys and values")]
+    StateFormat,
+    #[error("state dump has no {0} entry")]
+    MissingEntry(String),
+    #[error("failed to decode the light client state: {0}")]
+    InvalidState(std::io::Error),
+    #[error(transparent)]
+    Json(#[from] near_sdk::serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
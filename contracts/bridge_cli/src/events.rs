@@ -0,0 +1,460 @@
+//! Connector event logs, parsed the way the connectors consuming them do.
+
+use crate::errors::CliError;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::{self, json, Value};
+
+// event names, as logged by the connectors
+pub const DEPLOY_FT: &str = "CALIMERO_EVENT_DEPLOY_FT";
+pub const LOCK_FT: &str = "CALIMERO_EVENT_LOCK_FT";
+pub const BURN_FT: &str = "CALIMERO_EVENT_BURN_FT";
+pub const MINTED_FT: &str = "CALIMERO_EVENT_MINTED_FT";
+pub const MINT_FAILED_FT: &str = "CALIMERO_EVENT_MINT_FAILED_FT";
+pub const CANCEL_FT: &str = "CALIMERO_EVENT_CANCEL_FT";
+pub const LIMIT_EXCEEDED_FT: &str = "CALIMERO_EVENT_LIMIT_EXCEEDED_FT";
+pub const QUEUE_CANCELLED_FT: &str = "CALIMERO_EVENT_QUEUE_CANCELLED_FT";
+pub const DEPLOY_NFT: &str = "CALIMERO_EVENT_DEPLOY_NFT";
+pub const LOCK_NFT: &str = "CALIMERO_EVENT_LOCK_NFT";
+pub const BURN_NFT: &str = "CALIMERO_EVENT_BURN_NFT";
+pub const MINTED_NFT: &str = "CALIMERO_EVENT_MINTED_NFT";
+pub const MINT_FAILED_NFT: &str = "CALIMERO_EVENT_MINT_FAILED_NFT";
+pub const CANCEL_NFT: &str = "CALIMERO_EVENT_CANCEL_NFT";
+pub const LIMIT_EXCEEDED_NFT: &str = "CALIMERO_EVENT_LIMIT_EXCEEDED_NFT";
+pub const QUEUE_CANCELLED_NFT: &str = "CALIMERO_EVENT_QUEUE_CANCELLED_NFT";
+pub const CROSS_CALL: &str = "CALIMERO_EVENT_CROSS_CALL";
+pub const CROSS_RESPONSE: &str = "CALIMERO_EVENT_CROSS_RESPONSE";
+
+/// Response logged by the xsc connector when the cross contract call failed.
+pub const CROSS_CALL_FAILED: &str = "FAILED!";
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde", tag = "event")]
+pub enum BridgeEvent {
+    #[serde(rename = "CALIMERO_EVENT_DEPLOY_FT")]
+    DeployFt {
+        source_address: String,
+        bridge_token_address: String,
+    },
+    #[serde(rename = "CALIMERO_EVENT_LOCK_FT")]
+    LockFt {
+        token: String,
+        sender: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+        /// Account the Token is minted to on the other network
+        receiver: String,
+        /// Empty if the sender set none
+        memo: String,
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_BURN_FT")]
+    BurnFt {
+        token: String,
+        burner: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+    },
+    #[serde(rename = "CALIMERO_EVENT_MINTED_FT")]
+    MintedFt {
+        token: String,
+        receiver: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+        /// Nonce of the lock
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_MINT_FAILED_FT")]
+    MintFailedFt {
+        token: String,
+        receiver: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+        /// Nonce of the lock
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_CANCEL_FT")]
+    CancelFt {
+        token: String,
+        sender: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+        /// Nonce of the lock
+        nonce: u64,
+    },
+    /// Alert of a mint or unlock that was not executed and paused the connector
+    #[serde(rename = "CALIMERO_EVENT_LIMIT_EXCEEDED_FT")]
+    LimitExceededFt {
+        token: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+        /// `volume` or `global_transfers`
+        limit: String,
+    },
+    /// Queued mint or unlock dropped by the guardian
+    #[serde(rename = "CALIMERO_EVENT_QUEUE_CANCELLED_FT")]
+    QueueCancelledFt {
+        id: u64,
+        /// `mint` or `unlock`
+        action: String,
+        token: String,
+        receiver: String,
+        #[serde(with = "utils::u128_dec_format")]
+        amount: u128,
+    },
+    #[serde(rename = "CALIMERO_EVENT_DEPLOY_NFT")]
+    DeployNft {
+        source_address: String,
+        bridge_token_address: String,
+    },
+    #[serde(rename = "CALIMERO_EVENT_LOCK_NFT")]
+    LockNft {
+        token: String,
+        previous_owner: String,
+        token_id: String,
+        metadata: Value,
+        /// Account the Token is minted to on the other network
+        receiver: String,
+        /// Empty if the sender set none
+        memo: String,
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_BURN_NFT")]
+    BurnNft {
+        token: String,
+        burner: String,
+        token_id: String,
+    },
+    #[serde(rename = "CALIMERO_EVENT_MINTED_NFT")]
+    MintedNft {
+        token: String,
+        receiver: String,
+        token_id: String,
+        /// Nonce of the lock
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_MINT_FAILED_NFT")]
+    MintFailedNft {
+        token: String,
+        receiver: String,
+        token_id: String,
+        /// Nonce of the lock
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_CANCEL_NFT")]
+    CancelNft {
+        token: String,
+        sender: String,
+        token_id: String,
+        /// Nonce of the lock
+        nonce: u64,
+    },
+    #[serde(rename = "CALIMERO_EVENT_LIMIT_EXCEEDED_NFT")]
+    LimitExceededNft {
+        token: String,
+        /// Always 1
+        count: u64,
+        limit: String,
+    },
+    /// Queued mint or unlock dropped by the guardian
+    #[serde(rename = "CALIMERO_EVENT_QUEUE_CANCELLED_NFT")]
+    QueueCancelledNft {
+        id: u64,
+        /// `mint` or `unlock`
+        action: String,
+        token: String,
+        receiver: String,
+        token_id: String,
+    },
+    #[serde(rename = "CALIMERO_EVENT_CROSS_CALL")]
+    CrossCall {
+        destination_contract: String,
+        method: String,
+        /// JSON arguments as a value, other arguments as a string or `{"base64": ..}`
+        args: Value,
+        gas: u64,
+        #[serde(with = "utils::u128_dec_format")]
+        deposit: u128,
+        initiator: String,
+        callback_method: String,
+    },
+    #[serde(rename = "CALIMERO_EVENT_CROSS_RESPONSE")]
+    CrossResponse {
+        source_contract: String,
+        source_method: String,
+        /// Result of the call decoded like `CrossCall::args`, `None` if the call failed
+        result: Option<Value>,
+        destination_contract: String,
+    },
+}
+
+/// An event with the ids of the chain it was emitted on and the chain it is meant for, which
+/// the connectors log as its last two fields.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChainEvent {
+    #[serde(flatten)]
+    pub event: BridgeEvent,
+    pub source_chain: String,
+    pub destination_chain: String,
+}
+
+/// Decodes an event log, either as logged or base64 encoded.
+pub fn decode_event(input: &str) -> Result<ChainEvent, CliError> {
+    let input = input.trim();
+    if input.starts_with("CALIMERO_EVENT_") {
+        return parse_event(input);
+    }
+    let log = utils::from_base64(input)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| CliError::UnknownEvent(input.to_string()))?;
+    parse_event(&log)
+}
+
+/// Parses an event log as logged by the connectors.
+pub fn parse_event(log: &str) -> Result<ChainEvent, CliError> {
+    let fields: Vec<&str> = log.split(':').collect();
+    let event = match fields[0] {
+        DEPLOY_FT => {
+            let [source_address, bridge_token_address] = fields_of(DEPLOY_FT, &fields)?;
+            BridgeEvent::DeployFt {
+                source_address,
+                bridge_token_address,
+            }
+        }
+        LOCK_FT => {
+            let [token, sender, amount, receiver, memo, nonce] = fields_of(LOCK_FT, &fields)?;
+            BridgeEvent::LockFt {
+                token,
+                sender,
+                amount: number(LOCK_FT, "amount", &amount)?,
+                receiver,
+                memo: text(LOCK_FT, "memo", &memo)?,
+                nonce: number(LOCK_FT, "nonce", &nonce)?,
+            }
+        }
+        BURN_FT => {
+            let [token, burner, amount] = fields_of(BURN_FT, &fields)?;
+            BridgeEvent::BurnFt {
+                token,
+                burner,
+                amount: number(BURN_FT, "amount", &amount)?,
+            }
+        }
+        MINTED_FT => {
+            let [token, receiver, amount, nonce] = fields_of(MINTED_FT, &fields)?;
+            BridgeEvent::MintedFt {
+                token,
+                receiver,
+                amount: number(MINTED_FT, "amount", &amount)?,
+                nonce: number(MINTED_FT, "nonce", &nonce)?,
+            }
+        }
+        MINT_FAILED_FT => {
+            let [token, receiver, amount, nonce] = fields_of(MINT_FAILED_FT, &fields)?;
+            BridgeEvent::MintFailedFt {
+                token,
+                receiver,
+                amount: number(MINT_FAILED_FT, "amount", &amount)?,
+                nonce: number(MINT_FAILED_FT, "nonce", &nonce)?,
+            }
+        }
+        CANCEL_FT => {
+            let [token, sender, amount, nonce] = fields_of(CANCEL_FT, &fields)?;
+            BridgeEvent::CancelFt {
+                token,
+                sender,
+                amount: number(CANCEL_FT, "amount", &amount)?,
+                nonce: number(CANCEL_FT, "nonce", &nonce)?,
+            }
+        }
+        LIMIT_EXCEEDED_FT => {
+            let [token, amount, limit] = fields_of(LIMIT_EXCEEDED_FT, &fields)?;
+            BridgeEvent::LimitExceededFt {
+                token,
+                amount: number(LIMIT_EXCEEDED_FT, "amount", &amount)?,
+                limit,
+            }
+        }
+        QUEUE_CANCELLED_FT => {
+            let [id, action, token, receiver, amount] = fields_of(QUEUE_CANCELLED_FT, &fields)?;
+            BridgeEvent::QueueCancelledFt {
+                id: number(QUEUE_CANCELLED_FT, "id", &id)?,
+                action,
+                token,
+                receiver,
+                amount: number(QUEUE_CANCELLED_FT, "amount", &amount)?,
+            }
+        }
+        DEPLOY_NFT => {
+            let [source_address, bridge_token_address] = fields_of(DEPLOY_NFT, &fields)?;
+            BridgeEvent::DeployNft {
+                source_address,
+                bridge_token_address,
+            }
+        }
+        LOCK_NFT => {
+            let [token, previous_owner, token_id, metadata, receiver, memo, nonce] =
+                fields_of(LOCK_NFT, &fields)?;
+            let metadata = text(LOCK_NFT, "metadata", &metadata)?;
+            BridgeEvent::LockNft {
+                token,
+                previous_owner,
+                token_id: text(LOCK_NFT, "token_id", &token_id)?,
+                metadata: serde_json::from_str(&metadata).map_err(|_| CliError::InvalidField {
+                    event: LOCK_NFT,
+                    field: "metadata",
+                    value: metadata,
+                })?,
+                receiver,
+                memo: text(LOCK_NFT, "memo", &memo)?,
+                nonce: number(LOCK_NFT, "nonce", &nonce)?,
+            }
+        }
+        BURN_NFT => {
+            let [token, burner, token_id] = fields_of(BURN_NFT, &fields)?;
+            BridgeEvent::BurnNft {
+                token,
+                burner,
+                token_id: text(BURN_NFT, "token_id", &token_id)?,
+            }
+        }
+        MINTED_NFT => {
+            let [token, receiver, token_id, nonce] = fields_of(MINTED_NFT, &fields)?;
+            BridgeEvent::MintedNft {
+                token,
+                receiver,
+                token_id: text(MINTED_NFT, "token_id", &token_id)?,
+                nonce: number(MINTED_NFT, "nonce", &nonce)?,
+            }
+        }
+        MINT_FAILED_NFT => {
+            let [token, receiver, token_id, nonce] = fields_of(MINT_FAILED_NFT, &fields)?;
+            BridgeEvent::MintFailedNft {
+                token,
+                receiver,
+                token_id: text(MINT_FAILED_NFT, "token_id", &token_id)?,
+                nonce: number(MINT_FAILED_NFT, "nonce", &nonce)?,
+            }
+        }
+        CANCEL_NFT => {
+            let [token, sender, token_id, nonce] = fields_of(CANCEL_NFT, &fields)?;
+            BridgeEvent::CancelNft {
+                token,
+                sender,
+                token_id: text(CANCEL_NFT, "token_id", &token_id)?,
+                nonce: number(CANCEL_NFT, "nonce", &nonce)?,
+            }
+        }
+        LIMIT_EXCEEDED_NFT => {
+            let [token, count, limit] = fields_of(LIMIT_EXCEEDED_NFT, &fields)?;
+            BridgeEvent::LimitExceededNft {
+                token,
+                count: number(LIMIT_EXCEEDED_NFT, "count", &count)?,
+                limit,
+            }
+        }
+        QUEUE_CANCELLED_NFT => {
+            let [id, action, token, receiver, token_id] = fields_of(QUEUE_CANCELLED_NFT, &fields)?;
+            BridgeEvent::QueueCancelledNft {
+                id: number(QUEUE_CANCELLED_NFT, "id", &id)?,
+                action,
+                token,
+                receiver,
+                token_id: text(QUEUE_CANCELLED_NFT, "token_id", &token_id)?,
+            }
+        }
+        CROSS_CALL => {
+            let [destination_contract, method, args, gas, deposit, initiator, callback_method] =
+                fields_of(CROSS_CALL, &fields)?;
+            BridgeEvent::CrossCall {
+                destination_contract,
+                method,
+                args: payload(CROSS_CALL, "args", &args)?,
+                gas: number(CROSS_CALL, "gas", &gas)?,
+                deposit: number(CROSS_CALL, "deposit", &deposit)?,
+                initiator,
+                callback_method,
+            }
+        }
+        CROSS_RESPONSE => {
+            let [source_contract, source_method, result, destination_contract] =
+                fields_of(CROSS_RESPONSE, &fields)?;
+            let result = if result == CROSS_CALL_FAILED {
+                None
+            } else {
+                Some(payload(CROSS_RESPONSE, "result", &result)?)
+            };
+            BridgeEvent::CrossResponse {
+                source_contract,
+                source_method,
+                result,
+                destination_contract,
+            }
+        }
+        _ => return Err(CliError::UnknownEvent(log.to_string())),
+    };
+    // `fields_of` checked there are two more fields
+    Ok(ChainEvent {
+        event,
+        source_chain: fields[fields.len() - 2].to_string(),
+        destination_chain: fields[fields.len() - 1].to_string(),
+    })
+}
+
+/// Fields following the event name and preceding the chain ids, there must be exactly `N` of
+/// them.
+fn fields_of<const N: usize>(
+    event: &'static str,
+    fields: &[&str],
+) -> Result<[String; N], CliError> {
+    if fields.len() != N + 3 {
+        return Err(CliError::FieldCount {
+            event,
+            expected: N + 3,
+            found: fields.len(),
+        });
+    }
+    Ok(std::array::from_fn(|i| fields[i + 1].to_string()))
+}
+
+fn number<T: std::str::FromStr>(
+    event: &'static str,
+    field: &'static str,
+    value: &str,
+) -> Result<T, CliError> {
+    value.parse().map_err(|_| CliError::InvalidField {
+        event,
+        field,
+        value: value.to_string(),
+    })
+}
+
+fn base64(event: &'static str, field: &'static str, value: &str) -> Result<Vec<u8>, CliError> {
+    utils::from_base64(value).map_err(|_| CliError::InvalidField {
+        event,
+        field,
+        value: value.to_string(),
+    })
+}
+
+/// Base64 encoded UTF-8 text.
+fn text(event: &'static str, field: &'static str, value: &str) -> Result<String, CliError> {
+    String::from_utf8(base64(event, field, value)?).map_err(|_| CliError::InvalidField {
+        event,
+        field,
+        value: value.to_string(),
+    })
+}
+
+/// Base64 encoded call arguments or results, shown as JSON when they are JSON.
+fn payload(event: &'static str, field: &'static str, value: &str) -> Result<Value, CliError> {
+    let bytes = base64(event, field, value)?;
+    if let Ok(value) = serde_json::from_slice(&bytes) {
+        return Ok(value);
+    }
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => Value::String(text),
+        Err(err) => json!({ "base64": utils::to_base64(err.into_bytes()) }),
+    })
+}
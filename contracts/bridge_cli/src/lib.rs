@@ -0,0 +1,8 @@
+//! Offline helpers for bridge operators: decoding connector events, verifying outcome
+//! proofs, naming bridged tokens, building pause masks and reading light client state dumps.
+
+pub mod errors;
+pub mod events;
+pub mod light_client_state;
+pub mod pause;
+pub mod proof;
@@ -0,0 +1,156 @@
+//! Summary of the light client contract from a dump of its storage, as returned by the
+//! `view_state` RPC query.
+
+use crate::errors::CliError;
+use crate::pause;
+use admin_controlled::Mask;
+use near_sdk::borsh::{self, BorshDeserialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, Value};
+use std::collections::{HashMap, VecDeque};
+use types::Epoch;
+use utils::Hash;
+
+/// Storage key of the contract struct.
+pub const STATE_KEY: &[u8] = b"STATE";
+
+pub type Storage = HashMap<Vec<u8>, Vec<u8>>;
+
+/// Borsh layout of `light_client::LightClient`, keep in sync with the contract.
+#[derive(BorshDeserialize)]
+struct StoredLightClient {
+    epochs: StoredVector,
+    current_height: u64,
+    last_submitter: String,
+    initialized: bool,
+    next_epoch: bool,
+    hash: Hash,
+    merkle_root: Hash,
+    _next_hash: Hash,
+    timestamp: u64,
+    signature_set: u128,
+    _signatures: StoredVector,
+    current_epoch_index: u64,
+    block_hashes: VecDeque<(u64, Hash)>,
+    _block_merkle_roots: VecDeque<(u64, Hash)>,
+    paused: Mask,
+    blocks_to_keep: u64,
+}
+
+/// Borsh layout of `near_sdk::collections::Vector`, elements are stored under the prefix
+/// followed by their little endian index.
+#[derive(BorshDeserialize)]
+struct StoredVector {
+    len: u64,
+    prefix: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct StateItem {
+    #[serde(with = "utils::base64_format")]
+    key: Vec<u8>,
+    #[serde(with = "utils::base64_format")]
+    value: Vec<u8>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EpochSummary {
+    pub index: u64,
+    #[serde(with = "utils::base_hash_format")]
+    pub epoch_id: Hash,
+    pub block_producers: usize,
+    #[serde(with = "utils::u128_dec_format")]
+    pub total_stake: u128,
+    #[serde(with = "utils::u128_dec_format")]
+    pub stake_threshold: u128,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LightClientSummary {
+    pub initialized: bool,
+    pub current_height: u64,
+    #[serde(with = "utils::base_hash_format")]
+    pub head_hash: Hash,
+    #[serde(with = "utils::base_hash_format")]
+    pub head_merkle_root: Hash,
+    /// Nanoseconds
+    pub head_timestamp: u64,
+    /// Approvals of the head block kept by the contract
+    pub head_approvals: u32,
+    pub last_submitter: String,
+    pub current_epoch_index: u64,
+    /// Whether the next block must come from the next epoch
+    pub next_epoch: bool,
+    pub epochs: Vec<EpochSummary>,
+    /// Heights of the blocks connectors can prove against, newest first
+    pub known_heights: Vec<u64>,
+    pub blocks_to_keep: u64,
+    pub paused: Vec<String>,
+}
+
+/// Parses a `view_state` response, its `result` or the list of its `values`.
+pub fn parse_state_dump(input: &str) -> Result<Storage, CliError> {
+    let mut value: Value = serde_json::from_str(input)?;
+    if value.get("jsonrpc").is_some() {
+        value = rpc_types::from_rpc_response(value).map_err(|_| CliError::StateFormat)?;
+    }
+    let values = match value {
+        Value::Object(mut result) => result.remove("values").ok_or(CliError::StateFormat)?,
+        values => values,
+    };
+    let items: Vec<StateItem> =
+        serde_json::from_value(values).map_err(|_| CliError::StateFormat)?;
+    Ok(items
+        .into_iter()
+        .map(|item| (item.key, item.value))
+        .collect())
+}
+
+pub fn light_client_summary(storage: &Storage) -> Result<LightClientSummary, CliError> {
+    let state: StoredLightClient = read(storage, STATE_KEY, "STATE")?;
+
+    let epochs = (0..state.epochs.len)
+        .map(|index| {
+            let mut key = state.epochs.prefix.clone();
+            key.extend(index.to_le_bytes());
+            let epoch: Epoch = read(storage, &key, &format!("epoch {}", index))?;
+            Ok(EpochSummary {
+                index,
+                epoch_id: epoch.epoch_id,
+                block_producers: epoch.keys.len(),
+                total_stake: epoch.stakes.iter().sum(),
+                stake_threshold: epoch.stake_threshold,
+            })
+        })
+        .collect::<Result<_, CliError>>()?;
+
+    Ok(LightClientSummary {
+        initialized: state.initialized,
+        current_height: state.current_height,
+        head_hash: state.hash,
+        head_merkle_root: state.merkle_root,
+        head_timestamp: state.timestamp,
+        head_approvals: state.signature_set.count_ones(),
+        last_submitter: state.last_submitter,
+        current_epoch_index: state.current_epoch_index,
+        next_epoch: state.next_epoch,
+        epochs,
+        known_heights: state
+            .block_hashes
+            .iter()
+            .map(|(height, _)| *height)
+            .collect(),
+        blocks_to_keep: state.blocks_to_keep,
+        paused: pause::paused_flags("light-client", state.paused)?,
+    })
+}
+
+fn read<T: BorshDeserialize>(storage: &Storage, key: &[u8], name: &str) -> Result<T, CliError> {
+    let value = storage
+        .get(key)
+        .ok_or_else(|| CliError::MissingEntry(name.to_string()))?;
+    T::try_from_slice(value).map_err(CliError::InvalidState)
+}
@@ -0,0 +1,118 @@
+use bridge_cli::errors::CliError;
+use bridge_cli::{events, light_client_state, pause, proof};
+use clap::{Parser, Subcommand};
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::{self, json};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{fs, io, process};
+
+/// Offline tools for bridge operators.
+#[derive(Parser)]
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Decodes a CALIMERO_EVENT_* log
+    DecodeEvent {
+        /// The log as logged or base64 encoded, read from stdin when omitted
+        log: Option<String>,
+    },
+    /// Verifies an outcome proof against the merkle root of the block used for proving
+    VerifyProof {
+        /// EXPERIMENTAL_light_client_proof response, FullOutcomeProof or connector call
+        /// arguments, `-` for stdin
+        #[clap(long)]
+        proof: PathBuf,
+        /// Block merkle root known to the light client, hex or base58 encoded
+        #[clap(long)]
+        merkle_root: String,
+    },
+    /// Prints the account of the bridged token the deployer creates for a source token
+    TokenAccount {
+        /// Token account on the source chain
+        #[clap(long)]
+        source: String,
+        /// Master account of the source chain the deployer was initialized with
+        #[clap(long)]
+        master_account: String,
+        /// Account of the bridge token deployer
+        #[clap(long)]
+        deployer: String,
+    },
+    /// Prints the `set_paused` arguments pausing the given flags, none unpause everything
+    PauseMask {
+        /// light-client, ft-connector, nft-connector, xsc-connector or bridge-token
+        #[clap(long)]
+        contract: String,
+        /// Flags to pause, `all` pauses every flag of the contract
+        flags: Vec<String>,
+    },
+    /// Summarizes the light client from a view_state dump of its account
+    LightClientState {
+        /// view_state response or its values, `-` for stdin
+        #[clap(long)]
+        dump: PathBuf,
+    },
+}
+
+fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    fs::read_to_string(path)
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), CliError> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn run(command: Command) -> Result<(), CliError> {
+    match command {
+        Command::DecodeEvent { log } => {
+            let log = match log {
+                Some(log) => log,
+                None => read_input(Path::new("-"))?,
+            };
+            print_json(&events::decode_event(&log)?)
+        }
+        Command::VerifyProof { proof, merkle_root } => {
+            let merkle_root = proof::parse_hash(&merkle_root)?;
+            let proof = proof::parse_proof(&read_input(&proof)?)?;
+            print_json(&proof::verify_proof(&proof, merkle_root)?)
+        }
+        Command::TokenAccount {
+            source,
+            master_account,
+            deployer,
+        } => {
+            println!(
+                "{}",
+                utils::bridge_token_account_name(&source, &master_account, &deployer)
+            );
+            Ok(())
+        }
+        Command::PauseMask { contract, flags } => {
+            let paused = pause::pause_mask(&contract, &flags)?;
+            println!("{}", json!({ "paused": paused }));
+            Ok(())
+        }
+        Command::LightClientState { dump } => {
+            let storage = light_client_state::parse_state_dump(&read_input(&dump)?)?;
+            print_json(&light_client_state::light_client_summary(&storage)?)
+        }
+    }
+}
+
+fn main() {
+    if let Err(err) = run(Args::parse().command) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,97 @@
+//! Names of the flags contracts accept in `set_paused`.
+
+use crate::errors::CliError;
+use admin_controlled::Mask;
+
+/// Pausable contracts and their flags, by the names used on the command line.
+pub const CONTRACTS: &[(&str, &[(&str, Mask)])] = &[
+    (
+        "light-client",
+        &[("add_block_header", light_client::PAUSE_ADD_BLOCK_HEADER)],
+    ),
+    (
+        "ft-connector",
+        &[
+            ("deploy_token", ft_connector::PAUSE_DEPLOY_TOKEN),
+            ("mint", ft_connector::PAUSE_MINT),
+            ("lock", ft_connector::PAUSE_LOCK),
+            ("unlock", ft_connector::PAUSE_UNLOCK),
+        ],
+    ),
+    (
+        "nft-connector",
+        &[
+            ("deploy_token", nft_connector::PAUSE_DEPLOY_TOKEN),
+            ("mint", nft_connector::PAUSE_MINT),
+            ("lock", nft_connector::PAUSE_LOCK),
+            ("unlock", nft_connector::PAUSE_UNLOCK),
+        ],
+    ),
+    (
+        "xsc-connector",
+        &[("cross_call", xsc_connector::PAUSE_CROSS_CALL)],
+    ),
+    (
+        "bridge-token",
+        &[("withdraw", bridge_token::PAUSE_WITHDRAW)],
+    ),
+];
+
+/// Flag that pauses everything a contract can pause.
+pub const ALL: &str = "all";
+
+fn flags_of(contract: &str) -> Result<&'static [(&'static str, Mask)], CliError> {
+    CONTRACTS
+        .iter()
+        .find(|(name, _)| *name == contract)
+        .map(|(_, flags)| *flags)
+        .ok_or_else(|| CliError::UnknownContract {
+            contract: contract.to_string(),
+            expected: CONTRACTS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+}
+
+/// Mask pausing the named flags of `contract`, no flags unpause everything.
+pub fn pause_mask<S: AsRef<str>>(contract: &str, flags: &[S]) -> Result<Mask, CliError> {
+    let known = flags_of(contract)?;
+    let mut mask = 0;
+    for flag in flags {
+        let flag = flag.as_ref();
+        if flag == ALL {
+            mask |= known.iter().fold(0, |mask, (_, bit)| mask | bit);
+            continue;
+        }
+        let (_, bit) = known
+            .iter()
+            .find(|(name, _)| *name == flag)
+            .ok_or_else(|| CliError::UnknownPauseFlag {
+                contract: contract.to_string(),
+                flag: flag.to_string(),
+                expected: known
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .chain([ALL])
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+        mask |= bit;
+    }
+    Ok(mask)
+}
+
+/// Names of the flags set in `mask`, bits `contract` does not know are shown as numbers.
+pub fn paused_flags(contract: &str, mask: Mask) -> Result<Vec<String>, CliError> {
+    let known = flags_of(contract)?;
+    Ok((0..Mask::BITS)
+        .map(|bit| 1 << bit)
+        .filter(|bit| mask & bit != 0)
+        .map(|bit| match known.iter().find(|(_, known)| *known == bit) {
+            Some((name, _)) => name.to_string(),
+            None => bit.to_string(),
+        })
+        .collect())
+}
@@ -0,0 +1,87 @@
+//! Offline verification of outcome proofs, as done by the prover on the receiving chain.
+
+use crate::errors::CliError;
+use crate::events::{self, ChainEvent};
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::{self, Value};
+use types::{EncodedOutcomeProof, FullOutcomeProof};
+use utils::{hashes, Hash};
+
+/// Outcome proven by a valid proof.
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifiedOutcome {
+    /// Also the key connectors record the proof under, see `outcome_id_hex`
+    #[serde(with = "utils::base_hash_format")]
+    pub outcome_id: Hash,
+    /// As logged by connectors in `RecordProof:`
+    pub outcome_id_hex: String,
+    pub executor_id: String,
+    pub logs: Vec<String>,
+    /// The event of the first log, `None` if it is not a connector event
+    pub event: Option<ChainEvent>,
+    pub block_height: u64,
+    #[serde(with = "utils::base_hash_format")]
+    pub block_hash: Hash,
+    #[serde(with = "utils::base_hash_format")]
+    pub block_merkle_root: Hash,
+}
+
+/// Parses a proof given as an `EXPERIMENTAL_light_client_proof` response, a
+/// `FullOutcomeProof` as JSON or base64 encoded borsh, or the arguments of a connector call.
+pub fn parse_proof(input: &str) -> Result<FullOutcomeProof, CliError> {
+    let value: Value = match serde_json::from_str(input) {
+        Ok(value) => value,
+        // a bare base64 string
+        Err(_) => Value::String(input.trim().to_string()),
+    };
+    let value = match value {
+        Value::Object(mut args) if args.contains_key("proof") && args.contains_key("height") => {
+            args.remove("proof").unwrap()
+        }
+        value => value,
+    };
+    if let Ok(proof) = serde_json::from_value::<EncodedOutcomeProof>(value.clone()) {
+        return proof.decode().map_err(|_| CliError::ProofFormat);
+    }
+    rpc_types::outcome_proof_from_rpc(value).map_err(|_| CliError::ProofFormat)
+}
+
+/// Checks the proof against the merkle root of the light client block used for proving.
+pub fn verify_proof(
+    proof: &FullOutcomeProof,
+    block_merkle_root: Hash,
+) -> Result<VerifiedOutcome, CliError> {
+    rpc_types::validate_outcome_proof(proof, Some(block_merkle_root))?;
+
+    let outcome_with_id = &proof.outcome_proof.outcome_with_id;
+    let logs: Vec<String> = outcome_with_id
+        .outcome
+        .logs
+        .iter()
+        .map(|log| String::from_utf8_lossy(log).into_owned())
+        .collect();
+    Ok(VerifiedOutcome {
+        outcome_id: outcome_with_id.id,
+        outcome_id_hex: hashes::encode_hex(&outcome_with_id.id),
+        executor_id: outcome_with_id.outcome.executor_id.clone(),
+        event: logs.first().and_then(|log| events::parse_event(log).ok()),
+        logs,
+        block_height: proof.block_header_lite.inner_lite.height,
+        block_hash: proof.outcome_proof.block_hash,
+        block_merkle_root,
+    })
+}
+
+/// Parses a hash given as hex, like in `RecordProof:` logs, or base58 like in RPC responses.
+pub fn parse_hash(input: &str) -> Result<Hash, CliError> {
+    let input = input.trim();
+    let invalid = || CliError::InvalidHash(input.to_string());
+    if input.len() == 64 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return hashes::decode_hex(input).try_into().map_err(|_| invalid());
+    }
+    utils::from_base(input)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(invalid)
+}
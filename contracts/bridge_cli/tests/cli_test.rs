@@ -0,0 +1,303 @@
+#[cfg(test)]
+mod cli {
+    mod test {
+        use admin_controlled::AdminControlled;
+        use bridge_cli::errors::CliError;
+        use bridge_cli::events::{decode_event, BridgeEvent, ChainEvent};
+        use bridge_cli::light_client_state::{light_client_summary, parse_state_dump};
+        use bridge_cli::pause::{pause_mask, paused_flags};
+        use bridge_cli::proof::{parse_hash, parse_proof, verify_proof};
+        use light_client::{LightClient, PAUSE_ADD_BLOCK_HEADER};
+        use near_sdk::borsh::BorshSerialize;
+        use near_sdk::serde_json::{self, json};
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::{env, testing_env};
+        use rpc_types::views::LightClientExecutionProofView;
+        use test_utils::chain::{outcome, ChainGenerator};
+        use types::FullOutcomeProof;
+        use utils::hashes::encode_hex;
+
+        const LOCK_FT: &str =
+            "CALIMERO_EVENT_LOCK_FT:wrap.testnet:alice.testnet:1000:bob.calimero::7:testnet:calimero-1";
+
+        fn lock_proof() -> (FullOutcomeProof, utils::Hash) {
+            let mut chain = ChainGenerator::new("near", 1000, 10);
+            let lock = outcome("lock", "ft_connector.testnet", &[LOCK_FT]);
+            chain.produce_block(vec![lock.clone()]);
+            let head = chain.produce_blocks(3);
+            (
+                chain.outcome_proof(&lock.id, head),
+                chain.block_merkle_root(head),
+            )
+        }
+
+        #[test]
+        fn decodes_logs_as_logged_or_base64() {
+            let expected = ChainEvent {
+                event: BridgeEvent::LockFt {
+                    token: "wrap.testnet".to_string(),
+                    sender: "alice.testnet".to_string(),
+                    amount: 1000,
+                    receiver: "bob.calimero".to_string(),
+                    memo: String::new(),
+                    nonce: 7,
+                },
+                source_chain: "testnet".to_string(),
+                destination_chain: "calimero-1".to_string(),
+            };
+            assert_eq!(decode_event(LOCK_FT).unwrap(), expected);
+            assert_eq!(decode_event(&utils::to_base64(LOCK_FT)).unwrap(), expected);
+            assert_eq!(
+                serde_json::to_value(&expected).unwrap(),
+                json!({
+                    "event": "CALIMERO_EVENT_LOCK_FT",
+                    "token": "wrap.testnet",
+                    "sender": "alice.testnet",
+                    "amount": "1000",
+                    "receiver": "bob.calimero",
+                    "memo": "",
+                    "nonce": 7,
+                    "source_chain": "testnet",
+                    "destination_chain": "calimero-1",
+                })
+            );
+        }
+
+        #[test]
+        fn decodes_base64_fields() {
+            let log = format!(
+                "CALIMERO_EVENT_LOCK_NFT:nft.testnet:alice.testnet:{}:{}:alice.calimero:{}:3:testnet:calimero-1",
+                utils::to_base64("token-1"),
+                utils::to_base64(json!({ "title": "Sunset" }).to_string()),
+                utils::to_base64("gift: sunset")
+            );
+            assert_eq!(
+                decode_event(&log).unwrap().event,
+                BridgeEvent::LockNft {
+                    token: "nft.testnet".to_string(),
+                    previous_owner: "alice.testnet".to_string(),
+                    token_id: "token-1".to_string(),
+                    metadata: json!({ "title": "Sunset" }),
+                    receiver: "alice.calimero".to_string(),
+                    memo: "gift: sunset".to_string(),
+                    nonce: 3,
+                }
+            );
+
+            let log = format!(
+                "CALIMERO_EVENT_MINT_FAILED_NFT:nft.testnet:alice.testnet:{}:3:calimero-1:testnet",
+                utils::to_base64("token-1")
+            );
+            assert_eq!(
+                decode_event(&log).unwrap().event,
+                BridgeEvent::MintFailedNft {
+                    token: "nft.testnet".to_string(),
+                    receiver: "alice.testnet".to_string(),
+                    token_id: "token-1".to_string(),
+                    nonce: 3,
+                }
+            );
+
+            let log = format!(
+                "CALIMERO_EVENT_CANCEL_NFT:nft.testnet:alice.testnet:{}:3:testnet:calimero-1",
+                utils::to_base64("token-1")
+            );
+            assert_eq!(
+                decode_event(&log).unwrap().event,
+                BridgeEvent::CancelNft {
+                    token: "nft.testnet".to_string(),
+                    sender: "alice.testnet".to_string(),
+                    token_id: "token-1".to_string(),
+                    nonce: 3,
+                }
+            );
+
+            let log = format!(
+                "CALIMERO_EVENT_QUEUE_CANCELLED_NFT:4:unlock:nft.testnet:alice.testnet:{}:testnet:calimero-1",
+                utils::to_base64("token-1")
+            );
+            assert_eq!(
+                decode_event(&log).unwrap().event,
+                BridgeEvent::QueueCancelledNft {
+                    id: 4,
+                    action: "unlock".to_string(),
+                    token: "nft.testnet".to_string(),
+                    receiver: "alice.testnet".to_string(),
+                    token_id: "token-1".to_string(),
+                }
+            );
+
+            let log = format!(
+                "CALIMERO_EVENT_CROSS_CALL:game.calimero:start:{}:20000000000000:0:alice.testnet:on_start:testnet:calimero-1",
+                utils::to_base64(json!({ "players": 2 }).to_string())
+            );
+            match decode_event(&log).unwrap().event {
+                BridgeEvent::CrossCall { args, gas, .. } => {
+                    assert_eq!(args, json!({ "players": 2 }));
+                    assert_eq!(gas, 20_000_000_000_000);
+                }
+                event => panic!("unexpected event {:?}", event),
+            }
+
+            let log = "CALIMERO_EVENT_CROSS_RESPONSE:game.calimero:start:FAILED!:game.testnet:calimero-1:testnet";
+            match decode_event(log).unwrap().event {
+                BridgeEvent::CrossResponse { result, .. } => assert_eq!(result, None),
+                event => panic!("unexpected event {:?}", event),
+            }
+        }
+
+        #[test]
+        fn rejects_what_connectors_reject() {
+            assert!(matches!(
+                decode_event("CALIMERO_EVENT_LOCK_FT:wrap.testnet:alice.testnet:1000"),
+                Err(CliError::FieldCount {
+                    expected: 9,
+                    found: 4,
+                    ..
+                })
+            ));
+            assert!(matches!(
+                decode_event(
+                    "CALIMERO_EVENT_BURN_FT:wrap.testnet:alice.testnet:a lot:calimero-1:testnet"
+                ),
+                Err(CliError::InvalidField {
+                    field: "amount",
+                    ..
+                })
+            ));
+            assert!(matches!(
+                decode_event("RecordProof:00"),
+                Err(CliError::UnknownEvent(_))
+            ));
+        }
+
+        #[test]
+        fn verifies_proofs_in_every_format() {
+            let (proof, merkle_root) = lock_proof();
+            let inputs = [
+                serde_json::to_string(&proof).unwrap(),
+                json!(utils::to_base64(proof.try_to_vec().unwrap())).to_string(),
+                json!({ "proof": proof, "height": 1004 }).to_string(),
+                serde_json::to_string(&LightClientExecutionProofView::from(proof.clone())).unwrap(),
+            ];
+            for input in inputs {
+                assert_eq!(parse_proof(&input).unwrap(), proof);
+            }
+
+            let verified = verify_proof(&proof, merkle_root).unwrap();
+            assert_eq!(verified.executor_id, "ft_connector.testnet");
+            assert_eq!(verified.block_height, 1000);
+            assert_eq!(
+                verified.outcome_id_hex,
+                encode_hex(&proof.outcome_proof.outcome_with_id.id)
+            );
+            assert!(matches!(
+                verified.event,
+                Some(ChainEvent {
+                    event: BridgeEvent::LockFt { .. },
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn rejects_proofs_of_other_blocks() {
+            let (proof, merkle_root) = lock_proof();
+            let mut other_root = merkle_root;
+            other_root[0] ^= 1;
+            assert!(matches!(
+                verify_proof(&proof, other_root),
+                Err(CliError::InvalidProof(_))
+            ));
+            assert!(matches!(
+                parse_proof("{\"block_proof\": []}"),
+                Err(CliError::ProofFormat)
+            ));
+        }
+
+        #[test]
+        fn parses_hex_and_base58_hashes() {
+            let (_, merkle_root) = lock_proof();
+            assert_eq!(parse_hash(&encode_hex(&merkle_root)).unwrap(), merkle_root);
+            assert_eq!(
+                parse_hash(&utils::to_base(merkle_root)).unwrap(),
+                merkle_root
+            );
+            assert!(matches!(
+                parse_hash("not a hash"),
+                Err(CliError::InvalidHash(_))
+            ));
+        }
+
+        #[test]
+        fn pause_masks() {
+            assert_eq!(pause_mask("ft-connector", &["mint", "lock"]).unwrap(), 6);
+            assert_eq!(pause_mask("nft-connector", &["all"]).unwrap(), 15);
+            assert_eq!(pause_mask::<&str>("xsc-connector", &[]).unwrap(), 0);
+            assert_eq!(pause_mask("bridge-token", &["withdraw"]).unwrap(), 1);
+            assert_eq!(
+                paused_flags("ft-connector", 6 | 1 << 10).unwrap(),
+                vec!["mint", "lock", "1024"]
+            );
+            assert!(matches!(
+                pause_mask("ft-connector", &["withdraw"]),
+                Err(CliError::UnknownPauseFlag { .. })
+            ));
+            assert!(matches!(
+                pause_mask("prover", &["all"]),
+                Err(CliError::UnknownContract { .. })
+            ));
+        }
+
+        #[test]
+        fn summarizes_light_client_storage() {
+            let mut chain = ChainGenerator::new("near", 1000, 10);
+            chain.produce_blocks(20);
+
+            let mut context = VMContextBuilder::new();
+            context
+                .current_account_id(accounts(0))
+                .predecessor_account_id(accounts(0))
+                .signer_account_id(accounts(1));
+            testing_env!(context.build());
+            let mut light_client = LightClient::new(Some(2));
+            light_client.init_with_validators(chain.validators(0));
+            light_client.init_with_block(chain.light_client_block(1002));
+            for height in [1005, 1011, 1013] {
+                light_client.add_light_client_block(chain.light_client_block(height));
+            }
+            light_client.set_paused(PAUSE_ADD_BLOCK_HEADER);
+            env::state_write(&light_client);
+
+            let storage = near_sdk::mock::with_mocked_blockchain(|b| b.take_storage());
+            let values: Vec<_> = storage
+                .iter()
+                .map(|(key, value)| {
+                    json!({ "key": utils::to_base64(key), "value": utils::to_base64(value) })
+                })
+                .collect();
+            let dump = json!({
+                "jsonrpc": "2.0",
+                "id": "dontcare",
+                "result": { "values": values, "block_height": 1, "block_hash": "" },
+            });
+            let summary =
+                light_client_summary(&parse_state_dump(&dump.to_string()).unwrap()).unwrap();
+
+            assert!(summary.initialized);
+            assert_eq!(summary.current_height, 1013);
+            assert_eq!(summary.head_hash, chain.block_hash(1013));
+            assert_eq!(summary.head_merkle_root, chain.block_merkle_root(1013));
+            assert_eq!(summary.last_submitter, accounts(0).to_string());
+            assert_eq!(summary.known_heights, vec![1013, 1011]);
+            assert_eq!(summary.blocks_to_keep, 2);
+            assert_eq!(summary.paused, vec!["add_block_header"]);
+            assert_eq!(summary.epochs.len(), 3);
+            assert_eq!(summary.epochs[0].block_producers, 4);
+            assert!(summary
+                .epochs
+                .iter()
+                .any(|epoch| epoch.epoch_id == chain.epoch_id(1)));
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use relayer::errors::RelayerError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error(transparent)]
+    Rpc(#[from] RelayerError),
+    #[error("light client {account_id} does not know its head {height}")]
+    LightClientHead { account_id: String, height: u64 },
+    #[error("failed to access the state file: {0}")]
+    State(#[from] std::io::Error),
+}
@@ -0,0 +1,69 @@
+//! Connector events and the connector methods consuming them on the other side.
+
+use near_sdk::serde_json::Value;
+use types::{ExecutionOutcome, ExecutionStatus};
+
+/// Connector method called with the proof of each event, the event is the first log of the
+/// outcome followed by `:` and its payload.
+pub const ROUTES: &[(&str, &str)] = &[
+    ("CALIMERO_EVENT_DEPLOY_FT", "register_on_other"),
+    ("CALIMERO_EVENT_LOCK_FT", "mint"),
+    ("CALIMERO_EVENT_BURN_FT", "unlock"),
+    ("CALIMERO_EVENT_MINTED_FT", "acknowledge"),
+    ("CALIMERO_EVENT_MINT_FAILED_FT", "refund"),
+    ("CALIMERO_EVENT_CANCEL_FT", "cancel_mint"),
+    ("CALIMERO_EVENT_DEPLOY_NFT", "register_on_other"),
+    ("CALIMERO_EVENT_LOCK_NFT", "mint"),
+    ("CALIMERO_EVENT_BURN_NFT", "unlock"),
+    ("CALIMERO_EVENT_MINTED_NFT", "acknowledge"),
+    ("CALIMERO_EVENT_MINT_FAILED_NFT", "refund"),
+    ("CALIMERO_EVENT_CANCEL_NFT", "cancel_mint"),
+    ("CALIMERO_EVENT_CROSS_CALL", "cross_call_execute"),
+    (
+        "CALIMERO_EVENT_CROSS_RESPONSE",
+        "cross_call_receive_response",
+    ),
+];
+
+/// Logged by connectors once an event is consumed, followed by the hex encoded outcome id.
+pub const RECORD_PROOF: &str = "RecordProof:";
+
+/// Event name and connector method for an outcome, `None` if it failed or emitted no event.
+pub fn route(outcome: &ExecutionOutcome) -> Option<(&'static str, &'static str)> {
+    if !is_success(&outcome.status) {
+        return None;
+    }
+    let log = outcome.logs.first()?;
+    ROUTES
+        .iter()
+        .find(|(event, _)| log.starts_with(format!("{}:", event).as_bytes()))
+        .copied()
+}
+
+/// Outcome ids recorded by the successful receipts of a transaction, hex encoded as in the
+/// `RecordProof:` log. `outcome` is a `broadcast_tx_commit` result.
+pub fn recorded_proofs(outcome: &Value) -> Vec<String> {
+    let receipts = match outcome["receipts_outcome"].as_array() {
+        Some(receipts) => receipts,
+        None => return vec![],
+    };
+    receipts
+        .iter()
+        .map(|receipt| &receipt["outcome"])
+        .filter(|outcome| {
+            let status = &outcome["status"];
+            status.get("SuccessValue").is_some() || status.get("SuccessReceiptId").is_some()
+        })
+        .filter_map(|outcome| outcome["logs"].as_array())
+        .flatten()
+        .filter_map(|log| log.as_str()?.strip_prefix(RECORD_PROOF))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_success(status: &ExecutionStatus) -> bool {
+    matches!(
+        status,
+        ExecutionStatus::SuccessValue(_) | ExecutionStatus::SuccessReceiptId(_)
+    )
+}
@@ -0,0 +1,392 @@
+//! Reference service relaying connector events from one chain to the connectors of another.
+//!
+//! Blocks of the source chain are indexed for receipts executed by the watched connectors.
+//! Once the light client on the destination has passed the block of a receipt, the outcome
+//! proof is fetched from the source, checked against the light client and, if the outcome
+//! emitted a `CALIMERO_EVENT_*`, submitted to the matching connector method. Events are
+//! marked as done by the `RecordProof:` log of the destination connector, so neither a
+//! restart nor another service relaying the same events leads to paying twice.
+
+pub mod errors;
+pub mod events;
+pub mod state;
+
+use crate::errors::ServiceError;
+use crate::state::{FailedEvent, PendingReceipt, ServiceState};
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::{Balance, ONE_NEAR};
+use relayer::errors::RelayerError;
+use relayer::retry::RetryPolicy;
+use relayer::rpc::RpcClient;
+use relayer::{Signer, DEFAULT_GAS};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use types::{BridgeError, EncodedOutcomeProof};
+use utils::{hashes, Hash};
+
+/// Deposit attached to connector calls, it covers recording the proof and the rest is
+/// refunded.
+pub const DEFAULT_DEPOSIT: Balance = ONE_NEAR / 10;
+
+/// Source blocks indexed per step, so events of the first blocks are relayed without waiting
+/// for a long backlog to be indexed.
+pub const DEFAULT_BLOCKS_PER_STEP: u64 = 100;
+
+/// Connector on the source chain and its counterpart on the destination chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectorPair {
+    pub source: String,
+    pub destination: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayResult {
+    /// The destination connector recorded the event with this submission.
+    Recorded,
+    /// The event was recorded before, by this service or another one.
+    AlreadyRecorded,
+    /// The destination connector rejected the event, it is moved to `ServiceState::failed`.
+    Failed(String),
+}
+
+/// Event handled in a step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayedEvent {
+    pub outcome_id: Hash,
+    pub event: &'static str,
+    pub receiver_id: String,
+    pub method: &'static str,
+    pub result: RelayResult,
+}
+
+enum Progress {
+    /// The proof is not available yet or the submission has to be repeated.
+    Waiting,
+    /// The receipt emitted no event.
+    Discarded,
+    Relayed(RelayedEvent),
+}
+
+struct LightClientHead {
+    height: u64,
+    hash: Hash,
+    merkle_root: Hash,
+}
+
+pub struct EventService {
+    source: RpcClient,
+    destination: RpcClient,
+    /// Light client on the destination following the source chain.
+    light_client_account_id: String,
+    connectors: Vec<ConnectorPair>,
+    signer: Signer,
+    state: ServiceState,
+    state_file: Option<PathBuf>,
+    retry: RetryPolicy,
+    gas: u64,
+    deposit: Balance,
+    blocks_per_step: u64,
+}
+
+impl EventService {
+    pub fn new(
+        source: RpcClient,
+        destination: RpcClient,
+        light_client_account_id: &str,
+        connectors: Vec<ConnectorPair>,
+        signer: Signer,
+        state: ServiceState,
+    ) -> Self {
+        Self {
+            source,
+            destination,
+            light_client_account_id: light_client_account_id.to_string(),
+            connectors,
+            signer,
+            state,
+            state_file: None,
+            retry: RetryPolicy::default(),
+            gas: DEFAULT_GAS,
+            deposit: DEFAULT_DEPOSIT,
+            blocks_per_step: DEFAULT_BLOCKS_PER_STEP,
+        }
+    }
+
+    /// Saves the state to `path` after every change.
+    pub fn with_state_file(mut self, path: PathBuf) -> Self {
+        self.state_file = Some(path);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_gas(mut self, gas: u64) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    pub fn with_deposit(mut self, deposit: Balance) -> Self {
+        self.deposit = deposit;
+        self
+    }
+
+    pub fn with_blocks_per_step(mut self, blocks_per_step: u64) -> Self {
+        assert!(
+            blocks_per_step > 0,
+            "At least one block has to be indexed per step"
+        );
+        self.blocks_per_step = blocks_per_step;
+        self
+    }
+
+    pub fn state(&self) -> &ServiceState {
+        &self.state
+    }
+
+    /// Indexes the next source blocks, then relays the pending events the light client on
+    /// the destination can verify. Returns the events handled.
+    pub fn step(&mut self) -> Result<Vec<RelayedEvent>, ServiceError> {
+        self.index()?;
+        self.relay_pending()
+    }
+
+    /// Relays events forever, polls the source every `poll_interval`.
+    pub fn run(&mut self, poll_interval: Duration) -> ! {
+        loop {
+            if let Err(err) = self.step() {
+                log::error!("{}", err);
+            }
+            thread::sleep(poll_interval);
+        }
+    }
+
+    fn index(&mut self) -> Result<(), ServiceError> {
+        let final_height = self
+            .retry
+            .run("block", || self.source.final_block())?
+            .header
+            .height;
+        let first = self.state.next_height;
+        let last = final_height.min(first + self.blocks_per_step - 1);
+        if first > last {
+            return Ok(());
+        }
+
+        for height in first..=last {
+            let block = match self.retry.run("block", || self.source.block(height))? {
+                Some(block) => block,
+                None => continue,
+            };
+            // older chunks are repeated in blocks missing the chunk of their shard
+            for header in block
+                .chunks
+                .iter()
+                .filter(|header| header.height_included == height)
+            {
+                let chunk = self
+                    .retry
+                    .run("chunk", || self.source.chunk(height, header.shard_id))?;
+                for receipt in chunk.receipts {
+                    let watched = self
+                        .connectors
+                        .iter()
+                        .any(|connector| connector.source == receipt.receiver_id);
+                    let known = self
+                        .state
+                        .pending
+                        .iter()
+                        .any(|pending| pending.receipt_id == receipt.receipt_id);
+                    if watched && !known {
+                        log::info!(
+                            "found receipt {} to {} at {}",
+                            utils::to_base(receipt.receipt_id),
+                            receipt.receiver_id,
+                            height
+                        );
+                        self.state.pending.push(PendingReceipt {
+                            receipt_id: receipt.receipt_id,
+                            receiver_id: receipt.receiver_id,
+                            height,
+                        });
+                    }
+                }
+            }
+        }
+        self.state.next_height = last + 1;
+        self.save()
+    }
+
+    fn relay_pending(&mut self) -> Result<Vec<RelayedEvent>, ServiceError> {
+        if self.state.pending.is_empty() {
+            return Ok(vec![]);
+        }
+        let head = self.light_client_head()?;
+        let mut relayed = vec![];
+        for receipt in self.state.pending.clone() {
+            // the proof is checked against a block after the one of the outcome
+            if receipt.height >= head.height {
+                continue;
+            }
+            match self.relay(&receipt, &head)? {
+                Progress::Waiting => continue,
+                Progress::Discarded => {}
+                Progress::Relayed(event) => relayed.push(event),
+            }
+            self.state.pending.retain(|pending| pending != &receipt);
+            self.save()?;
+        }
+        Ok(relayed)
+    }
+
+    fn relay(
+        &mut self,
+        receipt: &PendingReceipt,
+        head: &LightClientHead,
+    ) -> Result<Progress, ServiceError> {
+        let receipt_id = utils::to_base(receipt.receipt_id);
+        let proof = match self.source.light_client_proof(
+            &receipt.receipt_id,
+            &receipt.receiver_id,
+            &head.hash,
+        ) {
+            Ok(proof) => proof,
+            Err(err) => {
+                // the outcome may not be included before the head yet
+                log::debug!("no proof of receipt {} yet: {}", receipt_id, err);
+                return Ok(Progress::Waiting);
+            }
+        };
+        if let Err(err) = rpc_types::validate_outcome_proof(&proof, Some(head.merkle_root)) {
+            log::warn!("proof of receipt {} is not valid: {}", receipt_id, err);
+            return Ok(Progress::Waiting);
+        }
+
+        let outcome = &proof.outcome_proof.outcome_with_id;
+        let (event, method) = match events::route(&outcome.outcome) {
+            Some(route) => route,
+            None => return Ok(Progress::Discarded),
+        };
+        let receiver_id = match self
+            .connectors
+            .iter()
+            .find(|connector| connector.source == outcome.outcome.executor_id)
+        {
+            Some(connector) => connector.destination.clone(),
+            None => return Ok(Progress::Discarded),
+        };
+        let outcome_id = hashes::encode_hex(&outcome.id);
+        let mut relayed = RelayedEvent {
+            outcome_id: outcome.id,
+            event,
+            receiver_id,
+            method,
+            result: RelayResult::AlreadyRecorded,
+        };
+        if self.state.recorded.contains(&outcome_id) {
+            return Ok(Progress::Relayed(relayed));
+        }
+
+        let args = serde_json::to_vec(&json!({
+            "proof": EncodedOutcomeProof::borsh(&proof),
+            "height": head.height,
+        }))
+        .unwrap();
+        let transaction = self.retry.run("access_key", || {
+            self.signer.function_call(
+                &self.destination,
+                &relayed.receiver_id,
+                method,
+                args.clone(),
+                self.gas,
+                self.deposit,
+            )
+        })?;
+        let result = match self.destination.send_transaction(&transaction) {
+            Ok(result) => result,
+            Err(err) if err.is_transient() => {
+                // the transaction may still have been executed, in which case the next
+                // submission is rejected as a reused event
+                log::warn!("submitting {} of {} failed: {}", event, receipt_id, err);
+                return Ok(Progress::Waiting);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let failure = result["status"]
+            .get("Failure")
+            .map(|failure| failure.to_string());
+        relayed.result = if events::recorded_proofs(&result).contains(&outcome_id) {
+            if let Some(failure) = &failure {
+                // e.g. the transfer after recording failed, the event is consumed anyway
+                log::warn!(
+                    "{} of {} failed after recording: {}",
+                    event,
+                    receipt_id,
+                    failure
+                );
+            }
+            RelayResult::Recorded
+        } else if failure.as_deref().and_then(BridgeError::from_failure)
+            == Some(BridgeError::EventReused)
+        {
+            RelayResult::AlreadyRecorded
+        } else {
+            let error = failure.unwrap_or_else(|| "the event was not recorded".to_string());
+            log::error!("{} of {} was rejected: {}", event, receipt_id, error);
+            self.state.failed.push(FailedEvent {
+                receipt: receipt.clone(),
+                method: method.to_string(),
+                error: error.clone(),
+            });
+            return Ok(Progress::Relayed(RelayedEvent {
+                result: RelayResult::Failed(error),
+                ..relayed
+            }));
+        };
+        log::info!("{} of {}: {:?}", event, receipt_id, relayed.result);
+        self.state.recorded.insert(outcome_id);
+        Ok(Progress::Relayed(relayed))
+    }
+
+    fn light_client_head(&self) -> Result<LightClientHead, ServiceError> {
+        let height: u64 = self.light_client_view("current_height", json!({}))?;
+        let hash: Option<Hash> =
+            self.light_client_view("block_hashes", json!({ "height": height }))?;
+        let merkle_root: Option<Hash> =
+            self.light_client_view("block_merkle_roots", json!({ "height": height }))?;
+        match (hash, merkle_root) {
+            (Some(hash), Some(merkle_root)) => Ok(LightClientHead {
+                height,
+                hash,
+                merkle_root,
+            }),
+            _ => Err(ServiceError::LightClientHead {
+                account_id: self.light_client_account_id.clone(),
+                height,
+            }),
+        }
+    }
+
+    fn light_client_view<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        args: Value,
+    ) -> Result<T, RelayerError> {
+        self.retry.run(method, || {
+            self.destination
+                .view_call(&self.light_client_account_id, method, args.clone())
+        })
+    }
+
+    fn save(&self) -> Result<(), ServiceError> {
+        if let Some(path) = &self.state_file {
+            self.state.save(path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use bridge_service::state::ServiceState;
+use bridge_service::{ConnectorPair, EventService, DEFAULT_BLOCKS_PER_STEP, DEFAULT_DEPOSIT};
+use clap::Parser;
+use relayer::retry::RetryPolicy;
+use relayer::rpc::RpcClient;
+use relayer::{Signer, DEFAULT_GAS};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Relays connector events of one chain to the connectors of another chain.
+#[derive(Parser)]
+struct Args {
+    /// JSON-RPC endpoint of the chain the events are emitted on
+    #[clap(long)]
+    source_rpc: String,
+    /// JSON-RPC endpoint of the chain the events are relayed to
+    #[clap(long)]
+    destination_rpc: String,
+    /// Account of the light client following the source chain on the destination chain
+    #[clap(long)]
+    light_client: String,
+    /// Connector on the source chain and its counterpart on the destination chain, as
+    /// `source=destination`. Repeat for every connector type.
+    #[clap(long = "connector", required = true, parse(try_from_str = parse_connector_pair))]
+    connectors: Vec<ConnectorPair>,
+    /// Credentials file of the submitting account, as written by near-cli
+    #[clap(long)]
+    key_file: PathBuf,
+    /// Progress of the service, created when missing
+    #[clap(long)]
+    state_file: PathBuf,
+    /// Source height to start indexing at when the state file is missing, the latest final
+    /// block by default
+    #[clap(long)]
+    start_height: Option<u64>,
+    #[clap(long, default_value = "1000")]
+    poll_interval_ms: u64,
+    #[clap(long, default_value = "10000")]
+    rpc_timeout_ms: u64,
+    #[clap(long, default_value = "5")]
+    max_attempts: u32,
+    #[clap(long, default_value_t = DEFAULT_GAS)]
+    gas: u64,
+    /// Deposit attached to connector calls, in yoctoNEAR
+    #[clap(long, default_value_t = DEFAULT_DEPOSIT)]
+    deposit: u128,
+    #[clap(long, default_value_t = DEFAULT_BLOCKS_PER_STEP)]
+    blocks_per_step: u64,
+}
+
+fn parse_connector_pair(pair: &str) -> Result<ConnectorPair, String> {
+    match pair.split_once('=') {
+        Some((source, destination)) if !source.is_empty() && !destination.is_empty() => {
+            Ok(ConnectorPair {
+                source: source.to_string(),
+                destination: destination.to_string(),
+            })
+        }
+        _ => Err(format!("expected source=destination, got {}", pair)),
+    }
+}
+
+fn load_state(args: &Args, source: &RpcClient) -> Result<ServiceState, Box<dyn Error>> {
+    if args.state_file.exists() {
+        return Ok(ServiceState::load(&args.state_file)?);
+    }
+    let start_height = match args.start_height {
+        Some(height) => height,
+        None => source.final_block()?.header.height,
+    };
+    let state = ServiceState::new(start_height);
+    state.save(&args.state_file)?;
+    Ok(state)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let timeout = Duration::from_millis(args.rpc_timeout_ms);
+    let source = RpcClient::new(&args.source_rpc, timeout);
+    let signer = Signer::load(&args.key_file)?;
+    let state = load_state(&args, &source)?;
+    log::info!(
+        "relaying events from {} to {} starting at block {}",
+        args.source_rpc,
+        args.destination_rpc,
+        state.next_height
+    );
+
+    let mut service = EventService::new(
+        source,
+        RpcClient::new(&args.destination_rpc, timeout),
+        &args.light_client,
+        args.connectors.clone(),
+        signer,
+        state,
+    )
+    .with_state_file(args.state_file.clone())
+    .with_retry(RetryPolicy {
+        max_attempts: args.max_attempts,
+        ..Default::default()
+    })
+    .with_gas(args.gas)
+    .with_deposit(args.deposit)
+    .with_blocks_per_step(args.blocks_per_step);
+    service.run(Duration::from_millis(args.poll_interval_ms))
+}
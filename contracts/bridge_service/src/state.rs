@@ -0,0 +1,62 @@
+//! Progress of the service, saved after every change so a restart neither misses nor
+//! resubmits events.
+
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use utils::{base_hash_format, Hash};
+
+/// Receipt executed by a source connector, possibly emitting an event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingReceipt {
+    #[serde(with = "base_hash_format")]
+    pub receipt_id: Hash,
+    pub receiver_id: String,
+    /// Height of the chunk carrying the receipt, its outcome can not be included earlier.
+    pub height: u64,
+}
+
+/// Event the destination connector rejected, kept for the operator instead of being retried.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FailedEvent {
+    pub receipt: PendingReceipt,
+    pub method: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ServiceState {
+    /// Next height of the source chain to index.
+    pub next_height: u64,
+    pub pending: Vec<PendingReceipt>,
+    /// Outcome ids the destination connectors recorded, hex encoded as in `RecordProof:` logs.
+    pub recorded: BTreeSet<String>,
+    pub failed: Vec<FailedEvent>,
+}
+
+impl ServiceState {
+    pub fn new(start_height: u64) -> Self {
+        Self {
+            next_height: start_height,
+            pending: vec![],
+            recorded: BTreeSet::new(),
+            failed: vec![],
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
+        // written aside first, so a crash never leaves a truncated state behind
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, path)
+    }
+}
@@ -0,0 +1,521 @@
+use bridge_service::state::ServiceState;
+use bridge_service::{ConnectorPair, EventService, RelayResult, DEFAULT_DEPOSIT};
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::{AccountId, ONE_NEAR, ONE_YOCTO};
+use relayer::retry::RetryPolicy;
+use relayer::rpc::RpcClient;
+use relayer::transaction::SignedTransaction;
+use relayer::{Signer, DEFAULT_GAS};
+use simulator::bridge::ACCOUNT_BALANCE;
+use simulator::contracts::bridge_token_code;
+use simulator::{BridgeSimulator, Runtime};
+use std::path::PathBuf;
+use std::time::Duration;
+use test_utils::chain::secret_key_from_seed;
+use test_utils::rpc::{MockResponse, MockRpcServer};
+use types::{Action, ExecutionStatus};
+use utils::hashes;
+
+/// Bridge simulator answering requests for NEAR under `/near` and for Calimero under
+/// `/calimero`.
+struct Node {
+    sim: BridgeSimulator,
+    /// Transactions still executed, but answered with a gateway timeout.
+    lost_responses: u32,
+    nonce: u64,
+}
+
+fn account(account_id: &str) -> AccountId {
+    account_id.parse().unwrap()
+}
+
+fn unknown_block() -> MockResponse {
+    MockResponse::Error(json!({ "name": "HANDLER_ERROR", "cause": { "name": "UNKNOWN_BLOCK" } }))
+}
+
+fn serve(node: &mut Node, path: &str, method: &str, params: &Value) -> MockResponse {
+    let runtime = match path {
+        "/near" => &mut node.sim.near,
+        "/calimero" => &mut node.sim.calimero,
+        _ => return MockResponse::Status(404),
+    };
+    match (method, params["request_type"].as_str()) {
+        ("block", _) => {
+            let chain = runtime.chain();
+            let height = params["block_id"]
+                .as_u64()
+                .unwrap_or_else(|| chain.head_height());
+            if height < chain.genesis_height() || height > chain.head_height() {
+                return unknown_block();
+            }
+            MockResponse::Result(json!({
+                "header": { "height": height, "hash": utils::to_base(chain.block_hash(height)) },
+                "chunks": [{
+                    "chunk_hash": utils::to_base(hashes::sha256(&height.to_le_bytes())),
+                    "shard_id": 0,
+                    "height_included": height,
+                }],
+            }))
+        }
+        ("chunk", _) => {
+            let height = params["block_id"].as_u64().unwrap();
+            let receipts: Vec<Value> = runtime
+                .included_outcomes()
+                .iter()
+                .filter(|(included, _)| *included == height)
+                .map(|(_, outcome)| {
+                    json!({
+                        "receipt_id": utils::to_base(outcome.id),
+                        "predecessor_id": "system",
+                        "receiver_id": outcome.outcome.executor_id,
+                    })
+                })
+                .collect();
+            MockResponse::Result(json!({ "receipts": receipts, "transactions": [] }))
+        }
+        ("EXPERIMENTAL_light_client_proof", _) => light_client_proof(runtime, params),
+        ("query", Some("call_function")) => {
+            let args = utils::from_base64(params["args_base64"].as_str().unwrap()).unwrap();
+            let result: Value = runtime.view(
+                &account(params["account_id"].as_str().unwrap()),
+                params["method_name"].as_str().unwrap(),
+                serde_json::from_slice(&args).unwrap(),
+            );
+            MockResponse::Result(
+                json!({ "result": serde_json::to_vec(&result).unwrap(), "logs": [] }),
+            )
+        }
+        ("query", Some("view_access_key")) => {
+            let head = runtime.chain().head_height();
+            MockResponse::Result(json!({
+                "nonce": node.nonce,
+                "block_hash": utils::to_base(runtime.chain().block_hash(head)),
+            }))
+        }
+        ("broadcast_tx_commit", _) => {
+            let transaction = utils::from_base64(params[0].as_str().unwrap()).unwrap();
+            let transaction = SignedTransaction::try_from_slice(&transaction).unwrap();
+            assert!(transaction.verify());
+            node.nonce = transaction.transaction.nonce;
+            let outcome = execute(runtime, &transaction);
+            if node.lost_responses > 0 {
+                node.lost_responses -= 1;
+                return MockResponse::Status(504);
+            }
+            MockResponse::Result(outcome)
+        }
+        _ => panic!("Unexpected request {} {}", method, params),
+    }
+}
+
+fn light_client_proof(runtime: &Runtime, params: &Value) -> MockResponse {
+    let chain = runtime.chain();
+    let head_hash = params["light_client_head"].as_str().unwrap();
+    let head = (chain.genesis_height()..=chain.head_height())
+        .find(|height| utils::to_base(chain.block_hash(*height)) == head_hash);
+    let receipt_id = params["receipt_id"].as_str().unwrap();
+    let included = runtime
+        .included_outcomes()
+        .iter()
+        .find(|(_, outcome)| utils::to_base(outcome.id) == receipt_id);
+    match (head, included) {
+        (Some(head), Some((height, outcome))) if *height < head => {
+            let proof = chain.outcome_proof(&outcome.id, head);
+            let view = rpc_types::views::LightClientExecutionProofView::from(proof);
+            MockResponse::Result(serde_json::to_value(&view).unwrap())
+        }
+        _ => MockResponse::Error(json!({
+            "name": "HANDLER_ERROR",
+            "cause": { "name": "UNKNOWN_TRANSACTION_OR_RECEIPT" },
+        })),
+    }
+}
+
+/// Executes the function call of `transaction` and answers as `broadcast_tx_commit` does.
+fn execute(runtime: &mut Runtime, transaction: &SignedTransaction) -> Value {
+    let call = match &transaction.transaction.actions[..] {
+        [Action::FunctionCall(call)] => call,
+        actions => panic!("Unexpected actions {:?}", actions),
+    };
+    let result = runtime.call_raw(
+        &account(&transaction.transaction.signer_id),
+        &account(&transaction.transaction.receiver_id),
+        &call.method_name,
+        call.args.clone(),
+        call.deposit,
+    );
+    runtime.produce_block();
+
+    let status = match &result.result {
+        Ok(value) => json!({ "SuccessValue": utils::to_base64(value) }),
+        Err(message) => json!({ "Failure": { "ActionError": {
+            "index": 0,
+            "kind": { "FunctionCallError": { "ExecutionError": message } },
+        } } }),
+    };
+    let receipts_outcome: Vec<Value> = result
+        .outcomes
+        .iter()
+        .map(|outcome| {
+            let logs: Vec<String> = outcome
+                .outcome
+                .logs
+                .iter()
+                .map(|log| String::from_utf8_lossy(log).into_owned())
+                .collect();
+            json!({
+                "id": utils::to_base(outcome.id),
+                "outcome": {
+                    "logs": logs,
+                    "executor_id": outcome.outcome.executor_id,
+                    "status": outcome.outcome.status,
+                },
+            })
+        })
+        .collect();
+    json!({ "status": status, "receipts_outcome": receipts_outcome })
+}
+
+/// Deploys `wrap.near` holding 1000 tokens of alice and bridges it to Calimero, as in the
+/// simulator round trip tests.
+fn setup() -> Node {
+    let mut sim = BridgeSimulator::new();
+    let wrap = account("wrap.near");
+    let alice = account("alice.near");
+    sim.near.create_account(&wrap, ACCOUNT_BALANCE);
+    sim.near.deploy(&wrap, bridge_token_code);
+    sim.near.create_account(&alice, ACCOUNT_BALANCE);
+    sim.calimero.create_account(&alice, ACCOUNT_BALANCE);
+    sim.near
+        .call(&wrap, &wrap, "new", json!({ "controller": wrap }), 0)
+        .unwrap();
+    sim.near
+        .call(
+            &wrap,
+            &wrap,
+            "mint",
+            json!({ "account_id": alice, "amount": "1000" }),
+            ONE_NEAR,
+        )
+        .unwrap();
+    let connector = sim.near_accounts.ft_connector.clone();
+    sim.near
+        .call(
+            &alice,
+            &wrap,
+            "storage_deposit",
+            json!({ "account_id": connector }),
+            ONE_NEAR,
+        )
+        .unwrap();
+    sim.near
+        .call(&alice, &connector, "deposit_fees", json!({}), ONE_NEAR)
+        .unwrap();
+    sim.deploy_bridge_token(&wrap);
+    Node {
+        sim,
+        lost_responses: 0,
+        nonce: 0,
+    }
+}
+
+fn start_node() -> MockRpcServer<Node> {
+    MockRpcServer::start_with_state(setup, serve)
+}
+
+/// Locks `amount` of alice's tokens on NEAR and returns the first height to index.
+fn lock(server: &MockRpcServer<Node>, amount: u128) -> u64 {
+    server.with_state(move |node| {
+        let start_height = node.sim.near.block_height();
+        let connector = node.sim.near_accounts.ft_connector.clone();
+        node.sim
+            .near
+            .call(
+                &account("alice.near"),
+                &account("wrap.near"),
+                "ft_transfer_call",
+                json!({ "receiver_id": connector, "amount": amount.to_string(), "msg": "" }),
+                ONE_YOCTO,
+            )
+            .unwrap();
+        node.sim.near.produce_block();
+        start_height
+    })
+}
+
+fn relay_headers(server: &MockRpcServer<Node>) {
+    server.with_state(|node| node.sim.relay_headers());
+}
+
+fn calimero_balance(server: &MockRpcServer<Node>) -> u128 {
+    server.with_state(|node| {
+        let balance: U128 = node.sim.calimero.view(
+            &account("wrap.ft_deployer.calimero"),
+            "ft_balance_of",
+            json!({ "account_id": "alice.near" }),
+        );
+        balance.0
+    })
+}
+
+fn fast_retry() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+    }
+}
+
+/// Service relaying events from NEAR to Calimero.
+fn near_to_calimero(server: &MockRpcServer<Node>, state: ServiceState) -> EventService {
+    let timeout = Duration::from_secs(10);
+    EventService::new(
+        RpcClient::new(&format!("{}/near", server.url()), timeout),
+        RpcClient::new(&format!("{}/calimero", server.url()), timeout),
+        "light_client.calimero",
+        vec![
+            ConnectorPair {
+                source: "ft_connector.near".to_string(),
+                destination: "ft_connector.calimero".to_string(),
+            },
+            ConnectorPair {
+                source: "xsc_connector.near".to_string(),
+                destination: "xsc_connector.calimero".to_string(),
+            },
+        ],
+        Signer {
+            account_id: "relayer.calimero".to_string(),
+            secret_key: secret_key_from_seed("relayer.calimero"),
+        },
+        state,
+    )
+    .with_retry(fast_retry())
+}
+
+fn submitted(server: &MockRpcServer<Node>) -> Vec<SignedTransaction> {
+    server
+        .requests_for("broadcast_tx_commit")
+        .iter()
+        .map(|params| {
+            let transaction = utils::from_base64(params[0].as_str().unwrap()).unwrap();
+            SignedTransaction::try_from_slice(&transaction).unwrap()
+        })
+        .collect()
+}
+
+fn temp_state_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "bridge_service_{}_{}.json",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn relays_lock_once_light_client_follows() {
+    let server = start_node();
+    let start_height = lock(&server, 400);
+    let mut service = near_to_calimero(&server, ServiceState::new(start_height));
+
+    // the light client on Calimero has not seen the block of the lock yet
+    assert!(service.step().unwrap().is_empty());
+    assert!(!service.state().pending.is_empty());
+    assert!(submitted(&server).is_empty());
+
+    relay_headers(&server);
+    let relayed = service.step().unwrap();
+    assert_eq!(relayed.len(), 1);
+    assert_eq!(relayed[0].event, "CALIMERO_EVENT_LOCK_FT");
+    assert_eq!(relayed[0].method, "mint");
+    assert_eq!(relayed[0].receiver_id, "ft_connector.calimero");
+    assert_eq!(relayed[0].result, RelayResult::Recorded);
+    assert_eq!(calimero_balance(&server), 400);
+    assert!(service.state().pending.is_empty());
+    assert!(service
+        .state()
+        .recorded
+        .contains(&hashes::encode_hex(&relayed[0].outcome_id)));
+
+    let transactions = submitted(&server);
+    assert_eq!(transactions.len(), 1);
+    let transaction = &transactions[0].transaction;
+    assert_eq!(transaction.signer_id, "relayer.calimero");
+    assert_eq!(transaction.receiver_id, "ft_connector.calimero");
+    match &transaction.actions[..] {
+        [Action::FunctionCall(call)] => {
+            assert_eq!(call.method_name, "mint");
+            assert_eq!(call.gas, DEFAULT_GAS);
+            assert_eq!(call.deposit, DEFAULT_DEPOSIT);
+        }
+        actions => panic!("Unexpected actions {:?}", actions),
+    }
+
+    assert!(service.step().unwrap().is_empty());
+    assert_eq!(submitted(&server).len(), 1);
+}
+
+#[test]
+fn restarted_service_skips_recorded_events() {
+    let server = start_node();
+    let start_height = lock(&server, 400);
+    relay_headers(&server);
+    let path = temp_state_file("restart");
+    let mut service =
+        near_to_calimero(&server, ServiceState::new(start_height)).with_state_file(path.clone());
+    assert_eq!(service.step().unwrap()[0].result, RelayResult::Recorded);
+
+    // indexing again from the start finds the event, but it is not submitted a second time
+    let mut state = ServiceState::load(&path).unwrap();
+    assert_eq!(&state, service.state());
+    state.next_height = start_height;
+    let mut restarted = near_to_calimero(&server, state);
+    let relayed = restarted.step().unwrap();
+    assert_eq!(relayed.len(), 1);
+    assert_eq!(relayed[0].result, RelayResult::AlreadyRecorded);
+    assert_eq!(submitted(&server).len(), 1);
+    assert_eq!(calimero_balance(&server), 400);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn event_relayed_by_another_service_is_recorded() {
+    let server = start_node();
+    let start_height = lock(&server, 400);
+    relay_headers(&server);
+    let mut first = near_to_calimero(&server, ServiceState::new(start_height));
+    let mut second = near_to_calimero(&server, ServiceState::new(start_height));
+    assert_eq!(first.step().unwrap()[0].result, RelayResult::Recorded);
+
+    let relayed = second.step().unwrap();
+    assert_eq!(relayed[0].result, RelayResult::AlreadyRecorded);
+    assert_eq!(second.state().recorded, first.state().recorded);
+    assert!(second.state().failed.is_empty());
+    assert_eq!(submitted(&server).len(), 2);
+    assert_eq!(calimero_balance(&server), 400);
+}
+
+#[test]
+fn lost_response_is_settled_by_the_next_step() {
+    let server = start_node();
+    let start_height = lock(&server, 400);
+    relay_headers(&server);
+    server.with_state(|node| node.lost_responses = 1);
+    let mut service = near_to_calimero(&server, ServiceState::new(start_height));
+
+    assert!(service.step().unwrap().is_empty());
+    assert_eq!(service.state().pending.len(), 1);
+    assert_eq!(calimero_balance(&server), 400);
+
+    let relayed = service.step().unwrap();
+    assert_eq!(relayed[0].result, RelayResult::AlreadyRecorded);
+    assert!(service.state().pending.is_empty());
+    assert_eq!(submitted(&server).len(), 2);
+    assert_eq!(calimero_balance(&server), 400);
+}
+
+#[test]
+fn rejected_event_is_kept_for_the_operator() {
+    let server = start_node();
+    let start_height = lock(&server, 400);
+    relay_headers(&server);
+    // recording the proof needs storage the deposit pays for
+    let mut service = near_to_calimero(&server, ServiceState::new(start_height)).with_deposit(0);
+
+    let relayed = service.step().unwrap();
+    assert!(
+        matches!(&relayed[0].result, RelayResult::Failed(error) if error.contains("Deposit too low"))
+    );
+    assert!(service.state().pending.is_empty());
+    assert!(service.state().recorded.is_empty());
+    assert_eq!(service.state().failed.len(), 1);
+    assert_eq!(service.state().failed[0].method, "mint");
+    assert_eq!(calimero_balance(&server), 0);
+
+    assert!(service.step().unwrap().is_empty());
+    assert_eq!(submitted(&server).len(), 1);
+}
+
+#[test]
+fn relays_burn_back_to_near() {
+    let server = start_node();
+    let start_height = lock(&server, 400);
+    relay_headers(&server);
+    near_to_calimero(&server, ServiceState::new(start_height))
+        .step()
+        .unwrap();
+
+    let calimero_start = server.with_state(|node| {
+        let start_height = node.sim.calimero.block_height();
+        node.sim
+            .calimero
+            .call(
+                &account("alice.near"),
+                &account("wrap.ft_deployer.calimero"),
+                "withdraw",
+                json!({ "amount": "150" }),
+                ONE_YOCTO,
+            )
+            .unwrap();
+        // the light client on NEAR has to follow several epochs of Calimero
+        for _ in 0..35 {
+            node.sim.calimero.produce_block();
+        }
+        node.sim.relay_headers();
+        start_height
+    });
+    let timeout = Duration::from_secs(10);
+    let mut service = EventService::new(
+        RpcClient::new(&format!("{}/calimero", server.url()), timeout),
+        RpcClient::new(&format!("{}/near", server.url()), timeout),
+        "light_client.near",
+        vec![ConnectorPair {
+            source: "ft_connector.calimero".to_string(),
+            destination: "ft_connector.near".to_string(),
+        }],
+        Signer {
+            account_id: "relayer.near".to_string(),
+            secret_key: secret_key_from_seed("relayer.near"),
+        },
+        ServiceState::new(calimero_start),
+    )
+    .with_retry(fast_retry());
+
+    let relayed = service.step().unwrap();
+    assert_eq!(relayed.len(), 1);
+    assert_eq!(relayed[0].event, "CALIMERO_EVENT_BURN_FT");
+    assert_eq!(relayed[0].method, "unlock");
+    assert_eq!(relayed[0].result, RelayResult::Recorded);
+    let balance: u128 = server.with_state(|node| {
+        let balance: U128 = node.sim.near.view(
+            &account("wrap.near"),
+            "ft_balance_of",
+            json!({ "account_id": "alice.near" }),
+        );
+        balance.0
+    });
+    assert_eq!(balance, 750);
+}
+
+#[test]
+fn failed_outcomes_emit_no_events() {
+    let outcome = |status| types::ExecutionOutcome {
+        logs: vec![b"CALIMERO_EVENT_LOCK_FT:payload".to_vec()],
+        receipt_ids: vec![],
+        gas_burnt: 0,
+        tokens_burnt: 0,
+        executor_id: "ft_connector.near".to_string(),
+        status,
+    };
+    assert_eq!(
+        bridge_service::events::route(&outcome(ExecutionStatus::SuccessValue(vec![]))),
+        Some(("CALIMERO_EVENT_LOCK_FT", "mint"))
+    );
+    assert_eq!(
+        bridge_service::events::route(&outcome(ExecutionStatus::Unknown)),
+        None
+    );
+}
@@ -1,11 +1,11 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{
-    env, near_bindgen, require, serde_json, AccountId, Balance, Gas, PanicOnDefault, PromiseResult,
+    env, near_bindgen, serde_json, AccountId, Balance, Gas, PanicOnDefault, PromiseResult,
 };
-use substring::Substring;
-use utils::hashes;
+use types::errors::panic_err;
+use types::{require_err, BridgeError};
 
-const BRIDGE_TOKEN_BINARY: &[u8] = include_bytes!(std::env!(
+pub const BRIDGE_TOKEN_BINARY: &[u8] = include_bytes!(std::env!(
     "BRIDGE_TOKEN",
     "Set BRIDGE_TOKEN to be the path of the bridge token binary"
 ));
@@ -19,8 +19,6 @@ const BRIDGE_TOKEN_INIT_BALANCE: Balance = 5_000_000_000_000_000_000_000_000; //
 const BRIDGE_TOKEN_NEW: Gas = Gas(50_000_000_000_000);
 const BRIDGE_TOKEN_COMPLETE: Gas = Gas(20_000_000_000_000);
 
-const MAX_ACCOUNT_ID_LENGTH: usize = 64;
-
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct BridgeTokenDeployer {
@@ -44,7 +42,7 @@ impl BridgeTokenDeployer {
     /// `source_master_account`: NEAR master account on source network, ex. 'testnet'
     #[init]
     pub fn new(bridge_account: AccountId, source_master_account: AccountId) -> Self {
-        require!(!env::state_exists(), "Already initialized");
+        require_err!(!env::state_exists(), BridgeError::AlreadyInitialized);
         Self {
             bridge_account,
             source_master_account,
@@ -53,32 +51,17 @@ impl BridgeTokenDeployer {
 
     #[payable]
     pub fn deploy_bridge_token(&mut self, source_address: String) {
-        require!(
+        require_err!(
             env::predecessor_account_id() == self.bridge_account,
-            "Deploy bridge token can be called with and only with a corresponding bridge account"
+            BridgeError::Unauthorized,
+            "deploy bridge token can be called with and only with a corresponding bridge account"
         );
 
-        let mut bridge_token_account_name = format!(
-            "{}.{}",
-            source_address
-                .strip_suffix(&format!(".{}", self.source_master_account))
-                .unwrap_or(&source_address)
-                .replace('.', "_"),
-            env::current_account_id()
+        let bridge_token_account_name = utils::bridge_token_account_name(
+            &source_address,
+            self.source_master_account.as_str(),
+            env::current_account_id().as_str(),
         );
-
-        if bridge_token_account_name.len() > MAX_ACCOUNT_ID_LENGTH {
-            let suffix_length = env::current_account_id().as_str().len() + 1;
-            let name_sha = hashes::encode_hex(&env::sha256(bridge_token_account_name.as_bytes()));
-            let bridge_token_account_prefix =
-                name_sha.substring(0, MAX_ACCOUNT_ID_LENGTH - suffix_length);
-            bridge_token_account_name = format!(
-                "{}.{}",
-                bridge_token_account_prefix,
-                env::current_account_id()
-            );
-        };
-
         let bridge_token_account_id = AccountId::new_unchecked(bridge_token_account_name);
 
         let deploy_bridge_token_batch_promise = env::promise_batch_create(&bridge_token_account_id);
@@ -114,17 +97,14 @@ impl BridgeTokenDeployer {
     }
 
     #[private]
-    pub fn complete_deployment(&mut self, bridge_token_address: AccountId) {
-        require!(
-            env::promise_results_count() == 1,
-            "One and only one result was expected"
-        );
+    pub fn complete_deployment(&mut self, bridge_token_address: AccountId) -> (AccountId,) {
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         match env::promise_result(0) {
             PromiseResult::Successful(_) => (),
-            _ => env::panic_str("Bridge token deployer deployment failed"),
+            _ => panic_err(BridgeError::DeployFailed),
         };
 
-        env::value_return(&serde_json::to_vec(&(bridge_token_address,)).unwrap());
+        (bridge_token_address,)
     }
 }
@@ -0,0 +1,72 @@
+//! Tokens a connector failed to transfer when unlocking, kept for their receiver to claim later.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, StorageUsage};
+
+/// Token of a failed unlock.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Claim<T> {
+    /// Hex id of the outcome proven to unlock the token
+    pub transfer_id: String,
+    pub token: AccountId,
+    pub transferable: T,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Claims<T> {
+    /// Claims by the account the token was meant for
+    claims: LookupMap<AccountId, Vec<Claim<T>>>,
+}
+
+impl<T: BorshDeserialize + BorshSerialize> Claims<T> {
+    pub fn new(prefix: &[u8]) -> Self {
+        Self {
+            claims: LookupMap::new(prefix.to_vec()),
+        }
+    }
+
+    pub fn of(&self, account_id: &AccountId) -> Vec<Claim<T>> {
+        self.claims.get(account_id).unwrap_or_default()
+    }
+
+    pub fn add(&mut self, account_id: &AccountId, claim: Claim<T>) {
+        let mut claims = self.of(account_id);
+        claims.push(claim);
+        self.claims.insert(account_id, &claims);
+    }
+
+    /// Storage adding `claim` for `account_id` takes at most, which is when the account has no
+    /// other claims. The claims are left unchanged.
+    pub fn max_storage_usage(&mut self, account_id: &AccountId, claim: &Claim<T>) -> StorageUsage
+    where
+        T: Clone,
+    {
+        let claims = self.claims.remove(account_id);
+        let initial_storage = env::storage_usage();
+        self.claims.insert(account_id, &vec![claim.clone()]);
+        let storage_usage = env::storage_usage() - initial_storage;
+        match claims {
+            Some(claims) => self.claims.insert(account_id, &claims),
+            None => self.claims.remove(account_id),
+        };
+        storage_usage
+    }
+
+    /// Removes the claim of `account_id` for the transfer `transfer_id`.
+    pub fn take(&mut self, account_id: &AccountId, transfer_id: &str) -> Option<Claim<T>> {
+        let mut claims = self.of(account_id);
+        let index = claims
+            .iter()
+            .position(|claim| claim.transfer_id == transfer_id)?;
+        let claim = claims.remove(index);
+        if claims.is_empty() {
+            self.claims.remove(account_id);
+        } else {
+            self.claims.insert(account_id, &claims);
+        }
+        Some(claim)
+    }
+}
@@ -0,0 +1,132 @@
+//! Parsing of the event logs connectors emit on one network and consume on the other.
+//!
+//! Logs come from proofs submitted by anyone, so every field is checked here and reported
+//! as an `EventError`. Contracts turn the error into a panic before calling the prover.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json;
+use near_sdk::AccountId;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    #[error("outcome has no logs")]
+    NoLogs,
+    #[error("event log is not valid UTF-8")]
+    NotUtf8,
+    #[error("expected {event} with {expected} fields, found {found} fields")]
+    UnexpectedEvent {
+        event: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("invalid {field} '{value}'")]
+    InvalidField { field: &'static str, value: String },
+    #[error("event is sent from {source_chain} to {destination_chain}, expected {expected_source} to {expected_destination}")]
+    WrongDomain {
+        source_chain: String,
+        destination_chain: String,
+        expected_source: String,
+        expected_destination: String,
+    },
+}
+
+/// Chain ids of the network a connector is deployed on and of the network its locker is on.
+/// Every event ends with the ids of the chain it was emitted on and the chain it is meant for,
+/// so a proof can only be used by the connector of that one pair of chains.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChainDomain {
+    pub chain_id: String,
+    pub peer_chain_id: String,
+}
+
+impl ChainDomain {
+    /// Chain ids are event fields, so they must be non empty and must not contain `:`.
+    pub fn is_valid_chain_id(chain_id: &str) -> bool {
+        !chain_id.is_empty() && !chain_id.contains(':')
+    }
+
+    /// Fields appended to events emitted on this chain for the peer chain.
+    pub fn event_suffix(&self) -> String {
+        format!("{}:{}", self.chain_id, self.peer_chain_id)
+    }
+}
+
+/// Fields of the first log of an outcome, which must be `event` followed by `count - 1`
+/// fields. The event name is kept as the first field.
+pub fn event_params(
+    logs: &[Vec<u8>],
+    event: &str,
+    count: usize,
+) -> Result<Vec<String>, EventError> {
+    let log = logs.first().ok_or(EventError::NoLogs)?;
+    let log = std::str::from_utf8(log).map_err(|_| EventError::NotUtf8)?;
+    let params: Vec<String> = log.split(':').map(String::from).collect();
+    if params.len() != count || params[0] != event {
+        return Err(EventError::UnexpectedEvent {
+            event: event.to_string(),
+            expected: count,
+            found: params.len(),
+        });
+    }
+    Ok(params)
+}
+
+/// Like `event_params` for an event of `count` fields followed by its source and destination
+/// chain ids, which must be the peer chain and this chain of `domain`. The chain ids are not
+/// returned.
+pub fn domain_event_params(
+    logs: &[Vec<u8>],
+    event: &str,
+    count: usize,
+    domain: &ChainDomain,
+) -> Result<Vec<String>, EventError> {
+    let mut params = event_params(logs, event, count + 2)?;
+    let destination_chain = params.pop().unwrap_or_default();
+    let source_chain = params.pop().unwrap_or_default();
+    if source_chain != domain.peer_chain_id || destination_chain != domain.chain_id {
+        return Err(EventError::WrongDomain {
+            source_chain,
+            destination_chain,
+            expected_source: domain.peer_chain_id.clone(),
+            expected_destination: domain.chain_id.clone(),
+        });
+    }
+    Ok(params)
+}
+
+pub fn parse_account(field: &'static str, value: &str) -> Result<AccountId, EventError> {
+    value.parse().map_err(|_| invalid(field, value))
+}
+
+pub fn parse_number<T: FromStr>(field: &'static str, value: &str) -> Result<T, EventError> {
+    value.parse().map_err(|_| invalid(field, value))
+}
+
+pub fn decode_base64(field: &'static str, value: &str) -> Result<Vec<u8>, EventError> {
+    utils::from_base64(value).map_err(|_| invalid(field, value))
+}
+
+/// Base64 encoded UTF-8 text.
+pub fn decode_text(field: &'static str, value: &str) -> Result<String, EventError> {
+    String::from_utf8(decode_base64(field, value)?).map_err(|_| invalid(field, value))
+}
+
+/// Base64 encoded JSON.
+pub fn decode_json<T: DeserializeOwned>(
+    field: &'static str,
+    value: &str,
+) -> Result<T, EventError> {
+    serde_json::from_slice(&decode_base64(field, value)?).map_err(|_| invalid(field, value))
+}
+
+fn invalid(field: &'static str, value: &str) -> EventError {
+    EventError::InvalidField {
+        field,
+        value: value.to_string(),
+    }
+}
@@ -0,0 +1,117 @@
+//! Bridge fees charged by a connector per token, accrued until withdrawn by the connector.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// Basis points in a whole.
+pub const MAX_BPS: u16 = 10_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeSchedule {
+    /// Charged on every transfer
+    pub flat: U128,
+    /// Charged on top of `flat`, in basis points of the amount transferred
+    pub bps: u16,
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+impl FeeSchedule {
+    pub fn is_valid(&self) -> bool {
+        self.bps <= MAX_BPS && self.max.map_or(true, |max| self.min.0 <= max.0)
+    }
+
+    /// Fee for transferring `amount`, which may exceed `amount`.
+    pub fn fee(&self, amount: u128) -> u128 {
+        let (bps, whole) = (u128::from(self.bps), u128::from(MAX_BPS));
+        // split so that large amounts do not overflow
+        let proportional = amount / whole * bps + amount % whole * bps / whole;
+        let fee = self.flat.0.saturating_add(proportional).max(self.min.0);
+        self.max.map_or(fee, |max| fee.min(max.0))
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Fees {
+    schedules: LookupMap<AccountId, FeeSchedule>,
+    /// Fees charged and not withdrawn yet, by token
+    accrued: LookupMap<AccountId, u128>,
+    /// Balances accounts deposited to pay the storage of their locks with, and fees if not paid
+    /// in the token transferred
+    deposits: LookupMap<AccountId, u128>,
+}
+
+impl Fees {
+    pub fn new(schedules_prefix: &[u8], accrued_prefix: &[u8], deposits_prefix: &[u8]) -> Self {
+        Self {
+            schedules: LookupMap::new(schedules_prefix.to_vec()),
+            accrued: LookupMap::new(accrued_prefix.to_vec()),
+            deposits: LookupMap::new(deposits_prefix.to_vec()),
+        }
+    }
+
+    pub fn schedule(&self, token: &AccountId) -> Option<FeeSchedule> {
+        self.schedules.get(token)
+    }
+
+    /// Sets the schedule of `token`, or removes it so that transfers of `token` are free.
+    pub fn set_schedule(&mut self, token: &AccountId, schedule: Option<FeeSchedule>) {
+        match schedule {
+            Some(schedule) => self.schedules.insert(token, &schedule),
+            None => self.schedules.remove(token),
+        };
+    }
+
+    /// Fee for transferring `amount` of `token`, nothing if it has no schedule.
+    pub fn fee(&self, token: &AccountId, amount: u128) -> u128 {
+        self.schedule(token)
+            .map_or(0, |schedule| schedule.fee(amount))
+    }
+
+    pub fn accrued(&self, token: &AccountId) -> u128 {
+        self.accrued.get(token).unwrap_or_default()
+    }
+
+    pub fn accrue(&mut self, token: &AccountId, fee: u128) {
+        if fee > 0 {
+            self.accrued.insert(token, &(self.accrued(token) + fee));
+        }
+    }
+
+    /// Removes `amount` from the fees accrued for `token`, returns `false` if less accrued.
+    pub fn take_accrued(&mut self, token: &AccountId, amount: u128) -> bool {
+        let accrued = self.accrued(token);
+        if accrued < amount {
+            return false;
+        }
+        self.accrued.insert(token, &(accrued - amount));
+        true
+    }
+
+    pub fn deposit_of(&self, account_id: &AccountId) -> u128 {
+        self.deposits.get(account_id).unwrap_or_default()
+    }
+
+    pub fn deposit(&mut self, account_id: &AccountId, amount: u128) {
+        self.deposits
+            .insert(account_id, &(self.deposit_of(account_id) + amount));
+    }
+
+    /// Removes `amount` from the deposit of `account_id`, returns `false` if it is smaller.
+    pub fn take_deposit(&mut self, account_id: &AccountId, amount: u128) -> bool {
+        let deposit = self.deposit_of(account_id);
+        if deposit < amount {
+            return false;
+        }
+        if deposit == amount {
+            self.deposits.remove(account_id);
+        } else {
+            self.deposits.insert(account_id, &(deposit - amount));
+        }
+        true
+    }
+}
@@ -1,18 +1,64 @@
+pub mod claims;
+pub mod events;
+pub mod fees;
+pub mod limits;
+pub mod locked;
 pub mod macros;
+pub mod queue;
+pub mod transfers;
+pub mod used_events;
 pub use macros::*;
 
+use near_sdk::json_types::{U128, U64};
 use near_sdk::{AccountId, Balance};
-use types::FullOutcomeProof;
+use claims::Claim;
+use events::{ChainDomain, EventError};
+use fees::FeeSchedule;
+use limits::{GlobalLimit, Limit};
+use queue::QueuedTransfer;
+use transfers::{Transfer, TransferStatus};
+use types::errors::{panic_err, panic_err_with};
+use types::{BridgeError, EncodedOutcomeProof, FullOutcomeProof};
+
+/// Decodes a proof passed to one of the connector entrypoints or callbacks.
+pub fn decode_proof(proof: EncodedOutcomeProof) -> FullOutcomeProof {
+    proof
+        .decode()
+        .unwrap_or_else(|_| panic_err(BridgeError::InvalidProofEncoding))
+}
+
+/// Unwraps a value parsed from an event log, panics with `BridgeError::UntrustedEvent`, the
+/// `required` proof and the reason otherwise. Events of another pair of chains panic with
+/// `BridgeError::WrongDomain` instead.
+pub fn require_event<T>(parsed: Result<T, EventError>, required: &str) -> T {
+    parsed.unwrap_or_else(|err| {
+        let bridge_error = match err {
+            EventError::WrongDomain { .. } => BridgeError::WrongDomain,
+            _ => BridgeError::UntrustedEvent,
+        };
+        panic_err_with(bridge_error, &format!("{}: {}", required, err))
+    })
+}
+
+/// Chain ids of a connector, which must be set before it emits or accepts any event.
+pub fn require_domain(chain_domain: &Option<ChainDomain>) -> &ChainDomain {
+    chain_domain
+        .as_ref()
+        .unwrap_or_else(|| panic_err(BridgeError::ChainDomainNotSet))
+}
 
 pub trait DeployerAware {
     fn set_deployer(&mut self, deployer_account: AccountId);
     fn deploy_bridge_token(&mut self, source_address: String);
-    fn complete_deployment(&mut self, source_address: AccountId);
+    fn complete_deployment(&mut self, source_address: AccountId) -> (AccountId,);
 }
 
 pub trait OtherNetworkAware {
     fn set_locker(&mut self, locker_account: AccountId);
+    fn set_chain_domain(&mut self, chain_id: String, peer_chain_id: String);
+    fn view_chain_domain(&self) -> Option<ChainDomain>;
     fn record_proof(&mut self, proof: &FullOutcomeProof) -> Balance;
+    fn prune_used_events(&mut self, limit: u64) -> u64;
 }
 
 pub trait OtherNetworkTokenAware {
@@ -26,31 +72,91 @@ pub trait OtherNetworkTokenAware {
         &mut self,
         source_contract: AccountId,
         destination_contract: AccountId,
-        proof: FullOutcomeProof,
+        proof: EncodedOutcomeProof,
     );
-    fn register_on_other(&mut self, proof: FullOutcomeProof, height: u64);
+    fn register_on_other(&mut self, proof: EncodedOutcomeProof, height: u64);
 }
 
 pub trait TokenUnlock<T> {
     fn burn(&mut self, burner_id: AccountId, transferable: T);
-    fn unlock(&mut self, proof: FullOutcomeProof, height: u64);
+    fn unlock(&mut self, proof: EncodedOutcomeProof, height: u64);
+    fn refund(&mut self, proof: EncodedOutcomeProof, height: u64);
     fn finish_unlock(
         &mut self,
         caller_id: AccountId,
         token_contract_account: AccountId,
         token_receiver_account: AccountId,
         transferable: T,
-        proof: FullOutcomeProof,
+        transfer_nonce: Option<u64>,
+        proof: EncodedOutcomeProof,
     );
+    fn resolve_unlock(
+        &mut self,
+        caller_id: AccountId,
+        remaining_deposit: U128,
+        receiver_id: AccountId,
+        claim: Claim<T>,
+    ) -> bool;
+    fn claim(&mut self, transfer_id: String, receiver_id: Option<AccountId>);
+    fn resolve_claim(&mut self, owner_id: AccountId, claim: Claim<T>) -> bool;
+    fn view_claimable(&self, account_id: AccountId) -> Vec<Claim<T>>;
+    fn acknowledge(&mut self, proof: EncodedOutcomeProof, height: u64);
+    fn finish_acknowledge(&mut self, caller_id: AccountId, nonce: u64, proof: EncodedOutcomeProof);
+    fn view_transfers(
+        &self,
+        account_id: AccountId,
+        status: Option<TransferStatus>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<Transfer<T>>;
+    fn set_cancel_delay(&mut self, delay_ns: u64);
+    fn cancel(&mut self, nonce: U64);
+}
+
+pub trait FeeAware {
+    fn set_fee_schedule(&mut self, token: AccountId, schedule: Option<FeeSchedule>);
+    fn view_fee_schedule(&self, token: AccountId) -> Option<FeeSchedule>;
+    fn view_accrued_fees(&self, token: AccountId) -> U128;
+    fn withdraw_fees(&mut self, token: AccountId, amount: U128, receiver_id: AccountId);
+    fn resolve_withdraw_fees(&mut self, token: AccountId, amount: U128) -> bool;
+    fn deposit_fees(&mut self);
+    fn withdraw_fee_deposit(&mut self, amount: U128);
+    fn view_fee_deposit(&self, account_id: AccountId) -> U128;
+}
+
+pub trait LimitAware {
+    fn set_transfer_limit(&mut self, token: AccountId, limit: Option<Limit>);
+    fn set_global_limit(&mut self, limit: Option<GlobalLimit>);
+    fn view_transfer_limit(&self, token: AccountId) -> Option<Limit>;
+    fn view_global_limit(&self) -> Option<GlobalLimit>;
+    fn view_volume(&self, token: AccountId) -> U128;
+}
+
+pub trait TransferQueue<T> {
+    fn set_guardian(&mut self, guardian_account: Option<AccountId>);
+    fn set_queue_delay(&mut self, delay_ns: u64);
+    fn set_queue_threshold(&mut self, token: AccountId, threshold: Option<U128>);
+    fn view_queue_threshold(&self, token: AccountId) -> Option<U128>;
+    fn view_queue(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<QueuedTransfer<T>>;
+    fn cancel_queued(&mut self, id: U64);
+    fn execute_queued(&mut self, id: U64);
 }
 
 pub trait TokenMint {
-    fn mint(&mut self, proof: FullOutcomeProof, height: u64);
+    fn mint(&mut self, proof: EncodedOutcomeProof, height: u64);
     fn finish_mint(
         &mut self,
         caller_id: AccountId,
         token_contract_account: String,
         params: Vec<String>,
-        proof: FullOutcomeProof,
+        proof: EncodedOutcomeProof,
+    );
+    fn resolve_mint(&mut self, params: Vec<String>) -> bool;
+    fn cancel_mint(&mut self, proof: EncodedOutcomeProof, height: u64);
+    fn finish_cancel_mint(
+        &mut self,
+        caller_id: AccountId,
+        params: Vec<String>,
+        proof: EncodedOutcomeProof,
     );
 }
@@ -0,0 +1,183 @@
+//! Limits on the volume leaving a connector, minted or unlocked, over a rolling window.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// Limits of a token, in its smallest unit. Every NFT counts as 1.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Limit {
+    pub max_transfer: Option<U128>,
+    /// Volume of the token that can leave within `window_ns`
+    pub max_volume: Option<U128>,
+    #[serde(with = "utils::u64_dec_format")]
+    pub window_ns: u64,
+}
+
+impl Limit {
+    pub fn is_valid(&self) -> bool {
+        self.window_ns > 0
+    }
+}
+
+/// Limit on the number of transfers of all tokens together.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GlobalLimit {
+    #[serde(with = "utils::u64_dec_format")]
+    pub max_transfers: u64,
+    #[serde(with = "utils::u64_dec_format")]
+    pub window_ns: u64,
+}
+
+impl GlobalLimit {
+    pub fn is_valid(&self) -> bool {
+        self.window_ns > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitExceeded {
+    Volume,
+    GlobalTransfers,
+}
+
+impl LimitExceeded {
+    /// Name of the limit in alert events.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitExceeded::Volume => "volume",
+            LimitExceeded::GlobalTransfers => "global_transfers",
+        }
+    }
+}
+
+/// Volume of the current window and of the one before, the rolling volume weighs the previous
+/// window by the part of it still within `window_ns` of now.
+#[derive(BorshDeserialize, BorshSerialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct Window {
+    started_at: u64,
+    volume: u128,
+    previous_volume: u128,
+}
+
+impl Window {
+    fn roll(&mut self, now: u64, window_ns: u64) {
+        let elapsed = now.saturating_sub(self.started_at) / window_ns;
+        if elapsed == 0 {
+            return;
+        }
+        self.previous_volume = if elapsed == 1 { self.volume } else { 0 };
+        self.volume = 0;
+        self.started_at += elapsed * window_ns;
+    }
+
+    pub fn volume(&self, now: u64, window_ns: u64) -> u128 {
+        let mut window = self.clone();
+        window.roll(now, window_ns);
+        let remaining = u128::from(window_ns - now.saturating_sub(window.started_at));
+        let window_ns = u128::from(window_ns);
+        // split so that large volumes do not overflow
+        let previous = window.previous_volume / window_ns * remaining
+            + window.previous_volume % window_ns * remaining / window_ns;
+        window.volume.saturating_add(previous)
+    }
+
+    fn add(&mut self, now: u64, window_ns: u64, volume: u128) {
+        self.roll(now, window_ns);
+        self.volume = self.volume.saturating_add(volume);
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Limits {
+    limits: LookupMap<AccountId, Limit>,
+    windows: LookupMap<AccountId, Window>,
+    global: Option<GlobalLimit>,
+    global_window: Window,
+}
+
+impl Limits {
+    pub fn new(limits_prefix: &[u8], windows_prefix: &[u8]) -> Self {
+        Self {
+            limits: LookupMap::new(limits_prefix.to_vec()),
+            windows: LookupMap::new(windows_prefix.to_vec()),
+            global: None,
+            global_window: Window::default(),
+        }
+    }
+
+    pub fn limit(&self, token: &AccountId) -> Option<Limit> {
+        self.limits.get(token)
+    }
+
+    pub fn set_limit(&mut self, token: &AccountId, limit: Option<Limit>) {
+        match limit {
+            Some(limit) => self.limits.insert(token, &limit),
+            None => self.limits.remove(token),
+        };
+    }
+
+    pub fn global(&self) -> Option<GlobalLimit> {
+        self.global.clone()
+    }
+
+    pub fn set_global(&mut self, limit: Option<GlobalLimit>) {
+        self.global = limit;
+    }
+
+    /// Whether a single transfer of `volume` of `token` is within its limit.
+    pub fn allows_transfer(&self, token: &AccountId, volume: u128) -> bool {
+        self.limit(token)
+            .and_then(|limit| limit.max_transfer)
+            .map_or(true, |max| volume <= max.0)
+    }
+
+    /// Rolling volume of `token` that left within the window of its limit.
+    pub fn volume(&self, token: &AccountId, now: u64) -> u128 {
+        match (self.limit(token), self.windows.get(token)) {
+            (Some(limit), Some(window)) => window.volume(now, limit.window_ns),
+            _ => 0,
+        }
+    }
+
+    /// Records `volume` of `token` leaving, unless it exceeds the rolling volume of the token or
+    /// the number of transfers of all tokens. A single transfer above `max_transfer` is checked
+    /// with `allows_transfer` instead.
+    pub fn record(
+        &mut self,
+        token: &AccountId,
+        volume: u128,
+        now: u64,
+    ) -> Result<(), LimitExceeded> {
+        if let Some(global) = &self.global {
+            if self.global_window.volume(now, global.window_ns) >= u128::from(global.max_transfers)
+            {
+                return Err(LimitExceeded::GlobalTransfers);
+            }
+        }
+        if let Some(max_volume) = self.limit(token).and_then(|limit| limit.max_volume) {
+            if self.volume(token, now).saturating_add(volume) > max_volume.0 {
+                return Err(LimitExceeded::Volume);
+            }
+        }
+        self.add(token, volume, now);
+        Ok(())
+    }
+
+    /// Records `volume` of `token` leaving without checking the limits, used for transfers the
+    /// guardian approved.
+    pub fn add(&mut self, token: &AccountId, volume: u128, now: u64) {
+        if let Some(limit) = self.limit(token) {
+            let mut window = self.windows.get(token).unwrap_or_default();
+            window.add(now, limit.window_ns, volume);
+            self.windows.insert(token, &window);
+        }
+        if let Some(global) = &self.global {
+            self.global_window.add(now, global.window_ns, 1);
+        }
+    }
+}
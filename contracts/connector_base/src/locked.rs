@@ -0,0 +1,110 @@
+//! What a connector holds of the tokens locked on this network, to reconcile with the supply
+//! bridged to the other network.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::env;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// NFT held by a connector.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockedToken {
+    pub token_id: String,
+    /// Account that owned the NFT when it was locked
+    pub owner: AccountId,
+    #[serde(with = "utils::u64_dec_format")]
+    pub locked_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Locked {
+    /// Amount locked of each token, tokens of which nothing is locked are not kept. Every NFT
+    /// counts as 1.
+    totals: UnorderedMap<AccountId, U128>,
+    /// NFTs held by collection
+    tokens: LookupMap<AccountId, UnorderedMap<String, LockedToken>>,
+    tokens_prefix: Vec<u8>,
+}
+
+impl Locked {
+    pub fn new(totals_prefix: &[u8], tokens_prefix: &[u8]) -> Self {
+        Self {
+            totals: UnorderedMap::new(totals_prefix.to_vec()),
+            tokens: LookupMap::new(tokens_prefix.to_vec()),
+            tokens_prefix: tokens_prefix.to_vec(),
+        }
+    }
+
+    pub fn balance(&self, token: &AccountId) -> u128 {
+        self.totals.get(token).map_or(0, |total| total.0)
+    }
+
+    pub fn lock(&mut self, token: &AccountId, amount: u128) {
+        let total = self.balance(token).saturating_add(amount);
+        self.totals.insert(token, &U128(total));
+    }
+
+    /// Releases `amount` of `token`, unless less of it is locked.
+    pub fn release(&mut self, token: &AccountId, amount: u128) -> bool {
+        let total = match self.balance(token).checked_sub(amount) {
+            Some(total) => total,
+            None => return false,
+        };
+        if total == 0 {
+            self.totals.remove(token);
+        } else {
+            self.totals.insert(token, &U128(total));
+        }
+        true
+    }
+
+    pub fn list(&self, from_index: u64, limit: u64) -> Vec<(AccountId, U128)> {
+        self.totals
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Records an NFT of `collection` as held.
+    pub fn lock_token(&mut self, collection: &AccountId, token: LockedToken) {
+        let mut tokens = self.tokens.get(collection).unwrap_or_else(|| {
+            let prefix = [&self.tokens_prefix[..], &env::sha256(collection.as_bytes())].concat();
+            UnorderedMap::new(prefix)
+        });
+        if tokens.insert(&token.token_id, &token).is_none() {
+            self.lock(collection, 1);
+        }
+        self.tokens.insert(collection, &tokens);
+    }
+
+    /// Releases an NFT of `collection`, unless it is not held.
+    pub fn release_token(&mut self, collection: &AccountId, token_id: &str) -> Option<LockedToken> {
+        let mut tokens = self.tokens.get(collection)?;
+        let token = tokens.remove(&token_id.to_string())?;
+        self.release(collection, 1);
+        if tokens.is_empty() {
+            self.tokens.remove(collection);
+        } else {
+            self.tokens.insert(collection, &tokens);
+        }
+        Some(token)
+    }
+
+    pub fn token(&self, collection: &AccountId, token_id: &str) -> Option<LockedToken> {
+        self.tokens.get(collection)?.get(&token_id.to_string())
+    }
+
+    pub fn tokens(&self, collection: &AccountId, from_index: u64, limit: u64) -> Vec<LockedToken> {
+        self.tokens.get(collection).map_or_else(Vec::new, |tokens| {
+            tokens
+                .values()
+                .skip(from_index as usize)
+                .take(limit as usize)
+                .collect()
+        })
+    }
+}
@@ -5,7 +5,7 @@ macro_rules! impl_deployer_aware {
         impl DeployerAware for $contract {
             #[private]
             fn set_deployer(&mut self, deployer_account: AccountId) {
-                require!(self.deployer_account.is_none(), "Deployer account should not be set");
+                require_err!(self.deployer_account.is_none(), BridgeError::DeployerAlreadySet);
                 self.deployer_account = Some(deployer_account);
             }
 
@@ -13,6 +13,7 @@ macro_rules! impl_deployer_aware {
             #[private]
             fn deploy_bridge_token(&mut self, source_address: String) {
                 self.assert_not_paused_flags(PAUSE_DEPLOY_TOKEN);
+                connector_base::require_domain(&self.chain_domain);
 
                 let deploy_bridge_token_promise = env::promise_create(
                     self.deployer_account.clone().unwrap(),
@@ -33,25 +34,31 @@ macro_rules! impl_deployer_aware {
             }
 
             #[private]
-            fn complete_deployment(&mut self, source_address: AccountId) {
-                require!(env::promise_results_count() == 1, "One and only one result was expected");
+            fn complete_deployment(&mut self, source_address: AccountId) -> (AccountId,) {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
 
                 let bridge_token_address = match env::promise_result(0) {
                     PromiseResult::Successful(x) => {
                         serde_json::from_slice::<Vec<AccountId>>(&x).unwrap()[0].clone()
                     }
-                    _ => env::panic_str("Deploy bridge token failed"),
+                    _ => panic_err(BridgeError::DeployFailed),
                 };
 
                 env::log_str(&format!(
-                    "{}:{}:{}",
-                    $deploy_event, &source_address, bridge_token_address
+                    "{}:{}:{}:{}",
+                    $deploy_event,
+                    &source_address,
+                    bridge_token_address,
+                    connector_base::require_domain(&self.chain_domain).event_suffix()
                 ));
                 self.contracts_mapping
                     .insert(&source_address, &bridge_token_address);
                 self.all_contracts.insert(&bridge_token_address);
 
-                env::value_return(&serde_json::to_vec(&(bridge_token_address,)).unwrap());
+                (bridge_token_address,)
             }
         }
     };
@@ -59,7 +66,7 @@ macro_rules! impl_deployer_aware {
 
 #[macro_export]
 macro_rules! impl_other_network_token_aware {
-    ($contract: ident, $deploy_event: ident) => {
+    ($contract: ident, $deploy_event: ident, $transferable: ident) => {
         #[near_bindgen]
         #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
         pub struct $contract {
@@ -69,10 +76,32 @@ macro_rules! impl_other_network_token_aware {
             connector_permissions_account: AccountId,
             /// The account of the locker on other network that is used to lock FT
             locker_account: Option<AccountId>,
+            /// Chain ids of this network and of the locker network
+            chain_domain: Option<connector_base::events::ChainDomain>,
             /// The account of the deployer for bridge token
             deployer_account: Option<AccountId>,
             /// Hashes of the events that were already used.
-            used_events: LookupSet<Hash>,
+            used_events: connector_base::used_events::UsedEvents,
+            /// Tokens of unlocks that failed to transfer, by the account they were meant for
+            claimable: connector_base::claims::Claims<$transferable>,
+            /// Transfers locked by this connector
+            transfers: connector_base::transfers::Transfers<$transferable>,
+            /// Duration in nanoseconds after which the sender of a pending transfer can cancel it,
+            /// transfers can not be cancelled if not provided
+            cancel_delay_ns: Option<u64>,
+            /// Nonces of the locks on the other network that were minted or cancelled
+            used_nonces: LookupSet<u64>,
+            /// Fee schedules, fees accrued and fee deposits
+            fees: connector_base::fees::Fees,
+            /// Limits on the volume minted or unlocked
+            limits: connector_base::limits::Limits,
+            /// Tokens locked on this network
+            locked: connector_base::locked::Locked,
+            /// Account that can cancel queued transfers and execute those above the limit
+            /// of their token
+            guardian_account: Option<AccountId>,
+            /// Mints and unlocks waiting for their delay to pass
+            queue: connector_base::queue::Queue<$transferable>,
             /// Public key of the account deploying connector.
             owner_pk: PublicKey,
             /// Mappings between FT contract on main network and FT contract on this network
@@ -96,14 +125,24 @@ macro_rules! impl_other_network_token_aware {
                 connector_permissions_account: AccountId,
                 proof_validity_ns: Option<u64>,
             ) -> Self {
-                require!(!env::state_exists(), "Already initialized");
+                require_err!(!env::state_exists(), BridgeError::AlreadyInitialized);
                 Self {
                     prover_account,
                     connector_permissions_account,
-                    used_events: LookupSet::new(b"u".to_vec()),
+                    used_events: connector_base::used_events::UsedEvents::new(b"u", b"b"),
+                    claimable: connector_base::claims::Claims::new(b"l"),
+                    transfers: connector_base::transfers::Transfers::new(b"t", b"n"),
+                    cancel_delay_ns: None,
+                    used_nonces: LookupSet::new(b"o".to_vec()),
+                    fees: connector_base::fees::Fees::new(b"f", b"e", b"d"),
+                    limits: connector_base::limits::Limits::new(b"r", b"w"),
+                    locked: connector_base::locked::Locked::new(b"k", b"h"),
+                    guardian_account: None,
+                    queue: connector_base::queue::Queue::new(b"q", b"i"),
                     contracts_mapping: LookupMap::new(b"c".to_vec()),
                     all_contracts: LookupSet::new(b"a".to_vec()),
                     locker_account: None,
+                    chain_domain: None,
                     deployer_account: None,
                     owner_pk: env::signer_account_pk(),
                     paused: Mask::default(),
@@ -121,57 +160,74 @@ macro_rules! impl_other_network_token_aware {
                 &mut self,
                 source_contract: AccountId,
                 destination_contract: AccountId,
-                proof: FullOutcomeProof,
+                proof: EncodedOutcomeProof,
             ) {
-                require!(env::promise_results_count() == 1, "One and only one result was expected");
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+                let proof = connector_base::decode_proof(proof);
 
                 let verification_success = match env::promise_result(0) {
                     PromiseResult::Successful(x) => {
-                        serde_json::from_slice::<bool>(&x).unwrap()
+                        serde_json::from_slice::<bool>(&x)
+                            .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed))
                     }
-                    _ => env::panic_str("Prover failed"),
+                    _ => panic_err(BridgeError::ProverFailed),
                 };
-                require!(verification_success, "Failed to verify the proof");
+                require_err!(verification_success, BridgeError::ProofNotVerified);
 
                 let remaining_deposit = self.record_proof(&proof);
                 let initial_storage = env::storage_usage() as u128;
                 self.contracts_mapping
                     .insert(&destination_contract, &source_contract);
                 let current_storage = env::storage_usage() as u128;
-                require!(
+                require_err!(
                     remaining_deposit
                         >= env::storage_byte_cost() * (current_storage - initial_storage),
-                    "Not enough attached deposit to complete mapping"
+                    BridgeError::InsufficientDeposit,
+                    "mapping requires {} yocto",
+                    env::storage_byte_cost() * (current_storage - initial_storage)
                 );
             }
 
             #[payable]
-            fn register_on_other(&mut self, proof: FullOutcomeProof, height: u64) {
-                require!(self.locker_account.is_some(), "Locker account should be set");
-                require!(
+            fn register_on_other(&mut self, proof: EncodedOutcomeProof, height: u64) {
+                let proof = connector_base::decode_proof(proof);
+                require_err!(self.locker_account.is_some(), BridgeError::LockerNotSet);
+                let required = "deploy_bridge_token receipt proof required";
+                require_err!(
                     proof.outcome_proof.outcome_with_id.outcome.executor_id
                         == self.locker_account.as_ref().unwrap().to_string(),
-                    "Untrusted prover account, deploy_bridge_token receipt proof required"
+                    BridgeError::UntrustedExecutor,
+                    "{}",
+                    required
                 );
-                let event_log = proof.outcome_proof.outcome_with_id.outcome.logs[0].clone();
-                let params: Vec<&str> = std::str::from_utf8(&event_log)
-                    .unwrap()
-                    .split(":")
-                    .collect();
-                require!(
-                    params.len() == 3 && params[0] == $deploy_event,
-                    "Untrusted proof, deploy_bridge_token receipt proof required"
+                let params = connector_base::require_event(
+                    connector_base::events::domain_event_params(
+                        &proof.outcome_proof.outcome_with_id.outcome.logs,
+                        $deploy_event,
+                        3,
+                        connector_base::require_domain(&self.chain_domain),
+                    ),
+                    required,
                 );
 
-                let token_contract_account_source: AccountId = params[1].clone().parse().unwrap();
-                let token_contract_account_destination: AccountId =
-                    params[2].clone().parse().unwrap();
+                let token_contract_account_source = connector_base::require_event(
+                    connector_base::events::parse_account("source token", &params[1]),
+                    required,
+                );
+                let token_contract_account_destination = connector_base::require_event(
+                    connector_base::events::parse_account("bridge token", &params[2]),
+                    required,
+                );
 
                 // check that account deployment was done by locker_account
+                let encoded_proof = EncodedOutcomeProof::borsh(&proof);
                 let promise_prover = env::promise_create(
                     self.prover_account.clone(),
                     "prove_outcome",
-                    &serde_json::to_vec(&(proof.clone(), height)).unwrap(),
+                    &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
                     NO_DEPOSIT,
                     VERIFY_LOG_ENTRY_GAS,
                 );
@@ -183,7 +239,7 @@ macro_rules! impl_other_network_token_aware {
                     &serde_json::to_vec(&(
                         token_contract_account_source,
                         token_contract_account_destination,
-                        proof,
+                        encoded_proof,
                     ))
                     .unwrap(),
                     env::attached_deposit(),
@@ -203,75 +259,136 @@ macro_rules! impl_other_network_aware {
         impl OtherNetworkAware for $contract {
             #[private]
             fn set_locker(&mut self, locker_account: AccountId) {
-                require!(self.locker_account.is_none(), "Locker account should not be set");
+                require_err!(self.locker_account.is_none(), BridgeError::LockerAlreadySet);
                 self.locker_account = Some(locker_account);
             }
 
+            /// Sets the chain id of this network and of the network the locker is on, which
+            /// events emitted by this connector are tagged with and which proven events must match.
+            #[private]
+            fn set_chain_domain(&mut self, chain_id: String, peer_chain_id: String) {
+                require_err!(self.chain_domain.is_none(), BridgeError::ChainDomainAlreadySet);
+                require_err!(
+                    connector_base::events::ChainDomain::is_valid_chain_id(&chain_id)
+                        && connector_base::events::ChainDomain::is_valid_chain_id(&peer_chain_id)
+                        && chain_id != peer_chain_id,
+                    BridgeError::InvalidChainId
+                );
+                self.chain_domain = Some(connector_base::events::ChainDomain {
+                    chain_id,
+                    peer_chain_id,
+                });
+            }
+
+            fn view_chain_domain(&self) -> Option<connector_base::events::ChainDomain> {
+                self.chain_domain.clone()
+            }
+
             /// Record proof if it is valid to make sure it is not re-used later for another deposit.
             #[private]
             fn record_proof(&mut self, proof: &FullOutcomeProof) -> Balance {
                 let initial_storage = env::storage_usage();
-
-                require!(
-                    self.proof_validity_ns.is_none()
-                        || env::block_timestamp()
-                            <= proof.block_header_lite.inner_lite.timestamp
-                                + self.proof_validity_ns.unwrap(),
-                    "Proof expired"
-                );
+                let proof_timestamp = proof.block_header_lite.inner_lite.timestamp;
+                self.check_proof(proof);
 
                 let proof_key = proof.outcome_proof.outcome_with_id.id;
-                require!(
-                    !self.used_events.contains(&proof_key),
-                    "Event cannot be reused for depositing."
-                );
-                self.used_events.insert(&proof_key);
+                self.used_events
+                    .insert(&proof_key, proof_timestamp, self.proof_validity_ns);
                 let current_storage = env::storage_usage();
                 let required_deposit =
                     Balance::from(current_storage - initial_storage) * env::storage_byte_cost();
 
                 env::log_str(&format!("RecordProof:{}", hashes::encode_hex(&proof_key)));
 
-                require!(
+                require_err!(
                     env::attached_deposit() >= required_deposit,
-                    "Deposit too low"
+                    BridgeError::DepositTooLow
                 );
                 env::attached_deposit() - required_deposit
             }
+
+            /// Removes at most `limit` used events of which the proofs expired, these proofs are
+            /// rejected as expired anyway. The storage deposit freed is sent to the caller.
+            fn prune_used_events(&mut self, limit: u64) -> u64 {
+                let validity_ns = self
+                    .proof_validity_ns
+                    .unwrap_or_else(|| panic_err(BridgeError::NotSupported));
+                let initial_storage = env::storage_usage();
+                let pruned = self
+                    .used_events
+                    .prune(env::block_timestamp(), validity_ns, limit);
+                let freed_deposit = Balance::from(
+                    initial_storage.saturating_sub(env::storage_usage()),
+                ) * env::storage_byte_cost();
+
+                if freed_deposit > 0 {
+                    let refund_promise = env::promise_batch_create(&env::predecessor_account_id());
+                    env::promise_batch_action_transfer(refund_promise, freed_deposit);
+                }
+                pruned
+            }
+        }
+
+        impl $contract {
+            /// Fails if the proof expired or its event was already used.
+            fn check_proof(&self, proof: &FullOutcomeProof) {
+                require_err!(
+                    self.proof_validity_ns.is_none()
+                        || env::block_timestamp()
+                            <= proof.block_header_lite.inner_lite.timestamp
+                                + self.proof_validity_ns.unwrap(),
+                    BridgeError::ProofExpired
+                );
+                require_err!(
+                    !self.used_events.contains(&proof.outcome_proof.outcome_with_id.id),
+                    BridgeError::EventReused
+                );
+            }
         }
     };
 }
 
 #[macro_export]
 macro_rules! impl_token_mint {
-    ($contract: ident) => {
+    (
+        $contract: ident,
+        $minted_event: ident,
+        $mint_failed_event: ident,
+        $cancel_event: ident
+    ) => {
         #[near_bindgen]
         impl TokenMint for $contract {
             /// Used when receiving Token from other network
             #[payable]
-            fn mint(&mut self, proof: FullOutcomeProof, height: u64) {
+            fn mint(&mut self, proof: EncodedOutcomeProof, height: u64) {
                 self.assert_not_paused(PAUSE_MINT);
-                require!(self.locker_account.is_some(), "Locker account should be set");
-                require!(
+                let proof = connector_base::decode_proof(proof);
+                require_err!(self.locker_account.is_some(), BridgeError::LockerNotSet);
+                let required = "lock receipt proof required";
+                require_err!(
                     proof.outcome_proof.outcome_with_id.outcome.executor_id
                         == self.locker_account.as_ref().unwrap().to_string(),
-                    "Untrusted proof, lock receipt proof required"
+                    BridgeError::UntrustedExecutor,
+                    "{}",
+                    required
+                );
+                let params = connector_base::require_event(
+                    $contract::mint_params(
+                        &proof.outcome_proof.outcome_with_id.outcome.logs,
+                        connector_base::require_domain(&self.chain_domain),
+                    ),
+                    required,
+                );
+                let token_contract_account = connector_base::require_event(
+                    connector_base::events::parse_account("token", &params[1]),
+                    required,
                 );
-                let event_log = proof.outcome_proof.outcome_with_id.outcome.logs[0].clone();
-                let params: Vec<String> = std::str::from_utf8(&event_log)
-                    .unwrap()
-                    .split(":")
-                    .map(String::from)
-                    .collect();
-
-                $contract::verify_mint_params(params.clone());
-
-                let token_contract_account = params[1].clone();
 
+                let encoded_proof = EncodedOutcomeProof::borsh(&proof);
                 let promise_prover = env::promise_create(
                     self.prover_account.clone(),
                     "prove_outcome",
-                    &serde_json::to_vec(&(proof.clone(), height)).unwrap(),
+                    &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
                     NO_DEPOSIT,
                     PROVE_OUTCOME_GAS,
                 );
@@ -284,7 +401,7 @@ macro_rules! impl_token_mint {
                         env::predecessor_account_id(),
                         token_contract_account,
                         params,
-                        proof,
+                        encoded_proof,
                     ))
                     .unwrap(),
                     env::attached_deposit(),
@@ -303,39 +420,243 @@ macro_rules! impl_token_mint {
                 caller_id: AccountId,
                 token_contract_account: String,
                 params: Vec<String>,
-                proof: FullOutcomeProof,
+                proof: EncodedOutcomeProof,
             ) {
-                require!(env::promise_results_count() == 1, "One and only one result was expected");
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+                let proof = connector_base::decode_proof(proof);
 
                 let verification_success = match env::promise_result(0) {
                     PromiseResult::Successful(x) => {
-                        serde_json::from_slice::<bool>(&x).unwrap()
+                        serde_json::from_slice::<bool>(&x)
+                            .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed))
                     }
-                    _ => env::panic_str("Prover failed"),
+                    _ => panic_err(BridgeError::ProverFailed),
                 };
-                require!(verification_success, "Failed to verify the proof");
+                require_err!(verification_success, BridgeError::ProofNotVerified);
 
-                let remaining_deposit = self.record_proof(&proof);
-                let transfer_promise = if let Some(token_contract) = self
+                let required = "lock receipt proof required";
+                let token_contract_account = connector_base::require_event(
+                    connector_base::events::parse_account("token", &token_contract_account),
+                    required,
+                );
+                let mint_params = connector_base::require_event(
+                    $contract::token_mint_params(params.clone()),
+                    required,
+                );
+                let nonce = connector_base::require_event(
+                    connector_base::events::parse_number::<u64>("nonce", &params[params.len() - 1]),
+                    required,
+                );
+                let transferable = connector_base::require_event(
+                    $contract::parse_transferable(&params[3]),
+                    required,
+                );
+                let token_contract = self
                     .contracts_mapping
-                    .get(&token_contract_account.parse().unwrap())
-                {
+                    .get(&token_contract_account)
+                    .unwrap_or_else(|| panic_err(BridgeError::TokenNotMapped));
+
+                let volume = $contract::transfer_volume(&transferable);
+
+                // a used or expired proof must not count towards the limits
+                self.check_proof(&proof);
+                require_err!(
+                    !self.used_nonces.contains(&nonce),
+                    BridgeError::TransferCancelled
+                );
+                // a transfer above the limit of the token waits for the guardian instead, queued
+                // transfers count towards the limits once executed
+                let queued = !self.limits.allows_transfer(&token_contract, volume)
+                    || self.queue.is_queued(&token_contract, volume);
+                // the proof is not recorded, so the lock can be minted once unpaused
+                if !queued && !self.within_limits(&token_contract, volume, PAUSE_MINT) {
                     let refund_promise = env::promise_batch_create(&caller_id);
-                    env::promise_batch_action_transfer(refund_promise, remaining_deposit);
+                    env::promise_batch_action_transfer(refund_promise, env::attached_deposit());
+                    return;
+                }
 
-                    env::promise_then(
-                        refund_promise,
+                let remaining_deposit =
+                    self.record_lock(&proof, nonce, BridgeError::TransferCancelled);
+                if queued {
+                    let receiver = connector_base::require_event(
+                        connector_base::events::parse_account(
+                            "receiver",
+                            &params[params.len() - 3],
+                        ),
+                        required,
+                    );
+                    self.enqueue(
+                        &caller_id,
+                        remaining_deposit,
                         token_contract,
-                        "mint",
-                        &serde_json::to_vec(&$contract::token_mint_params(params)).unwrap(),
-                        near_sdk::ONE_NEAR,
-                        MINT_GAS,
-                    )
-                } else {
-                    env::panic_str("Token is not yet mapped")
+                        receiver,
+                        transferable,
+                        connector_base::queue::QueuedAction::Mint { params },
+                    );
+                    return;
+                }
+                let refund_promise = env::promise_batch_create(&caller_id);
+                env::promise_batch_action_transfer(refund_promise, remaining_deposit);
+                let transfer_promise = env::promise_then(
+                    refund_promise,
+                    token_contract,
+                    "mint",
+                    &serde_json::to_vec(&mint_params).unwrap(),
+                    near_sdk::ONE_NEAR,
+                    MINT_GAS,
+                );
+
+                env::promise_return(env::promise_then(
+                    transfer_promise,
+                    env::current_account_id(),
+                    "resolve_mint",
+                    &serde_json::to_vec(&(params,)).unwrap(),
+                    NO_DEPOSIT,
+                    RESOLVE_MINT_GAS,
+                ))
+            }
+
+            /// Emits an event acknowledging the transfer to the other network, or an event the
+            /// other network refunds the locked Token with if minting failed. The event of the
+            /// lock is used either way and can not be proven here again.
+            #[private]
+            fn resolve_mint(&mut self, params: Vec<String>) -> bool {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+
+                let minted = matches!(env::promise_result(0), PromiseResult::Successful(_));
+                self.log_mint_result(&params, minted);
+                minted
+            }
+
+            /// Used when the sender cancelled a Token locked on the other network, makes sure it
+            /// can not be minted anymore and emits the event the other network refunds it with
+            #[payable]
+            fn cancel_mint(&mut self, proof: EncodedOutcomeProof, height: u64) {
+                let proof = connector_base::decode_proof(proof);
+                let required = "cancel receipt proof required";
+                let params = self.locker_event_params(&proof, $cancel_event, 5, required);
+                let nonce = connector_base::require_event(
+                    connector_base::events::parse_number::<u64>("nonce", &params[4]),
+                    required,
+                );
+                require_err!(
+                    !self.used_nonces.contains(&nonce),
+                    BridgeError::TransferAlreadyMinted
+                );
+
+                let encoded_proof = EncodedOutcomeProof::borsh(&proof);
+                let promise_prover = env::promise_create(
+                    self.prover_account.clone(),
+                    "prove_outcome",
+                    &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
+                    NO_DEPOSIT,
+                    PROVE_OUTCOME_GAS,
+                );
+
+                let promise_result = env::promise_then(
+                    promise_prover,
+                    env::current_account_id(),
+                    "finish_cancel_mint",
+                    &serde_json::to_vec(&(env::predecessor_account_id(), params, encoded_proof))
+                        .unwrap(),
+                    env::attached_deposit(),
+                    FINISH_DEPOSIT_GAS,
+                );
+
+                env::promise_return(promise_result)
+            }
+
+            /// Finish cancelling once the proof was successfully validated. Can only be called by
+            /// the contract itself.
+            #[payable]
+            #[private]
+            fn finish_cancel_mint(
+                &mut self,
+                caller_id: AccountId,
+                params: Vec<String>,
+                proof: EncodedOutcomeProof,
+            ) {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+                let proof = connector_base::decode_proof(proof);
+
+                let verification_success = match env::promise_result(0) {
+                    PromiseResult::Successful(x) => {
+                        serde_json::from_slice::<bool>(&x)
+                            .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed))
+                    }
+                    _ => panic_err(BridgeError::ProverFailed),
                 };
+                require_err!(verification_success, BridgeError::ProofNotVerified);
 
-                env::promise_return(transfer_promise)
+                let nonce = connector_base::require_event(
+                    connector_base::events::parse_number::<u64>("nonce", &params[4]),
+                    "cancel receipt proof required",
+                );
+                // token, sender and transferable of the cancel event, followed by its nonce. Only
+                // the first log of an outcome is proven, so it precedes the recorded proof.
+                env::log_str(&format!(
+                    "{}:{}:{}:{}:{}:{}",
+                    $mint_failed_event,
+                    params[1],
+                    params[2],
+                    params[3],
+                    nonce,
+                    connector_base::require_domain(&self.chain_domain).event_suffix(),
+                ));
+                let remaining_deposit =
+                    self.record_lock(&proof, nonce, BridgeError::TransferAlreadyMinted);
+
+                if remaining_deposit > 0 {
+                    let refund_promise = env::promise_batch_create(&caller_id);
+                    env::promise_batch_action_transfer(refund_promise, remaining_deposit);
+                }
+            }
+        }
+
+        impl $contract {
+            /// Logs the event the other network acknowledges a mint or refunds the lock with.
+            fn log_mint_result(&self, params: &[String], minted: bool) {
+                // token, receiver and transferable of the lock event, followed by its nonce. Lock
+                // events end with the receiver, the memo and the nonce.
+                env::log_str(&format!(
+                    "{}:{}:{}:{}:{}:{}",
+                    if minted { $minted_event } else { $mint_failed_event },
+                    params[1],
+                    params[params.len() - 3],
+                    params[3],
+                    params[params.len() - 1],
+                    connector_base::require_domain(&self.chain_domain).event_suffix(),
+                ));
+            }
+
+            /// Records the proof of a lock or of its cancellation along with the nonce of the
+            /// lock, so that it is either minted or cancelled but never both. Fails with `err`
+            /// if the nonce was already used.
+            fn record_lock(
+                &mut self,
+                proof: &FullOutcomeProof,
+                nonce: u64,
+                err: BridgeError,
+            ) -> Balance {
+                let remaining_deposit = self.record_proof(proof);
+                let initial_storage = env::storage_usage();
+                require_err!(self.used_nonces.insert(&nonce), err);
+                let required_deposit = Balance::from(env::storage_usage() - initial_storage)
+                    * env::storage_byte_cost();
+                require_err!(
+                    remaining_deposit >= required_deposit,
+                    BridgeError::DepositTooLow
+                );
+                remaining_deposit - required_deposit
             }
         }
     };
@@ -343,55 +664,272 @@ macro_rules! impl_token_mint {
 
 #[macro_export]
 macro_rules! impl_token_unlock {
-    ($contract: ident, $burn_event: ident, $transferable: ident, $transfer_function: literal) => {
+    (
+        $contract: ident,
+        $burn_event: ident,
+        $minted_event: ident,
+        $mint_failed_event: ident,
+        $cancel_event: ident,
+        $transferable: ident,
+        $transfer_function: literal
+    ) => {
         #[near_bindgen]
         impl TokenUnlock<$transferable> for $contract {
             /// used when burning Token on this network
             fn burn(&mut self, burner_id: AccountId, transferable: $transferable) {
-                require!(
+                require_err!(
                     self.all_contracts.contains(&env::predecessor_account_id()),
-                    "Untrusted burn"
+                    BridgeError::UntrustedBurn
                 );
                 env::log_str(&format!(
-                    "{}:{}:{}:{}",
+                    "{}:{}:{}:{}:{}",
                     $burn_event,
                     env::predecessor_account_id(),
                     burner_id,
                     $contract::transform_transferable(transferable),
+                    connector_base::require_domain(&self.chain_domain).event_suffix(),
                 ));
             }
 
             /// Used when receiving Token from other network
             #[payable]
-            fn unlock(&mut self, proof: FullOutcomeProof, height: u64) {
-                require!(self.locker_account.is_some(), "Locker account should be set");
-                require!(
-                    proof.outcome_proof.outcome_with_id.outcome.executor_id
-                        == self.locker_account.as_ref().unwrap().to_string(),
-                    "Untrusted prover account, burn receipt proof required"
+            fn unlock(&mut self, proof: EncodedOutcomeProof, height: u64) {
+                self.assert_not_paused(PAUSE_UNLOCK);
+                let proof = connector_base::decode_proof(proof);
+                let required = "burn receipt proof required";
+                let params = self.locker_event_params(&proof, $burn_event, 4, required);
+                let destination_contract = connector_base::require_event(
+                    connector_base::events::parse_account("token", &params[1]),
+                    required,
                 );
-                let event_log = proof.outcome_proof.outcome_with_id.outcome.logs[0].clone();
-                let params: Vec<&str> = std::str::from_utf8(&event_log)
-                    .unwrap()
-                    .split(":")
-                    .collect();
-                require!(
-                    params.len() == 4 && params[0] == $burn_event,
-                    "Untrusted proof, burn receipt proof required"
+                let token_receiver_account = connector_base::require_event(
+                    connector_base::events::parse_account("receiver", &params[2]),
+                    required,
+                );
+                let transferable = connector_base::require_event(
+                    $contract::parse_transferable(&params[3]),
+                    required,
                 );
-                let destination_contract = &params[1];
-                let token_receiver_account = &params[2];
-                let transferable = $contract::parse_transferable(params[3].clone().to_owned());
 
                 let token_contract_account: AccountId = self
                     .contracts_mapping
-                    .get(&destination_contract.parse().unwrap())
-                    .unwrap();
+                    .get(&destination_contract)
+                    .unwrap_or_else(|| panic_err(BridgeError::TokenNotMapped));
+
+                self.prove_unlock(
+                    proof,
+                    height,
+                    token_contract_account,
+                    token_receiver_account,
+                    transferable,
+                    None,
+                );
+            }
+
+            /// Used when minting the Token locked on this network failed on the other network,
+            /// returns the Token to the account that locked it
+            #[payable]
+            fn refund(&mut self, proof: EncodedOutcomeProof, height: u64) {
+                self.assert_not_paused(PAUSE_UNLOCK);
+                let proof = connector_base::decode_proof(proof);
+                let required = "resolve_mint receipt proof required";
+                let params = self.locker_event_params(&proof, $mint_failed_event, 5, required);
+                let transfer = self.pending_transfer(&params[4], required);
+
+                self.prove_unlock(
+                    proof,
+                    height,
+                    transfer.token,
+                    transfer.sender,
+                    transfer.transferable,
+                    Some(transfer.nonce),
+                );
+            }
+
+            /// Finish depositing once the proof was successfully validated. Can only be called by the contract
+            /// itself.
+            #[payable]
+            #[private]
+            fn finish_unlock(
+                &mut self,
+                caller_id: AccountId,
+                token_contract_account: AccountId,
+                token_receiver_account: AccountId,
+                transferable: $transferable,
+                transfer_nonce: Option<u64>,
+                proof: EncodedOutcomeProof,
+            ) {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+                let proof = connector_base::decode_proof(proof);
 
+                let verification_success = match env::promise_result(0) {
+                    PromiseResult::Successful(x) => {
+                        serde_json::from_slice::<bool>(&x)
+                            .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed))
+                    }
+                    _ => panic_err(BridgeError::ProverFailed),
+                };
+                require_err!(verification_success, BridgeError::ProofNotVerified);
+
+                // a used or expired proof must not count towards the limits
+                self.check_proof(&proof);
+                let volume = $contract::transfer_volume(&transferable);
+                // a transfer above the limit of the token waits for the guardian instead, queued
+                // transfers count towards the limits once executed
+                let queued = !self.limits.allows_transfer(&token_contract_account, volume)
+                    || self.queue.is_queued(&token_contract_account, volume);
+                // the proof is not recorded, so the Token can be unlocked once unpaused
+                if !queued && !self.within_limits(&token_contract_account, volume, PAUSE_UNLOCK) {
+                    let refund_promise = env::promise_batch_create(&caller_id);
+                    env::promise_batch_action_transfer(refund_promise, env::attached_deposit());
+                    return;
+                }
+
+                let remaining_deposit = self.record_proof(&proof);
+                let transfer_id = hashes::encode_hex(&proof.outcome_proof.outcome_with_id.id);
+                // nothing is released until the unlock is executed, so a cancelled one keeps
+                // its Token locked
+                if queued {
+                    self.enqueue(
+                        &caller_id,
+                        remaining_deposit,
+                        token_contract_account,
+                        token_receiver_account,
+                        transferable,
+                        connector_base::queue::QueuedAction::Unlock {
+                            transfer_id,
+                            refund_nonce: transfer_nonce.map(near_sdk::json_types::U64),
+                        },
+                    );
+                    return;
+                }
+                let claim = connector_base::claims::Claim {
+                    transferable: self.complete_unlock(
+                        &token_contract_account,
+                        transferable,
+                        transfer_nonce,
+                    ),
+                    transfer_id,
+                    token: token_contract_account,
+                };
+                self.require_claim_deposit(&token_receiver_account, &claim, remaining_deposit);
+
+                let transfer_promise = self.transfer_claim(&token_receiver_account, &claim);
+                env::promise_return(env::promise_then(
+                    transfer_promise,
+                    env::current_account_id(),
+                    "resolve_unlock",
+                    &serde_json::to_vec(&(
+                        caller_id,
+                        near_sdk::json_types::U128(remaining_deposit),
+                        token_receiver_account,
+                        claim,
+                    ))
+                    .unwrap(),
+                    NO_DEPOSIT,
+                    RESOLVE_UNLOCK_GAS,
+                ))
+            }
+
+            /// Keeps the Token for the receiver to claim if the transfer failed, the storage it
+            /// takes is paid from the deposit left for the caller of the unlock, which was checked
+            /// to cover it.
+            #[private]
+            fn resolve_unlock(
+                &mut self,
+                caller_id: AccountId,
+                remaining_deposit: near_sdk::json_types::U128,
+                receiver_id: AccountId,
+                claim: connector_base::claims::Claim<$transferable>,
+            ) -> bool {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+
+                let mut remaining_deposit = remaining_deposit.0;
+                let transferred = matches!(env::promise_result(0), PromiseResult::Successful(_));
+                if !transferred {
+                    let initial_storage = env::storage_usage();
+                    self.claimable.add(&receiver_id, claim);
+                    let required_deposit = Balance::from(env::storage_usage() - initial_storage)
+                        * env::storage_byte_cost();
+                    remaining_deposit -= required_deposit;
+                }
+
+                if remaining_deposit > 0 {
+                    let refund_promise = env::promise_batch_create(&caller_id);
+                    env::promise_batch_action_transfer(refund_promise, remaining_deposit);
+                }
+                transferred
+            }
+
+            /// Transfers the Token of a failed unlock meant for the caller to `receiver_id`, or
+            /// to the caller if not provided.
+            #[payable]
+            fn claim(&mut self, transfer_id: String, receiver_id: Option<AccountId>) {
+                near_sdk::assert_one_yocto();
+                let owner_id = env::predecessor_account_id();
+                let claim = self
+                    .claimable
+                    .take(&owner_id, &transfer_id)
+                    .unwrap_or_else(|| panic_err(BridgeError::ClaimNotFound));
+                let receiver_id = receiver_id.unwrap_or_else(|| owner_id.clone());
+
+                let transfer_promise = self.transfer_claim(&receiver_id, &claim);
+                env::promise_return(env::promise_then(
+                    transfer_promise,
+                    env::current_account_id(),
+                    "resolve_claim",
+                    &serde_json::to_vec(&(owner_id, claim)).unwrap(),
+                    NO_DEPOSIT,
+                    RESOLVE_UNLOCK_GAS,
+                ))
+            }
+
+            /// Gives the claim back to its owner if the transfer failed.
+            #[private]
+            fn resolve_claim(
+                &mut self,
+                owner_id: AccountId,
+                claim: connector_base::claims::Claim<$transferable>,
+            ) -> bool {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+
+                let transferred = matches!(env::promise_result(0), PromiseResult::Successful(_));
+                if !transferred {
+                    self.claimable.add(&owner_id, claim);
+                }
+                transferred
+            }
+
+            fn view_claimable(
+                &self,
+                account_id: AccountId,
+            ) -> Vec<connector_base::claims::Claim<$transferable>> {
+                self.claimable.of(&account_id)
+            }
+
+            /// Used when the other network acknowledged minting a Token locked on this network,
+            /// marks its transfer completed
+            #[payable]
+            fn acknowledge(&mut self, proof: EncodedOutcomeProof, height: u64) {
+                let proof = connector_base::decode_proof(proof);
+                let required = "resolve_mint receipt proof required";
+                let params = self.locker_event_params(&proof, $minted_event, 5, required);
+                let transfer = self.pending_transfer(&params[4], required);
+
+                let encoded_proof = EncodedOutcomeProof::borsh(&proof);
                 let promise_prover = env::promise_create(
                     self.prover_account.clone(),
                     "prove_outcome",
-                    &serde_json::to_vec(&(proof.clone(), height)).unwrap(),
+                    &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
                     NO_DEPOSIT,
                     VERIFY_LOG_ENTRY_GAS,
                 );
@@ -399,68 +937,672 @@ macro_rules! impl_token_unlock {
                 let promise_result = env::promise_then(
                     promise_prover,
                     env::current_account_id(),
-                    "finish_unlock",
+                    "finish_acknowledge",
                     &serde_json::to_vec(&(
                         env::predecessor_account_id(),
-                        token_contract_account,
-                        token_receiver_account,
-                        transferable,
-                        proof,
+                        transfer.nonce,
+                        encoded_proof,
                     ))
                     .unwrap(),
                     env::attached_deposit(),
-                    FINISH_UNLOCK_GAS + MINT_GAS + TRANSFER_CALL_GAS,
+                    FINISH_UNLOCK_GAS,
                 );
 
                 env::promise_return(promise_result)
             }
 
-            /// Finish depositing once the proof was successfully validated. Can only be called by the contract
-            /// itself.
+            /// Finish acknowledging once the proof was successfully validated. Can only be called
+            /// by the contract itself.
             #[payable]
             #[private]
-            fn finish_unlock(
+            fn finish_acknowledge(
                 &mut self,
                 caller_id: AccountId,
-                token_contract_account: AccountId,
-                token_receiver_account: AccountId,
-                transferable: $transferable,
-                proof: FullOutcomeProof,
+                nonce: u64,
+                proof: EncodedOutcomeProof,
             ) {
-                require!(env::promise_results_count() == 1, "One and only one result was expected");
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+                let proof = connector_base::decode_proof(proof);
 
                 let verification_success = match env::promise_result(0) {
                     PromiseResult::Successful(x) => {
-                        serde_json::from_slice::<bool>(&x).unwrap()
+                        serde_json::from_slice::<bool>(&x)
+                            .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed))
                     }
-                    _ => env::panic_str("Prover failed"),
+                    _ => panic_err(BridgeError::ProverFailed),
                 };
-                require!(verification_success, "Failed to verify the proof");
+                require_err!(verification_success, BridgeError::ProofNotVerified);
 
                 let remaining_deposit = self.record_proof(&proof);
+                self.transfers
+                    .finish(nonce, connector_base::transfers::TransferStatus::Completed)
+                    .unwrap_or_else(|| panic_err(BridgeError::TransferNotPending));
 
-                let refund_promise = env::promise_batch_create(&caller_id);
-                env::promise_batch_action_transfer(refund_promise, remaining_deposit);
+                if remaining_deposit > 0 {
+                    let refund_promise = env::promise_batch_create(&caller_id);
+                    env::promise_batch_action_transfer(refund_promise, remaining_deposit);
+                }
+            }
+
+            /// Transfers locked by `account_id` with `status`, or all of them, oldest first.
+            /// `from_index` and `limit` page through all transfers of `account_id`, before
+            /// filtering by `status`.
+            fn view_transfers(
+                &self,
+                account_id: AccountId,
+                status: Option<connector_base::transfers::TransferStatus>,
+                from_index: Option<u64>,
+                limit: Option<u64>,
+            ) -> Vec<connector_base::transfers::Transfer<$transferable>> {
+                self.transfers.of(
+                    &account_id,
+                    status,
+                    from_index.unwrap_or(0),
+                    limit.unwrap_or(u64::MAX),
+                )
+            }
+
+            /// Sets the duration after which the sender of a pending transfer can cancel it.
+            #[private]
+            fn set_cancel_delay(&mut self, delay_ns: u64) {
+                self.cancel_delay_ns = Some(delay_ns);
+            }
+
+            /// Used by the sender of a transfer that was not minted on the other network in time,
+            /// emits an event the other network answers with the event the Token is refunded with
+            /// unless it was minted meanwhile
+            #[payable]
+            fn cancel(&mut self, nonce: near_sdk::json_types::U64) {
+                near_sdk::assert_one_yocto();
+                let delay_ns = self
+                    .cancel_delay_ns
+                    .unwrap_or_else(|| panic_err(BridgeError::NotSupported));
+                let transfer = self
+                    .transfers
+                    .get(nonce.0)
+                    .filter(|transfer| {
+                        transfer.status == connector_base::transfers::TransferStatus::Pending
+                    })
+                    .unwrap_or_else(|| panic_err(BridgeError::TransferNotPending));
+                require_err!(
+                    transfer.sender == env::predecessor_account_id(),
+                    BridgeError::Unauthorized
+                );
+                require_err!(
+                    env::block_timestamp() >= transfer.locked_at.saturating_add(delay_ns),
+                    BridgeError::CancelTooEarly
+                );
+
+                env::log_str(&format!(
+                    "{}:{}:{}:{}:{}:{}",
+                    $cancel_event,
+                    transfer.token,
+                    transfer.sender,
+                    $contract::transform_transferable(transfer.transferable),
+                    transfer.nonce,
+                    connector_base::require_domain(&self.chain_domain).event_suffix(),
+                ));
+            }
+        }
+
+        impl $contract {
+            /// Params of the `event` the locker logged in the proven outcome.
+            fn locker_event_params(
+                &self,
+                proof: &FullOutcomeProof,
+                event: &str,
+                count: usize,
+                required: &str,
+            ) -> Vec<String> {
+                require_err!(self.locker_account.is_some(), BridgeError::LockerNotSet);
+                require_err!(
+                    proof.outcome_proof.outcome_with_id.outcome.executor_id
+                        == self.locker_account.as_ref().unwrap().to_string(),
+                    BridgeError::UntrustedExecutor,
+                    "{}",
+                    required
+                );
+                connector_base::require_event(
+                    connector_base::events::domain_event_params(
+                        &proof.outcome_proof.outcome_with_id.outcome.logs,
+                        event,
+                        count,
+                        connector_base::require_domain(&self.chain_domain),
+                    ),
+                    required,
+                )
+            }
+
+            /// Releases the Token an executed unlock takes from this connector, then marks the
+            /// transfer with `refund_nonce` refunded or charges the fee of the unlock.
+            fn complete_unlock(
+                &mut self,
+                token: &AccountId,
+                transferable: $transferable,
+                refund_nonce: Option<u64>,
+            ) -> $transferable {
+                self.release_locked(token, &transferable);
+                if let Some(nonce) = refund_nonce {
+                    self.transfers
+                        .finish(nonce, connector_base::transfers::TransferStatus::Refunded)
+                        .unwrap_or_else(|| panic_err(BridgeError::TransferNotPending));
+                    transferable
+                } else {
+                    self.charge_unlock_fee(token, transferable)
+                }
+            }
+
+            /// Fails unless `remaining_deposit` covers the storage of keeping `claim` for
+            /// `receiver_id` if its transfer fails.
+            fn require_claim_deposit(
+                &mut self,
+                receiver_id: &AccountId,
+                claim: &connector_base::claims::Claim<$transferable>,
+                remaining_deposit: Balance,
+            ) {
+                let required_deposit =
+                    Balance::from(self.claimable.max_storage_usage(receiver_id, claim))
+                        * env::storage_byte_cost();
+                require_err!(
+                    remaining_deposit >= required_deposit,
+                    BridgeError::DepositTooLow
+                );
+            }
+
+            /// Transfers the Token held by this connector once the proof is verified.
+            fn prove_unlock(
+                &self,
+                proof: FullOutcomeProof,
+                height: u64,
+                token_contract_account: AccountId,
+                token_receiver_account: AccountId,
+                transferable: $transferable,
+                transfer_nonce: Option<u64>,
+            ) {
+                let encoded_proof = EncodedOutcomeProof::borsh(&proof);
+                let promise_prover = env::promise_create(
+                    self.prover_account.clone(),
+                    "prove_outcome",
+                    &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
+                    NO_DEPOSIT,
+                    VERIFY_LOG_ENTRY_GAS,
+                );
+
+                let promise_result = env::promise_then(
+                    promise_prover,
+                    env::current_account_id(),
+                    "finish_unlock",
+                    &serde_json::to_vec(&(
+                        env::predecessor_account_id(),
+                        token_contract_account,
+                        token_receiver_account,
+                        transferable,
+                        transfer_nonce,
+                        encoded_proof,
+                    ))
+                    .unwrap(),
+                    env::attached_deposit(),
+                    FINISH_UNLOCK_GAS + MINT_GAS + TRANSFER_CALL_GAS + RESOLVE_UNLOCK_GAS,
+                );
+
+                env::promise_return(promise_result)
+            }
+
+            /// Transfer of the `nonce` logged in an event, which must still be pending.
+            fn pending_transfer(
+                &self,
+                nonce: &str,
+                required: &str,
+            ) -> connector_base::transfers::Transfer<$transferable> {
+                let nonce = connector_base::require_event(
+                    connector_base::events::parse_number::<u64>("nonce", nonce),
+                    required,
+                );
+                self.transfers
+                    .get(nonce)
+                    .filter(|transfer| {
+                        transfer.status == connector_base::transfers::TransferStatus::Pending
+                    })
+                    .unwrap_or_else(|| panic_err(BridgeError::TransferNotPending))
+            }
 
+            /// Transfers the Token held by this connector for `claim` to `receiver_id`.
+            fn transfer_claim(
+                &self,
+                receiver_id: &AccountId,
+                claim: &connector_base::claims::Claim<$transferable>,
+            ) -> near_sdk::PromiseIndex {
                 let memo = String::from(format!(
                     "Transfer from {}",
                     self.locker_account.as_ref().unwrap().to_string()
                 ));
 
-                env::promise_return(env::promise_then(
-                    refund_promise,
-                    token_contract_account,
+                env::promise_create(
+                    claim.token.clone(),
                     $transfer_function,
                     &serde_json::to_vec(&$contract::token_unlock_params(
-                        token_receiver_account,
-                        transferable,
+                        receiver_id.clone(),
+                        claim.transferable.clone(),
                         memo,
                     ))
                     .unwrap(),
                     near_sdk::ONE_YOCTO,
                     MINT_GAS,
+                )
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_fee_aware {
+    ($contract: ident) => {
+        #[near_bindgen]
+        impl FeeAware for $contract {
+            /// Sets the fees charged for bridging `token`, bridging it is free without a schedule.
+            #[private]
+            fn set_fee_schedule(
+                &mut self,
+                token: AccountId,
+                schedule: Option<connector_base::fees::FeeSchedule>,
+            ) {
+                require_err!(
+                    schedule.as_ref().map_or(true, |schedule| schedule.is_valid()),
+                    BridgeError::InvalidFeeSchedule
+                );
+                self.fees.set_schedule(&token, schedule);
+            }
+
+            fn view_fee_schedule(
+                &self,
+                token: AccountId,
+            ) -> Option<connector_base::fees::FeeSchedule> {
+                self.fees.schedule(&token)
+            }
+
+            fn view_accrued_fees(&self, token: AccountId) -> near_sdk::json_types::U128 {
+                near_sdk::json_types::U128(self.fees.accrued(&token))
+            }
+
+            /// Sends `amount` of the fees accrued for `token` to `receiver_id`.
+            #[private]
+            fn withdraw_fees(
+                &mut self,
+                token: AccountId,
+                amount: near_sdk::json_types::U128,
+                receiver_id: AccountId,
+            ) {
+                require_err!(
+                    self.fees.take_accrued(&token, amount.0),
+                    BridgeError::InsufficientFees
+                );
+
+                let transfer_promise = $contract::transfer_fees(&token, &receiver_id, amount.0);
+                env::promise_return(env::promise_then(
+                    transfer_promise,
+                    env::current_account_id(),
+                    "resolve_withdraw_fees",
+                    &serde_json::to_vec(&(token, amount)).unwrap(),
+                    NO_DEPOSIT,
+                    RESOLVE_UNLOCK_GAS,
                 ))
             }
+
+            /// Accrues the fees again if the transfer failed.
+            #[private]
+            fn resolve_withdraw_fees(
+                &mut self,
+                token: AccountId,
+                amount: near_sdk::json_types::U128,
+            ) -> bool {
+                require_err!(
+                    env::promise_results_count() == 1,
+                    BridgeError::UnexpectedPromiseResults
+                );
+
+                let withdrawn = matches!(env::promise_result(0), PromiseResult::Successful(_));
+                if !withdrawn {
+                    self.fees.accrue(&token, amount.0);
+                }
+                withdrawn
+            }
+
+            /// Deposits the attached NEAR for paying the storage of the transfers the caller
+            /// locks, and their fees where charged in NEAR. The storage the deposit takes is paid
+            /// from it.
+            #[payable]
+            fn deposit_fees(&mut self) {
+                let account_id = env::predecessor_account_id();
+                let initial_storage = env::storage_usage();
+                self.fees.deposit(&account_id, env::attached_deposit());
+                let required_deposit = Balance::from(env::storage_usage() - initial_storage)
+                    * env::storage_byte_cost();
+                require_err!(
+                    env::attached_deposit() > required_deposit,
+                    BridgeError::InsufficientDeposit
+                );
+                self.fees.take_deposit(&account_id, required_deposit);
+            }
+
+            /// Returns `amount` of the fee deposit to the caller.
+            #[payable]
+            fn withdraw_fee_deposit(&mut self, amount: near_sdk::json_types::U128) {
+                near_sdk::assert_one_yocto();
+                let account_id = env::predecessor_account_id();
+                require_err!(
+                    self.fees.take_deposit(&account_id, amount.0),
+                    BridgeError::InsufficientFees
+                );
+                let refund_promise = env::promise_batch_create(&account_id);
+                env::promise_batch_action_transfer(refund_promise, amount.0);
+            }
+
+            fn view_fee_deposit(&self, account_id: AccountId) -> near_sdk::json_types::U128 {
+                near_sdk::json_types::U128(self.fees.deposit_of(&account_id))
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_limit_aware {
+    ($contract: ident, $limit_event: ident) => {
+        #[near_bindgen]
+        impl LimitAware for $contract {
+            /// Sets the limits on the volume of `token` minted or unlocked, or removes them.
+            #[private]
+            fn set_transfer_limit(
+                &mut self,
+                token: AccountId,
+                limit: Option<connector_base::limits::Limit>,
+            ) {
+                require_err!(
+                    limit.as_ref().map_or(true, |limit| limit.is_valid()),
+                    BridgeError::InvalidLimit
+                );
+                self.limits.set_limit(&token, limit);
+            }
+
+            /// Sets the limit on the number of transfers of all tokens minted or unlocked, or
+            /// removes it.
+            #[private]
+            fn set_global_limit(&mut self, limit: Option<connector_base::limits::GlobalLimit>) {
+                require_err!(
+                    limit.as_ref().map_or(true, |limit| limit.is_valid()),
+                    BridgeError::InvalidLimit
+                );
+                self.limits.set_global(limit);
+            }
+
+            fn view_transfer_limit(
+                &self,
+                token: AccountId,
+            ) -> Option<connector_base::limits::Limit> {
+                self.limits.limit(&token)
+            }
+
+            fn view_global_limit(&self) -> Option<connector_base::limits::GlobalLimit> {
+                self.limits.global()
+            }
+
+            /// Volume of `token` minted or unlocked within the window of its limit.
+            fn view_volume(&self, token: AccountId) -> near_sdk::json_types::U128 {
+                near_sdk::json_types::U128(self.limits.volume(&token, env::block_timestamp()))
+            }
+        }
+
+        impl $contract {
+            /// Records `volume` of `token` leaving this connector. If that exceeds a limit, pauses
+            /// `flag` and logs an alert instead.
+            fn within_limits(&mut self, token: &AccountId, volume: u128, flag: Mask) -> bool {
+                match self.limits.record(token, volume, env::block_timestamp()) {
+                    Ok(()) => true,
+                    Err(exceeded) => {
+                        self.paused |= flag;
+                        env::log_str(&format!(
+                            "{}:{}:{}:{}:{}",
+                            $limit_event,
+                            token,
+                            volume,
+                            exceeded.as_str(),
+                            connector_base::require_domain(&self.chain_domain).event_suffix(),
+                        ));
+                        false
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_transfer_queue {
+    ($contract: ident, $transferable: ident, $queue_cancelled_event: ident) => {
+        #[near_bindgen]
+        impl TransferQueue<$transferable> for $contract {
+            #[private]
+            fn set_guardian(&mut self, guardian_account: Option<AccountId>) {
+                self.guardian_account = guardian_account;
+            }
+
+            /// Sets the duration queued transfers wait before they can be executed.
+            #[private]
+            fn set_queue_delay(&mut self, delay_ns: u64) {
+                self.queue.set_delay(delay_ns);
+            }
+
+            /// Sets the volume of `token` above which its mints and unlocks are queued, or
+            /// removes it.
+            #[private]
+            fn set_queue_threshold(
+                &mut self,
+                token: AccountId,
+                threshold: Option<near_sdk::json_types::U128>,
+            ) {
+                self.queue.set_threshold(&token, threshold);
+            }
+
+            fn view_queue_threshold(&self, token: AccountId) -> Option<near_sdk::json_types::U128> {
+                self.queue.threshold(&token)
+            }
+
+            fn view_queue(
+                &self,
+                from_index: Option<u64>,
+                limit: Option<u64>,
+            ) -> Vec<connector_base::queue::QueuedTransfer<$transferable>> {
+                self.queue
+                    .list(from_index.unwrap_or(0), limit.unwrap_or(u64::MAX))
+            }
+
+            /// Drops a queued transfer, used by the guardian when it deems it fraudulent. The
+            /// event it was proven with stays used. A dropped mint logs the event the other
+            /// network refunds the lock with, a dropped unlock keeps its Token held by this
+            /// connector.
+            #[payable]
+            fn cancel_queued(&mut self, id: near_sdk::json_types::U64) {
+                near_sdk::assert_one_yocto();
+                let caller_id = env::predecessor_account_id();
+                require_err!(
+                    self.guardian_account.as_ref() == Some(&caller_id)
+                        || caller_id == env::current_account_id(),
+                    BridgeError::Unauthorized
+                );
+                let queued = self
+                    .queue
+                    .take(id.0)
+                    .unwrap_or_else(|| panic_err(BridgeError::QueuedTransferNotFound));
+                let action = match &queued.action {
+                    connector_base::queue::QueuedAction::Mint { params } => {
+                        // relayed first, the nonce of the lock stays used so it is refunded
+                        self.log_mint_result(params, false);
+                        "mint"
+                    }
+                    connector_base::queue::QueuedAction::Unlock { .. } => "unlock",
+                };
+                env::log_str(&format!(
+                    "{}:{}:{}:{}:{}:{}:{}",
+                    $queue_cancelled_event,
+                    id.0,
+                    action,
+                    queued.token,
+                    queued.receiver,
+                    $contract::transform_transferable(queued.transferable),
+                    connector_base::require_domain(&self.chain_domain).event_suffix(),
+                ));
+            }
+
+            /// Executes a queued transfer once its delay passed, the attached deposit must cover
+            /// keeping a failed unlock claimable and the rest is refunded. A transfer above the
+            /// limit of its token is only executed by the guardian, its volume then counts towards
+            /// the limits without pausing. Any other transfer exceeding a limit pauses like a
+            /// transfer that is not queued, and stays queued.
+            #[payable]
+            fn execute_queued(&mut self, id: near_sdk::json_types::U64) {
+                let queued = self
+                    .queue
+                    .get(id.0)
+                    .unwrap_or_else(|| panic_err(BridgeError::QueuedTransferNotFound));
+                require_err!(
+                    env::block_timestamp() >= queued.executable_at,
+                    BridgeError::QueuedTransferNotReady
+                );
+                let caller_id = env::predecessor_account_id();
+                let volume = $contract::transfer_volume(&queued.transferable);
+                let oversized = !self.limits.allows_transfer(&queued.token, volume);
+                require_err!(
+                    !oversized
+                        || self.guardian_account.as_ref() == Some(&caller_id)
+                        || caller_id == env::current_account_id(),
+                    BridgeError::Unauthorized
+                );
+                let flag = match queued.action {
+                    connector_base::queue::QueuedAction::Mint { .. } => PAUSE_MINT,
+                    connector_base::queue::QueuedAction::Unlock { .. } => PAUSE_UNLOCK,
+                };
+                self.assert_not_paused(flag);
+                if oversized {
+                    // approved by the guardian, counts towards the limits without pausing
+                    self.limits.add(&queued.token, volume, env::block_timestamp());
+                } else if !self.within_limits(&queued.token, volume, flag) {
+                    // stays queued, so it can be executed once unpaused
+                    if env::attached_deposit() > 0 {
+                        let refund_promise = env::promise_batch_create(&caller_id);
+                        env::promise_batch_action_transfer(refund_promise, env::attached_deposit());
+                    }
+                    return;
+                }
+                self.queue.take(id.0);
+
+                match queued.action {
+                    connector_base::queue::QueuedAction::Mint { params } => {
+                        let mint_params = connector_base::require_event(
+                            $contract::token_mint_params(params.clone()),
+                            "queued lock",
+                        );
+                        if env::attached_deposit() > 0 {
+                            let refund_promise =
+                                env::promise_batch_create(&env::predecessor_account_id());
+                            env::promise_batch_action_transfer(
+                                refund_promise,
+                                env::attached_deposit(),
+                            );
+                        }
+
+                        let transfer_promise = env::promise_create(
+                            queued.token,
+                            "mint",
+                            &serde_json::to_vec(&mint_params).unwrap(),
+                            near_sdk::ONE_NEAR,
+                            MINT_GAS,
+                        );
+                        env::promise_return(env::promise_then(
+                            transfer_promise,
+                            env::current_account_id(),
+                            "resolve_mint",
+                            &serde_json::to_vec(&(params,)).unwrap(),
+                            NO_DEPOSIT,
+                            RESOLVE_MINT_GAS,
+                        ))
+                    }
+                    connector_base::queue::QueuedAction::Unlock {
+                        transfer_id,
+                        refund_nonce,
+                    } => {
+                        let claim = connector_base::claims::Claim {
+                            transferable: self.complete_unlock(
+                                &queued.token,
+                                queued.transferable,
+                                refund_nonce.map(|nonce| nonce.0),
+                            ),
+                            transfer_id,
+                            token: queued.token,
+                        };
+                        self.require_claim_deposit(
+                            &queued.receiver,
+                            &claim,
+                            env::attached_deposit(),
+                        );
+
+                        let transfer_promise = self.transfer_claim(&queued.receiver, &claim);
+                        env::promise_return(env::promise_then(
+                            transfer_promise,
+                            env::current_account_id(),
+                            "resolve_unlock",
+                            &serde_json::to_vec(&(
+                                env::predecessor_account_id(),
+                                near_sdk::json_types::U128(env::attached_deposit()),
+                                queued.receiver,
+                                claim,
+                            ))
+                            .unwrap(),
+                            NO_DEPOSIT,
+                            RESOLVE_UNLOCK_GAS,
+                        ))
+                    }
+                }
+            }
+        }
+
+        impl $contract {
+            /// Queues a transfer, the storage it takes is paid from `remaining_deposit` and the
+            /// rest is refunded to `caller_id`.
+            fn enqueue(
+                &mut self,
+                caller_id: &AccountId,
+                remaining_deposit: Balance,
+                token: AccountId,
+                receiver: AccountId,
+                transferable: $transferable,
+                action: connector_base::queue::QueuedAction,
+            ) {
+                let initial_storage = env::storage_usage();
+                let id = self.queue.push(
+                    token,
+                    receiver,
+                    transferable,
+                    action,
+                    env::block_timestamp(),
+                );
+                let required_deposit = Balance::from(env::storage_usage() - initial_storage)
+                    * env::storage_byte_cost();
+                require_err!(
+                    remaining_deposit >= required_deposit,
+                    BridgeError::DepositTooLow
+                );
+                env::log_str(&format!("TransferQueued:{}", id));
+
+                if remaining_deposit > required_deposit {
+                    let refund_promise = env::promise_batch_create(caller_id);
+                    env::promise_batch_action_transfer(
+                        refund_promise,
+                        remaining_deposit - required_deposit,
+                    );
+                }
+            }
         }
     };
 }
@@ -0,0 +1,121 @@
+//! Mints and unlocks above a threshold, held by a connector until a delay passed.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum QueuedAction {
+    /// Mint of the lock event with these params
+    Mint { params: Vec<String> },
+    /// Unlock of the proven outcome with this hex id, refunding the transfer with
+    /// `refund_nonce` if any
+    Unlock {
+        transfer_id: String,
+        #[serde(default)]
+        refund_nonce: Option<U64>,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QueuedTransfer<T> {
+    #[serde(with = "utils::u64_dec_format")]
+    pub id: u64,
+    pub token: AccountId,
+    pub receiver: AccountId,
+    pub transferable: T,
+    pub action: QueuedAction,
+    #[serde(with = "utils::u64_dec_format")]
+    pub executable_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Queue<T> {
+    /// Duration in nanoseconds transfers stay queued
+    delay_ns: u64,
+    /// Volume of a token above which its transfers are queued
+    thresholds: LookupMap<AccountId, U128>,
+    next_id: u64,
+    transfers: UnorderedMap<u64, QueuedTransfer<T>>,
+}
+
+impl<T: BorshDeserialize + BorshSerialize> Queue<T> {
+    pub fn new(thresholds_prefix: &[u8], transfers_prefix: &[u8]) -> Self {
+        Self {
+            delay_ns: 0,
+            thresholds: LookupMap::new(thresholds_prefix.to_vec()),
+            next_id: 0,
+            transfers: UnorderedMap::new(transfers_prefix.to_vec()),
+        }
+    }
+
+    pub fn delay_ns(&self) -> u64 {
+        self.delay_ns
+    }
+
+    pub fn set_delay(&mut self, delay_ns: u64) {
+        self.delay_ns = delay_ns;
+    }
+
+    pub fn threshold(&self, token: &AccountId) -> Option<U128> {
+        self.thresholds.get(token)
+    }
+
+    pub fn set_threshold(&mut self, token: &AccountId, threshold: Option<U128>) {
+        match threshold {
+            Some(threshold) => self.thresholds.insert(token, &threshold),
+            None => self.thresholds.remove(token),
+        };
+    }
+
+    /// Whether a transfer of `volume` of `token` has to be queued.
+    pub fn is_queued(&self, token: &AccountId, volume: u128) -> bool {
+        self.threshold(token)
+            .map_or(false, |threshold| volume > threshold.0)
+    }
+
+    /// Queues a transfer executable once the delay passed from `now`, returns its id.
+    pub fn push(
+        &mut self,
+        token: AccountId,
+        receiver: AccountId,
+        transferable: T,
+        action: QueuedAction,
+        now: u64,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.transfers.insert(
+            &id,
+            &QueuedTransfer {
+                id,
+                token,
+                receiver,
+                transferable,
+                action,
+                executable_at: now.saturating_add(self.delay_ns),
+            },
+        );
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<QueuedTransfer<T>> {
+        self.transfers.get(&id)
+    }
+
+    pub fn take(&mut self, id: u64) -> Option<QueuedTransfer<T>> {
+        self.transfers.remove(&id)
+    }
+
+    pub fn list(&self, from_index: u64, limit: u64) -> Vec<QueuedTransfer<T>> {
+        self.transfers
+            .values()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+}
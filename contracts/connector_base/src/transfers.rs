@@ -0,0 +1,133 @@
+//! Transfers locked on a source connector, followed until the other network acknowledged
+//! minting them or minting failed and they were refunded.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, Vector};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json;
+use near_sdk::{env, AccountId};
+
+/// `msg` of the `ft_transfer_call` or `nft_transfer_call` locking a Token, as a JSON object. Any
+/// other `msg`, such as the other network id it used to hold, keeps the defaults.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde", deny_unknown_fields)]
+pub struct TransferMessage {
+    /// Account receiving the Token on the other network, the sender by default
+    pub receiver_id: Option<AccountId>,
+    pub memo: Option<String>,
+}
+
+impl TransferMessage {
+    /// `None` if `msg` is a JSON object that is not a valid message.
+    pub fn parse(msg: &str) -> Option<Self> {
+        if !msg.trim_start().starts_with('{') {
+            return Some(Self::default());
+        }
+        serde_json::from_str(msg).ok()
+    }
+}
+
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum TransferStatus {
+    /// Locked, minting on the other network was not acknowledged yet
+    Pending,
+    /// Minted on the other network
+    Completed,
+    /// Minting on the other network failed and the Token was returned
+    Refunded,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Transfer<T> {
+    #[serde(with = "utils::u64_dec_format")]
+    pub nonce: u64,
+    pub token: AccountId,
+    pub sender: AccountId,
+    pub transferable: T,
+    pub status: TransferStatus,
+    /// Block timestamp of the lock
+    #[serde(with = "utils::u64_dec_format")]
+    pub locked_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Transfers<T> {
+    next_nonce: u64,
+    transfers: LookupMap<u64, Transfer<T>>,
+    /// Nonces of the transfers of each sender
+    nonces: LookupMap<AccountId, Vector<u64>>,
+    nonces_prefix: Vec<u8>,
+}
+
+impl<T: BorshDeserialize + BorshSerialize> Transfers<T> {
+    pub fn new(transfers_prefix: &[u8], nonces_prefix: &[u8]) -> Self {
+        Self {
+            next_nonce: 0,
+            transfers: LookupMap::new(transfers_prefix.to_vec()),
+            nonces: LookupMap::new(nonces_prefix.to_vec()),
+            nonces_prefix: nonces_prefix.to_vec(),
+        }
+    }
+
+    pub fn get(&self, nonce: u64) -> Option<Transfer<T>> {
+        self.transfers.get(&nonce)
+    }
+
+    /// Records a pending transfer and returns its nonce.
+    pub fn lock(&mut self, sender: &AccountId, token: &AccountId, transferable: T) -> u64 {
+        let nonce = self.next_nonce;
+        self.next_nonce += 1;
+        self.transfers.insert(
+            &nonce,
+            &Transfer {
+                nonce,
+                token: token.clone(),
+                sender: sender.clone(),
+                transferable,
+                status: TransferStatus::Pending,
+                locked_at: env::block_timestamp(),
+            },
+        );
+        let mut nonces = self.nonces.get(sender).unwrap_or_else(|| {
+            let prefix = [&self.nonces_prefix[..], &env::sha256(sender.as_bytes())].concat();
+            Vector::new(prefix)
+        });
+        nonces.push(&nonce);
+        self.nonces.insert(sender, &nonces);
+        nonce
+    }
+
+    /// Moves a pending transfer to `status`, returns `None` if it is not pending.
+    pub fn finish(&mut self, nonce: u64, status: TransferStatus) -> Option<Transfer<T>> {
+        let mut transfer = self
+            .get(nonce)
+            .filter(|transfer| transfer.status == TransferStatus::Pending)?;
+        transfer.status = status;
+        self.transfers.insert(&nonce, &transfer);
+        Some(transfer)
+    }
+
+    /// At most `limit` transfers of `sender` from the `from_index`-th, oldest first, of which
+    /// those with `status` if any.
+    pub fn of(
+        &self,
+        sender: &AccountId,
+        status: Option<TransferStatus>,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<Transfer<T>> {
+        self.nonces.get(sender).map_or_else(Vec::new, |nonces| {
+            nonces
+                .iter()
+                .skip(from_index as usize)
+                .take(limit as usize)
+                .filter_map(|nonce| self.get(nonce))
+                .filter(|transfer| status.map_or(true, |status| transfer.status == status))
+                .collect()
+        })
+    }
+}
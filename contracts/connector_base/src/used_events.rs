@@ -0,0 +1,87 @@
+//! Ids of the events a connector consumed, kept to reject a second proof of the same event.
+//!
+//! When connectors only accept proofs within a validity window, the ids are also grouped in
+//! buckets of the proof timestamp, one window wide. A proof is bound to the timestamp of the
+//! block including its outcome, so once every proof of a bucket expired no event of the bucket
+//! can be proven again and its ids can be pruned.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupSet, TreeMap, Vector};
+use utils::Hash;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct UsedEvents {
+    /// Every used event id
+    events: LookupSet<Hash>,
+    /// Event ids of proofs accepted within a validity window, by bucket
+    buckets: TreeMap<u64, Vector<Hash>>,
+    buckets_prefix: Vec<u8>,
+}
+
+impl UsedEvents {
+    pub fn new(events_prefix: &[u8], buckets_prefix: &[u8]) -> Self {
+        Self {
+            events: LookupSet::new(events_prefix.to_vec()),
+            buckets: TreeMap::new(buckets_prefix.to_vec()),
+            buckets_prefix: buckets_prefix.to_vec(),
+        }
+    }
+
+    pub fn contains(&self, event_id: &Hash) -> bool {
+        self.events.contains(event_id)
+    }
+
+    /// Records an event proven by a proof of `proof_timestamp`. Events accepted without a
+    /// validity window are kept forever.
+    pub fn insert(&mut self, event_id: &Hash, proof_timestamp: u64, validity_ns: Option<u64>) {
+        self.events.insert(event_id);
+        if let Some(validity_ns) = validity_ns {
+            let bucket = proof_timestamp / validity_ns.max(1);
+            let mut events = self.buckets.get(&bucket).unwrap_or_else(|| {
+                let mut prefix = self.buckets_prefix.clone();
+                prefix.push(b'e');
+                prefix.extend(bucket.to_le_bytes());
+                Vector::new(prefix)
+            });
+            events.push(event_id);
+            self.buckets.insert(&bucket, &events);
+        }
+    }
+
+    /// Oldest bucket of which every proof expired at `now`.
+    pub fn expired_bucket(&self, now: u64, validity_ns: u64) -> Option<u64> {
+        let validity_ns = validity_ns.max(1);
+        // the last proof of bucket `b` has timestamp `(b + 1) * validity_ns - 1`
+        self.buckets
+            .min()
+            .filter(|bucket| (bucket + 2).saturating_mul(validity_ns) <= now)
+    }
+
+    /// Removes the ids of at most `limit` events of expired buckets, oldest first. Returns the
+    /// number of removed ids.
+    pub fn prune(&mut self, now: u64, validity_ns: u64, limit: u64) -> u64 {
+        let mut pruned = 0;
+        while pruned < limit {
+            let bucket = match self.expired_bucket(now, validity_ns) {
+                Some(bucket) => bucket,
+                None => break,
+            };
+            let mut events = self.buckets.get(&bucket).unwrap();
+            while pruned < limit {
+                match events.pop() {
+                    Some(event_id) => {
+                        self.events.remove(&event_id);
+                        pruned += 1;
+                    }
+                    None => break,
+                }
+            }
+            if events.is_empty() {
+                self.buckets.remove(&bucket);
+            } else {
+                self.buckets.insert(&bucket, &events);
+            }
+        }
+        pruned
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod claims {
+    mod test {
+        use connector_base::claims::{Claim, Claims};
+        use near_sdk::json_types::U128;
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::{env, testing_env};
+
+        fn claim(transfer_id: &str, amount: u128) -> Claim<U128> {
+            Claim {
+                transfer_id: transfer_id.to_string(),
+                token: "wrap.testnet".parse().unwrap(),
+                transferable: U128(amount),
+            }
+        }
+
+        #[test]
+        fn claims_are_kept_by_account() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut claims = Claims::new(b"l");
+            claims.add(&accounts(0), claim("01", 10));
+            claims.add(&accounts(0), claim("02", 20));
+            claims.add(&accounts(1), claim("03", 30));
+            assert_eq!(claims.of(&accounts(0)), [claim("01", 10), claim("02", 20)]);
+            assert_eq!(claims.of(&accounts(1)), [claim("03", 30)]);
+            assert!(claims.of(&accounts(2)).is_empty());
+        }
+
+        #[test]
+        fn claims_are_taken_once() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut claims = Claims::new(b"l");
+            claims.add(&accounts(0), claim("01", 10));
+            claims.add(&accounts(0), claim("02", 20));
+
+            assert_eq!(claims.take(&accounts(1), "01"), None);
+            assert_eq!(claims.take(&accounts(0), "01"), Some(claim("01", 10)));
+            assert_eq!(claims.take(&accounts(0), "01"), None);
+            assert_eq!(claims.of(&accounts(0)), [claim("02", 20)]);
+            assert_eq!(claims.take(&accounts(0), "02"), Some(claim("02", 20)));
+            assert!(claims.of(&accounts(0)).is_empty());
+        }
+
+        #[test]
+        fn storage_of_a_claim_is_measured_without_other_claims() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut claims = Claims::new(b"l");
+            let initial_storage = env::storage_usage();
+            let max_storage = claims.max_storage_usage(&accounts(0), &claim("01", 10));
+            assert_eq!(env::storage_usage(), initial_storage);
+            assert!(claims.of(&accounts(0)).is_empty());
+
+            claims.add(&accounts(0), claim("01", 10));
+            assert_eq!(env::storage_usage() - initial_storage, max_storage);
+            assert_eq!(claims.max_storage_usage(&accounts(0), &claim("02", 20)), max_storage);
+            assert_eq!(claims.of(&accounts(0)), [claim("01", 10)]);
+            let storage = env::storage_usage();
+            claims.add(&accounts(0), claim("02", 20));
+            assert!(env::storage_usage() - storage < max_storage);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod events {
+    mod test {
+        use connector_base::events::{
+            decode_json, decode_text, domain_event_params, event_params, parse_account,
+            parse_number, ChainDomain, EventError,
+        };
+        use near_sdk::serde_json::{self, Value};
+        use proptest::prelude::*;
+
+        const LOCK_EVENT: &str = "CALIMERO_EVENT_LOCK_FT";
+
+        #[test]
+        fn lock_event_params() {
+            let logs = vec![b"CALIMERO_EVENT_LOCK_FT:usdt.testnet:alice.testnet:100".to_vec()];
+            assert_eq!(
+                event_params(&logs, LOCK_EVENT, 4).unwrap(),
+                [LOCK_EVENT, "usdt.testnet", "alice.testnet", "100"]
+            );
+        }
+
+        fn calimero_domain() -> ChainDomain {
+            ChainDomain { chain_id: "calimero-1".to_string(), peer_chain_id: "testnet".to_string() }
+        }
+
+        #[test]
+        fn domain_event_params_strips_chain_ids() {
+            let logs =
+                vec![b"CALIMERO_EVENT_LOCK_FT:usdt.testnet:alice.testnet:100:testnet:calimero-1"
+                    .to_vec()];
+            assert_eq!(
+                domain_event_params(&logs, LOCK_EVENT, 4, &calimero_domain()).unwrap(),
+                [LOCK_EVENT, "usdt.testnet", "alice.testnet", "100"]
+            );
+        }
+
+        #[test]
+        fn other_domain_is_rejected() {
+            for log in [
+                "CALIMERO_EVENT_LOCK_FT:usdt.testnet:alice.testnet:100:testnet:calimero-2",
+                "CALIMERO_EVENT_LOCK_FT:usdt.testnet:alice.testnet:100:mainnet:calimero-1",
+                // emitted by this chain
+                "CALIMERO_EVENT_LOCK_FT:usdt.testnet:alice.testnet:100:calimero-1:testnet",
+            ] {
+                assert!(matches!(
+                    domain_event_params(
+                        &[log.as_bytes().to_vec()],
+                        LOCK_EVENT,
+                        4,
+                        &calimero_domain()
+                    ),
+                    Err(EventError::WrongDomain { .. })
+                ));
+            }
+            // events without chain ids
+            let logs = vec![b"CALIMERO_EVENT_LOCK_FT:usdt.testnet:alice.testnet:100".to_vec()];
+            assert!(matches!(
+                domain_event_params(&logs, LOCK_EVENT, 4, &calimero_domain()),
+                Err(EventError::UnexpectedEvent { expected: 6, found: 4, .. })
+            ));
+        }
+
+        #[test]
+        fn chain_ids() {
+            let domain = calimero_domain();
+            assert_eq!(domain.event_suffix(), "calimero-1:testnet");
+            assert!(ChainDomain::is_valid_chain_id("calimero-1"));
+            assert!(!ChainDomain::is_valid_chain_id(""));
+            assert!(!ChainDomain::is_valid_chain_id("calimero:1"));
+        }
+
+        #[test]
+        fn missing_log_is_rejected() {
+            assert_eq!(event_params(&[], LOCK_EVENT, 4), Err(EventError::NoLogs));
+        }
+
+        #[test]
+        fn invalid_utf8_is_rejected() {
+            let logs = vec![vec![0xff, 0xfe]];
+            assert_eq!(event_params(&logs, LOCK_EVENT, 4), Err(EventError::NotUtf8));
+        }
+
+        #[test]
+        fn other_event_is_rejected() {
+            let logs = vec![b"CALIMERO_EVENT_BURN_FT:usdt.testnet:alice.testnet:100".to_vec()];
+            assert!(matches!(
+                event_params(&logs, LOCK_EVENT, 4),
+                Err(EventError::UnexpectedEvent { found: 4, .. })
+            ));
+        }
+
+        #[test]
+        fn invalid_fields_are_rejected() {
+            assert!(parse_account("receiver", "Alice!").is_err());
+            assert!(parse_number::<u128>("amount", "-1").is_err());
+            assert!(
+                parse_number::<u128>("amount", "340282366920938463463374607431768211456").is_err()
+            );
+            assert!(decode_text("token id", "not base64!").is_err());
+            // valid base64 which is not UTF-8
+            assert!(decode_text("token id", "//4=").is_err());
+            assert!(decode_json::<Value>("token metadata", "bm90IGpzb24=").is_err());
+        }
+
+        proptest! {
+            #[test]
+            fn event_params_never_panics(
+                logs in proptest::collection::vec(proptest::collection::vec(any::<u8>(), 0..64), 0..3),
+                count in 0usize..8,
+            ) {
+                let _ = event_params(&logs, LOCK_EVENT, count);
+            }
+
+            #[test]
+            fn event_params_round_trip(fields in proptest::collection::vec("[^:]*", 0..6)) {
+                let log = std::iter::once(LOCK_EVENT.to_string())
+                    .chain(fields.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(":");
+                let params = event_params(&[log.into_bytes()], LOCK_EVENT, fields.len() + 1).unwrap();
+                prop_assert_eq!(&params[1..], &fields[..]);
+            }
+
+            #[test]
+            fn field_parsers_never_panic(value in "\\PC*") {
+                let _ = parse_account("receiver", &value);
+                let _ = parse_number::<u128>("amount", &value);
+                let _ = parse_number::<u64>("gas", &value);
+                let _ = decode_text("token id", &value);
+                let _ = decode_json::<Value>("token metadata", &value);
+            }
+
+            #[test]
+            fn amounts_round_trip(amount in any::<u128>()) {
+                prop_assert_eq!(parse_number::<u128>("amount", &amount.to_string()), Ok(amount));
+            }
+
+            #[test]
+            fn encoded_fields_round_trip(text in "\\PC*") {
+                prop_assert_eq!(decode_text("token id", &base64::encode(&text)), Ok(text.clone()));
+                let json = serde_json::to_vec(&Value::String(text.clone())).unwrap();
+                prop_assert_eq!(
+                    decode_json::<Value>("token metadata", &base64::encode(json)),
+                    Ok(Value::String(text))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod fees {
+    mod test {
+        use connector_base::fees::{FeeSchedule, Fees};
+        use near_sdk::json_types::U128;
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::testing_env;
+
+        fn schedule(flat: u128, bps: u16, min: u128, max: Option<u128>) -> FeeSchedule {
+            FeeSchedule { flat: U128(flat), bps, min: U128(min), max: max.map(U128) }
+        }
+
+        #[test]
+        fn fees_are_flat_and_proportional_within_bounds() {
+            assert_eq!(schedule(10, 0, 0, None).fee(1000), 10);
+            assert_eq!(schedule(0, 25, 0, None).fee(1000), 2);
+            assert_eq!(schedule(10, 100, 0, None).fee(1000), 20);
+            assert_eq!(schedule(0, 100, 15, None).fee(1000), 15);
+            assert_eq!(schedule(10, 100, 0, Some(12)).fee(1000), 12);
+            assert_eq!(schedule(0, 10_000, 0, None).fee(u128::MAX), u128::MAX);
+            assert_eq!(schedule(5, 0, 0, None).fee(0), 5);
+        }
+
+        #[test]
+        fn invalid_schedules() {
+            assert!(schedule(0, 10_000, 5, Some(5)).is_valid());
+            assert!(!schedule(0, 10_001, 0, None).is_valid());
+            assert!(!schedule(0, 100, 6, Some(5)).is_valid());
+        }
+
+        #[test]
+        fn fees_accrue_by_token_until_taken() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut fees = Fees::new(b"f", b"e", b"d");
+            assert_eq!(fees.fee(&accounts(0), 1000), 0);
+            fees.set_schedule(&accounts(0), Some(schedule(10, 100, 0, None)));
+            assert_eq!(fees.fee(&accounts(0), 1000), 20);
+
+            fees.accrue(&accounts(0), 20);
+            fees.accrue(&accounts(0), 15);
+            fees.accrue(&accounts(1), 5);
+            assert_eq!(fees.accrued(&accounts(0)), 35);
+            assert!(!fees.take_accrued(&accounts(0), 36));
+            assert!(fees.take_accrued(&accounts(0), 30));
+            assert_eq!(fees.accrued(&accounts(0)), 5);
+            assert_eq!(fees.accrued(&accounts(1)), 5);
+
+            fees.set_schedule(&accounts(0), None);
+            assert_eq!(fees.schedule(&accounts(0)), None);
+        }
+
+        #[test]
+        fn deposits_pay_fees() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut fees = Fees::new(b"f", b"e", b"d");
+            assert!(fees.take_deposit(&accounts(0), 0));
+            assert!(!fees.take_deposit(&accounts(0), 1));
+            fees.deposit(&accounts(0), 100);
+            assert!(fees.take_deposit(&accounts(0), 60));
+            assert!(!fees.take_deposit(&accounts(0), 41));
+            assert_eq!(fees.deposit_of(&accounts(0)), 40);
+            assert!(fees.take_deposit(&accounts(0), 40));
+            assert_eq!(fees.deposit_of(&accounts(0)), 0);
+        }
+    }
+}
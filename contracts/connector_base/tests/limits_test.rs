@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod limits {
+    mod test {
+        use connector_base::limits::{GlobalLimit, Limit, LimitExceeded, Limits};
+        use near_sdk::json_types::U128;
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::testing_env;
+
+        fn limit(max_transfer: Option<u128>, max_volume: Option<u128>) -> Limit {
+            Limit {
+                max_transfer: max_transfer.map(U128),
+                max_volume: max_volume.map(U128),
+                window_ns: 100,
+            }
+        }
+
+        #[test]
+        fn volume_rolls_over_the_window() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut limits = Limits::new(b"r", b"w");
+            limits.set_limit(&accounts(0), Some(limit(None, Some(100))));
+
+            assert_eq!(limits.record(&accounts(0), 60, 1000), Ok(()));
+            assert_eq!(limits.record(&accounts(0), 50, 1050), Err(LimitExceeded::Volume));
+            // the previous window still counts fully at its end
+            assert_eq!(limits.record(&accounts(0), 50, 1100), Err(LimitExceeded::Volume));
+            // and half of it halfway through the next window
+            assert_eq!(limits.volume(&accounts(0), 1150), 30);
+            assert_eq!(limits.record(&accounts(0), 50, 1150), Ok(()));
+            assert_eq!(limits.volume(&accounts(0), 1150), 80);
+            assert_eq!(limits.volume(&accounts(0), 1300), 0);
+            assert_eq!(limits.record(&accounts(0), 100, 1300), Ok(()));
+        }
+
+        #[test]
+        fn transfers_above_the_maximum_are_rejected() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut limits = Limits::new(b"r", b"w");
+            limits.set_limit(&accounts(0), Some(limit(Some(70), None)));
+
+            assert!(limits.allows_transfer(&accounts(0), 70));
+            assert!(!limits.allows_transfer(&accounts(0), 71));
+            assert!(limits.allows_transfer(&accounts(1), 1000));
+            // the maximum is enforced by queueing, not by the rolling volume
+            assert_eq!(limits.record(&accounts(0), 80, 1000), Ok(()));
+            assert_eq!(limits.volume(&accounts(0), 1000), 80);
+            assert!(!Limit { window_ns: 0, ..limit(None, None) }.is_valid());
+        }
+
+        #[test]
+        fn approved_transfers_count_without_checking_the_limits() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut limits = Limits::new(b"r", b"w");
+            limits.set_limit(&accounts(0), Some(limit(Some(70), Some(100))));
+            limits.set_global(Some(GlobalLimit { max_transfers: 1, window_ns: 100 }));
+
+            limits.add(&accounts(0), 150, 1000);
+            assert_eq!(limits.volume(&accounts(0), 1000), 150);
+            assert_eq!(limits.record(&accounts(1), 1, 1010), Err(LimitExceeded::GlobalTransfers));
+            limits.set_global(None);
+            assert_eq!(limits.record(&accounts(0), 1, 1010), Err(LimitExceeded::Volume));
+        }
+
+        #[test]
+        fn global_limit_counts_transfers_of_all_tokens() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut limits = Limits::new(b"r", b"w");
+            let global = GlobalLimit { max_transfers: 2, window_ns: 100 };
+            assert!(global.is_valid());
+            assert!(!GlobalLimit { max_transfers: 2, window_ns: 0 }.is_valid());
+            limits.set_global(Some(global));
+
+            assert_eq!(limits.record(&accounts(0), 1000, 1000), Ok(()));
+            assert_eq!(limits.record(&accounts(1), 1, 1010), Ok(()));
+            assert_eq!(limits.record(&accounts(2), 1, 1020), Err(LimitExceeded::GlobalTransfers));
+            assert_eq!(limits.record(&accounts(2), 1, 1300), Ok(()));
+
+            limits.set_global(None);
+            assert_eq!(limits.global(), None);
+            assert_eq!(limits.record(&accounts(2), 1, 1300), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod locked {
+    mod test {
+        use connector_base::locked::{Locked, LockedToken};
+        use near_sdk::json_types::U128;
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::testing_env;
+
+        #[test]
+        fn releases_can_not_exceed_the_locked_balance() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut locked = Locked::new(b"k", b"h");
+            assert!(!locked.release(&accounts(0), 1));
+            locked.lock(&accounts(0), 300);
+            locked.lock(&accounts(0), 100);
+            locked.lock(&accounts(1), 50);
+            assert_eq!(locked.balance(&accounts(0)), 400);
+
+            assert!(!locked.release(&accounts(0), 401));
+            assert!(locked.release(&accounts(0), 150));
+            assert_eq!(locked.balance(&accounts(0)), 250);
+            assert!(!locked.release(&accounts(1), 250));
+        }
+
+        #[test]
+        fn tokens_released_entirely_are_not_listed() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut locked = Locked::new(b"k", b"h");
+            locked.lock(&accounts(0), 300);
+            locked.lock(&accounts(1), 50);
+            assert_eq!(locked.list(0, 10), vec![(accounts(0), U128(300)), (accounts(1), U128(50))]);
+            assert_eq!(locked.list(1, 10), vec![(accounts(1), U128(50))]);
+
+            assert!(locked.release(&accounts(0), 300));
+            assert_eq!(locked.list(0, 10), vec![(accounts(1), U128(50))]);
+            assert!(locked.release(&accounts(0), 0));
+        }
+
+        fn locked_token(token_id: &str, locked_at: u64) -> LockedToken {
+            LockedToken { token_id: token_id.to_string(), owner: accounts(2), locked_at }
+        }
+
+        #[test]
+        fn nfts_are_held_by_collection() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut locked = Locked::new(b"k", b"h");
+            locked.lock_token(&accounts(0), locked_token("1", 10));
+            locked.lock_token(&accounts(0), locked_token("2", 20));
+            locked.lock_token(&accounts(1), locked_token("1", 30));
+            assert_eq!(locked.balance(&accounts(0)), 2);
+            assert_eq!(locked.token(&accounts(1), "1"), Some(locked_token("1", 30)));
+            assert_eq!(locked.token(&accounts(1), "2"), None);
+            assert_eq!(
+                locked.tokens(&accounts(0), 0, 10),
+                vec![locked_token("1", 10), locked_token("2", 20)]
+            );
+            assert_eq!(locked.tokens(&accounts(0), 1, 1), vec![locked_token("2", 20)]);
+
+            assert_eq!(locked.release_token(&accounts(0), "1"), Some(locked_token("1", 10)));
+            assert_eq!(locked.release_token(&accounts(0), "1"), None);
+            assert_eq!(locked.release_token(&accounts(2), "1"), None);
+            assert_eq!(locked.balance(&accounts(0)), 1);
+            assert_eq!(locked.release_token(&accounts(1), "1"), Some(locked_token("1", 30)));
+            assert_eq!(locked.tokens(&accounts(1), 0, 10), vec![]);
+            assert_eq!(locked.list(0, 10), vec![(accounts(0), U128(1))]);
+        }
+    }
+}
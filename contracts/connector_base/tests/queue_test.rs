@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod queue {
+    mod test {
+        use connector_base::queue::{Queue, QueuedAction};
+        use near_sdk::json_types::U128;
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::testing_env;
+
+        fn unlock(transfer_id: &str) -> QueuedAction {
+            QueuedAction::Unlock { transfer_id: transfer_id.to_string(), refund_nonce: None }
+        }
+
+        #[test]
+        fn transfers_above_the_threshold_are_queued() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut queue: Queue<U128> = Queue::new(b"q", b"i");
+            assert!(!queue.is_queued(&accounts(0), u128::MAX));
+            queue.set_threshold(&accounts(0), Some(U128(300)));
+            assert!(!queue.is_queued(&accounts(0), 300));
+            assert!(queue.is_queued(&accounts(0), 301));
+            assert!(!queue.is_queued(&accounts(1), 301));
+
+            queue.set_threshold(&accounts(0), None);
+            assert_eq!(queue.threshold(&accounts(0)), None);
+            assert!(!queue.is_queued(&accounts(0), 301));
+        }
+
+        #[test]
+        fn queued_transfers_wait_for_the_delay() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut queue = Queue::new(b"q", b"i");
+            queue.set_delay(100);
+            let first = queue.push(accounts(0), accounts(1), U128(400), unlock("aa"), 1000);
+            queue.set_delay(u64::MAX);
+            let second = queue.push(
+                accounts(0),
+                accounts(2),
+                U128(500),
+                QueuedAction::Mint { params: vec!["lock".to_string()] },
+                1000,
+            );
+            assert_eq!((first, second), (0, 1));
+
+            let queued = queue.get(first).unwrap();
+            assert_eq!(queued.executable_at, 1100);
+            assert_eq!(queued.receiver, accounts(1));
+            assert_eq!(queued.action, unlock("aa"));
+            assert_eq!(queue.get(second).unwrap().executable_at, u64::MAX);
+            assert_eq!(queue.list(0, 10).len(), 2);
+            assert_eq!(queue.list(1, 10).len(), 1);
+
+            assert_eq!(queue.take(first).map(|queued| queued.transferable), Some(U128(400)));
+            assert_eq!(queue.take(first), None);
+            assert_eq!(queue.list(0, 10), vec![queue.get(second).unwrap()]);
+            // ids are not reused
+            assert_eq!(queue.push(accounts(0), accounts(1), U128(1), unlock("bb"), 0), 2);
+        }
+    }
+}
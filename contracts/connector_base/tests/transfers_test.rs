@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod transfers {
+    mod test {
+        use connector_base::transfers::{TransferMessage, TransferStatus, Transfers};
+        use near_sdk::json_types::U128;
+        use near_sdk::test_utils::{accounts, VMContextBuilder};
+        use near_sdk::testing_env;
+
+        fn token() -> near_sdk::AccountId {
+            "wrap.testnet".parse().unwrap()
+        }
+
+        #[test]
+        fn locks_get_increasing_nonces() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut transfers = Transfers::new(b"t", b"n");
+            assert_eq!(transfers.lock(&accounts(0), &token(), U128(10)), 0);
+            assert_eq!(transfers.lock(&accounts(1), &token(), U128(20)), 1);
+            assert_eq!(transfers.lock(&accounts(0), &token(), U128(30)), 2);
+
+            let transfer = transfers.get(1).unwrap();
+            assert_eq!(transfer.sender, accounts(1));
+            assert_eq!(transfer.transferable, U128(20));
+            assert_eq!(transfer.status, TransferStatus::Pending);
+            let nonces: Vec<u64> = transfers
+                .of(&accounts(0), None, 0, u64::MAX)
+                .iter()
+                .map(|transfer| transfer.nonce)
+                .collect();
+            assert_eq!(nonces, [0, 2]);
+            let page = transfers.of(&accounts(0), None, 1, 5);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].nonce, 2);
+            assert!(transfers.of(&accounts(2), None, 0, u64::MAX).is_empty());
+        }
+
+        #[test]
+        fn only_pending_transfers_are_finished() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut transfers = Transfers::new(b"t", b"n");
+            transfers.lock(&accounts(0), &token(), U128(10));
+            transfers.lock(&accounts(0), &token(), U128(20));
+
+            let completed = transfers.finish(0, TransferStatus::Completed).unwrap();
+            assert_eq!(completed.status, TransferStatus::Completed);
+            assert_eq!(transfers.finish(0, TransferStatus::Refunded), None);
+            assert_eq!(transfers.finish(5, TransferStatus::Completed), None);
+            assert_eq!(transfers.get(0).unwrap().status, TransferStatus::Completed);
+
+            let pending = transfers.of(&accounts(0), Some(TransferStatus::Pending), 0, u64::MAX);
+            assert_eq!(pending.len(), 1);
+            assert_eq!(pending[0].nonce, 1);
+            assert_eq!(
+                transfers.of(&accounts(0), Some(TransferStatus::Completed), 0, u64::MAX).len(),
+                1
+            );
+            assert!(transfers
+                .of(&accounts(0), Some(TransferStatus::Refunded), 0, u64::MAX)
+                .is_empty());
+        }
+
+        #[test]
+        fn transfer_messages_are_json_objects() {
+            assert_eq!(TransferMessage::parse(""), Some(TransferMessage::default()));
+            assert_eq!(
+                TransferMessage::parse(r#"{"receiver_id":"bob.calimero","memo":"rent: october"}"#),
+                Some(TransferMessage {
+                    receiver_id: Some("bob.calimero".parse().unwrap()),
+                    memo: Some("rent: october".to_string()),
+                })
+            );
+            assert_eq!(TransferMessage::parse(r#"{"memo":"rent"}"#).unwrap().receiver_id, None);
+            // the other network id `msg` used to hold is still accepted
+            assert_eq!(TransferMessage::parse("testnet"), Some(TransferMessage::default()));
+            assert_eq!(TransferMessage::parse(r#"{"receiver_id":"bob.calimero""#), None);
+            assert_eq!(TransferMessage::parse(r#"{"receiver_id":"Bob:calimero"}"#), None);
+            assert_eq!(TransferMessage::parse(r#"{"receiver":"bob.calimero"}"#), None);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod used_events {
+    mod test {
+        use connector_base::used_events::UsedEvents;
+        use near_sdk::env;
+        use near_sdk::test_utils::VMContextBuilder;
+        use near_sdk::testing_env;
+
+        const VALIDITY_NS: u64 = 1_000;
+
+        fn event(id: u8) -> [u8; 32] {
+            [id; 32]
+        }
+
+        #[test]
+        fn events_are_recorded() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut used_events = UsedEvents::new(b"u", b"b");
+            used_events.insert(&event(1), 500, Some(VALIDITY_NS));
+            used_events.insert(&event(2), 500, None);
+            assert!(used_events.contains(&event(1)));
+            assert!(used_events.contains(&event(2)));
+            assert!(!used_events.contains(&event(3)));
+        }
+
+        #[test]
+        fn only_expired_buckets_are_pruned() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut used_events = UsedEvents::new(b"u", b"b");
+            // bucket 0
+            used_events.insert(&event(1), 0, Some(VALIDITY_NS));
+            used_events.insert(&event(2), 999, Some(VALIDITY_NS));
+            // bucket 1
+            used_events.insert(&event(3), 1_000, Some(VALIDITY_NS));
+            // kept forever
+            used_events.insert(&event(4), 0, None);
+
+            // the proof of event 2 is valid until 1999
+            assert_eq!(used_events.expired_bucket(1_999, VALIDITY_NS), None);
+            assert_eq!(used_events.prune(1_999, VALIDITY_NS, 10), 0);
+            assert!(used_events.contains(&event(2)));
+
+            assert_eq!(used_events.expired_bucket(2_000, VALIDITY_NS), Some(0));
+            assert_eq!(used_events.prune(2_000, VALIDITY_NS, 10), 2);
+            assert!(!used_events.contains(&event(1)));
+            assert!(!used_events.contains(&event(2)));
+            assert!(used_events.contains(&event(3)));
+            assert!(used_events.contains(&event(4)));
+
+            assert_eq!(used_events.prune(10_000, VALIDITY_NS, 10), 1);
+            assert!(!used_events.contains(&event(3)));
+            assert!(used_events.contains(&event(4)));
+            assert_eq!(used_events.expired_bucket(10_000, VALIDITY_NS), None);
+        }
+
+        #[test]
+        fn pruning_is_limited() {
+            testing_env!(VMContextBuilder::new().build());
+            let mut used_events = UsedEvents::new(b"u", b"b");
+            for id in 0..5 {
+                used_events.insert(&event(id), 100, Some(VALIDITY_NS));
+            }
+            used_events.insert(&event(5), 1_100, Some(VALIDITY_NS));
+            let storage_with_events = env::storage_usage();
+
+            assert_eq!(used_events.prune(5_000, VALIDITY_NS, 3), 3);
+            assert_eq!(used_events.expired_bucket(5_000, VALIDITY_NS), Some(0));
+            assert_eq!(used_events.prune(5_000, VALIDITY_NS, 3), 3);
+            assert_eq!(used_events.prune(5_000, VALIDITY_NS, 3), 0);
+            assert!((0..6).all(|id| !used_events.contains(&event(id))));
+            assert!(env::storage_usage() < storage_with_events);
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::UnorderedSet;
-use near_sdk::{env, near_bindgen, require, AccountId, PanicOnDefault};
+use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault};
 use regex::Regex;
-use types::ConnectorType;
+use types::{require_err, BridgeError, ConnectorType};
 
 const ALLOW_REGEX_RULES_GETTER_SIZE: usize = 10;
 const DENY_REGEX_ACCOUNT_PER_CONTRACT_GETTER_SIZE: usize = 20;
@@ -35,7 +35,7 @@ impl ConnectorPermissions {
         nft_connector_account: AccountId,
         xsc_connector_account: AccountId,
     ) -> Self {
-        require!(!env::state_exists(), "Already initialized");
+        require_err!(!env::state_exists(), BridgeError::AlreadyInitialized);
         Self {
             ft_connector_account,
             nft_connector_account,
@@ -50,10 +50,10 @@ impl ConnectorPermissions {
     }
 
     fn require_enough_deposit(&self, current_storage: u128, initial_storage: u128) {
-        require!(
+        require_err!(
             env::attached_deposit()
                 >= env::storage_byte_cost() * (current_storage - initial_storage),
-            "Not enough attached deposit to complete action"
+            BridgeError::InsufficientDeposit
         );
     }
 
@@ -81,10 +81,10 @@ impl ConnectorPermissions {
     // and for XSC additionally all deny rules per contract per account are also removed. Permissions are returned to the
     // initial state (deny all).
     pub fn reset_permissions(&mut self, connector_type: ConnectorType) {
-        assert_eq!(
-            env::predecessor_account_id(),
-            *self.connector_from_type(connector_type),
-            "Only corresponding connector can reset regex rules"
+        require_err!(
+            env::predecessor_account_id() == *self.connector_from_type(connector_type),
+            BridgeError::Unauthorized,
+            "only corresponding connector can reset regex rules"
         );
         match connector_type {
             ConnectorType::FT => self.allow_regex_rules_for_bridging_fts.clear(),
@@ -141,10 +141,16 @@ impl ConnectorPermissions {
         regex_rule: &String,
         connector_type: ConnectorType,
     ) -> bool {
-        assert_eq!(
-            env::predecessor_account_id(),
-            *self.connector_from_type(connector_type),
-            "Only corresponding connector can add regex rules to the rule list"
+        require_err!(
+            env::predecessor_account_id() == *self.connector_from_type(connector_type),
+            BridgeError::Unauthorized,
+            "only corresponding connector can add regex rules to the rule list"
+        );
+        require_err!(
+            Regex::new(regex_rule).is_ok(),
+            BridgeError::InvalidRegexRule,
+            "{}",
+            regex_rule
         );
         let initial_storage = env::storage_usage() as u128;
 
@@ -168,10 +174,10 @@ impl ConnectorPermissions {
         regex_rule: &String,
         connector_type: ConnectorType,
     ) -> bool {
-        assert_eq!(
-            env::predecessor_account_id(),
-            *self.connector_from_type(connector_type),
-            "Only corresponding connector can remove regex rules from the rule list"
+        require_err!(
+            env::predecessor_account_id() == *self.connector_from_type(connector_type),
+            BridgeError::Unauthorized,
+            "only corresponding connector can remove regex rules from the rule list"
         );
         match connector_type {
             ConnectorType::FT => self.allow_regex_rules_for_bridging_fts.remove(regex_rule),
@@ -189,11 +195,19 @@ impl ConnectorPermissions {
         account_regex: &String,
         contract_regex: &String,
     ) -> bool {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.xsc_connector_account,
-            "Only cross shard connector can add accounts regex rules to the deny list for contract regex rule"
+        require_err!(
+            env::predecessor_account_id() == self.xsc_connector_account,
+            BridgeError::Unauthorized,
+            "only cross shard connector can add accounts regex rules to the deny list for contract regex rule"
         );
+        for regex_rule in [account_regex, contract_regex] {
+            require_err!(
+                Regex::new(regex_rule).is_ok(),
+                BridgeError::InvalidRegexRule,
+                "{}",
+                regex_rule
+            );
+        }
         let initial_storage = env::storage_usage() as u128;
 
         let action_success = self.deny_regex_account_per_regex_contract_for_cross_shard_calls
@@ -211,10 +225,10 @@ impl ConnectorPermissions {
         account_regex: &String,
         contract_regex: &String,
     ) -> bool {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.xsc_connector_account,
-            "Only cross shard connector can remove accounts regex rules from the deny list for contract regex rule"
+        require_err!(
+            env::predecessor_account_id() == self.xsc_connector_account,
+            BridgeError::Unauthorized,
+            "only cross shard connector can remove accounts regex rules from the deny list for contract regex rule"
         );
         self.deny_regex_account_per_regex_contract_for_cross_shard_calls
             .remove(&(contract_regex.to_string(), account_regex.to_string()))
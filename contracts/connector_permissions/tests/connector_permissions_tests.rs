@@ -196,6 +196,28 @@ mod connector_permissions {
             assert!(!result.is_success());
         }
 
+        #[tokio::test]
+        #[should_panic(expected = "Regex rule is not valid")]
+        async fn test_add_invalid_allow_regex_rule() {
+            let (worker, connector_permissions_contract) = init().await;
+
+            let sec = workspaces::types::SecretKey::from_seed(workspaces::types::KeyType::ED25519, format!("secret_key_ft_connector_{}", FT_CONNECTOR_ACCOUNT_ID).as_str());
+            let tla = workspaces::AccountId::try_from(FT_CONNECTOR_ACCOUNT_ID.to_string()).unwrap();
+            let connector_account = worker.create_tla(tla, sec).await.unwrap().unwrap();
+
+            connector_account.call(&worker, connector_permissions_contract.id(), "add_allow_regex_rule")
+                .args_json(json!({
+                    "regex_rule": "dev-(",
+                    "connector_type": ConnectorType::FT,
+                }))
+                .unwrap()
+                .gas(parse_gas!("300 Tgas") as u64)
+                .deposit(parse_near!("1"))
+                .transact()
+                .await
+                .unwrap();
+        }
+
         #[tokio::test]
         async fn test_deny_account_for_contract() {
             let (worker, connector_permissions_contract) = init().await;
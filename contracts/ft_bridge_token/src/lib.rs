@@ -13,6 +13,8 @@ use near_sdk::{
     assert_one_yocto, env, ext_contract, near_bindgen, AccountId, Gas, PanicOnDefault, Promise,
     PromiseOrValue, StorageUsage,
 };
+use types::errors::panic_err_with;
+use types::{require_err, BridgeError};
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -37,13 +39,13 @@ trait ExtConnector {
 /// Gas to call burn method on controller.
 const BURN_GAS: Gas = Gas(30_000_000_000_000);
 
-const PAUSE_WITHDRAW: Mask = 1 << 0;
+pub const PAUSE_WITHDRAW: Mask = 1 << 0;
 
 #[near_bindgen]
 impl BridgeToken {
     #[init]
     pub fn new(controller: AccountId) -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+        require_err!(!env::state_exists(), BridgeError::AlreadyInitialized);
         Self {
             controller,
             token: FungibleToken::new(b"t".to_vec()),
@@ -68,8 +70,12 @@ impl BridgeToken {
         icon: Option<String>,
     ) {
         // Only owner can change the metadata
-        assert!(self.controller_or_self(), "Only owner can change FT contract metadata");
-        assert!(!self.metadata_set, "Metadata was already set");
+        require_err!(
+            self.controller_or_self(),
+            BridgeError::Unauthorized,
+            "only owner can change FT contract metadata"
+        );
+        require_err!(!self.metadata_set, BridgeError::MetadataAlreadySet);
 
         self.name = name;
         self.symbol = symbol;
@@ -82,10 +88,10 @@ impl BridgeToken {
 
     #[payable]
     pub fn mint(&mut self, account_id: AccountId, amount: U128) {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.controller,
-            "Only controller can call mint"
+        require_err!(
+            env::predecessor_account_id() == self.controller,
+            BridgeError::Unauthorized,
+            "only controller can call mint"
         );
 
         self.storage_deposit(Some(account_id.clone()), None);
@@ -142,12 +148,12 @@ impl StorageManagement for BridgeToken {
 
     #[payable]
     fn storage_withdraw(&mut self, _amount: Option<U128>) -> StorageBalance {
-        panic!("storage_withdraw method is not supported for bridged tokens.")
+        panic_err_with(BridgeError::NotSupported, "storage_withdraw for bridged tokens")
     }
 
     #[payable]
     fn storage_unregister(&mut self, _force: Option<bool>) -> bool {
-        panic!("storage_unregister method is not supported for bridged tokens.")
+        panic_err_with(BridgeError::NotSupported, "storage_unregister for bridged tokens")
     }
 
     fn storage_balance_bounds(&self) -> StorageBalanceBounds {
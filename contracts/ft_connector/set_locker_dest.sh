@@ -13,3 +13,9 @@ near call "$contract_id" set_locker \
   --args  "{\"locker_account\":\"$locker\"}" \
   --nodeUrl "https://api.development.calimero.network/api/v1/shards/$1-calimero-testnet/neard-rpc" \
   --networkId "$1"
+
+near call "$contract_id" set_chain_domain \
+  --accountId "$contract_id" \
+  --args  "{\"chain_id\":\"$1-calimero-testnet\",\"peer_chain_id\":\"testnet\"}" \
+  --nodeUrl "https://api.development.calimero.network/api/v1/shards/$1-calimero-testnet/neard-rpc" \
+  --networkId "$1"
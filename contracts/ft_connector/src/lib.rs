@@ -1,16 +1,18 @@
 use admin_controlled::Mask;
+use connector_base::events::{self, ChainDomain, EventError};
+use connector_base::transfers::TransferMessage;
 use connector_base::{
-    DeployerAware, OtherNetworkAware, OtherNetworkTokenAware, TokenMint, TokenUnlock,
+    DeployerAware, FeeAware, LimitAware, OtherNetworkAware, OtherNetworkTokenAware, TokenMint,
+    TokenUnlock, TransferQueue,
 };
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, LookupSet};
 use near_sdk::json_types::U128;
 use near_sdk::serde_json;
-use near_sdk::{
-    env, near_bindgen, require, AccountId, Balance, Gas, PanicOnDefault, PromiseResult,
-};
-use types::{ConnectorType, FullOutcomeProof};
-use utils::{hashes, Hash};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Gas, PanicOnDefault, PromiseResult};
+use types::errors::panic_err;
+use types::{require_err, BridgeError, ConnectorType, EncodedOutcomeProof, FullOutcomeProof};
+use utils::hashes;
 
 use near_sdk::PublicKey;
 
@@ -23,6 +25,12 @@ const BRIDGE_TOKEN_COMPLETE: Gas = Gas(20_000_000_000_000);
 /// Gas to call mint method on bridge token.
 const MINT_GAS: Gas = Gas(30_000_000_000_000);
 
+/// Gas to call resolve_mint method.
+const RESOLVE_MINT_GAS: Gas = Gas(10_000_000_000_000);
+
+/// Gas to call resolve_unlock and resolve_claim methods.
+const RESOLVE_UNLOCK_GAS: Gas = Gas(10_000_000_000_000);
+
 /// Gas to call ft_transfer_call when the target of deposit is a contract
 const TRANSFER_CALL_GAS: Gas = Gas(80_000_000_000_000);
 
@@ -48,31 +56,58 @@ const PERMISSIONS_VERIFICATION_GAS: Gas = Gas(40_000_000_000_000);
 pub const PAUSE_DEPLOY_TOKEN: Mask = 1 << 0;
 pub const PAUSE_MINT: Mask = 1 << 1;
 pub const PAUSE_LOCK: Mask = 1 << 2;
+pub const PAUSE_UNLOCK: Mask = 1 << 3;
 
 const CALIMERO_EVENT_DEPLOY_FT: &str = "CALIMERO_EVENT_DEPLOY_FT";
 const CALIMERO_EVENT_BURN_FT: &str = "CALIMERO_EVENT_BURN_FT";
 const CALIMERO_EVENT_LOCK_FT: &str = "CALIMERO_EVENT_LOCK_FT";
+const CALIMERO_EVENT_MINTED_FT: &str = "CALIMERO_EVENT_MINTED_FT";
+const CALIMERO_EVENT_MINT_FAILED_FT: &str = "CALIMERO_EVENT_MINT_FAILED_FT";
+const CALIMERO_EVENT_CANCEL_FT: &str = "CALIMERO_EVENT_CANCEL_FT";
+/// Alert logged when a mint or unlock exceeds a limit and pauses the connector
+const CALIMERO_EVENT_LIMIT_EXCEEDED_FT: &str = "CALIMERO_EVENT_LIMIT_EXCEEDED_FT";
+/// Logged when the guardian drops a queued mint or unlock
+const CALIMERO_EVENT_QUEUE_CANCELLED_FT: &str = "CALIMERO_EVENT_QUEUE_CANCELLED_FT";
 
 connector_base::impl_deployer_aware!(FungibleTokenConnector, CALIMERO_EVENT_DEPLOY_FT);
 connector_base::impl_other_network_aware!(FungibleTokenConnector);
-connector_base::impl_other_network_token_aware!(FungibleTokenConnector, CALIMERO_EVENT_DEPLOY_FT);
-connector_base::impl_token_mint!(FungibleTokenConnector);
+connector_base::impl_other_network_token_aware!(
+    FungibleTokenConnector,
+    CALIMERO_EVENT_DEPLOY_FT,
+    U128
+);
+connector_base::impl_token_mint!(
+    FungibleTokenConnector,
+    CALIMERO_EVENT_MINTED_FT,
+    CALIMERO_EVENT_MINT_FAILED_FT,
+    CALIMERO_EVENT_CANCEL_FT
+);
 connector_base::impl_token_unlock!(
     FungibleTokenConnector,
     CALIMERO_EVENT_BURN_FT,
+    CALIMERO_EVENT_MINTED_FT,
+    CALIMERO_EVENT_MINT_FAILED_FT,
+    CALIMERO_EVENT_CANCEL_FT,
     U128,
     "ft_transfer"
 );
+connector_base::impl_fee_aware!(FungibleTokenConnector);
+connector_base::impl_limit_aware!(FungibleTokenConnector, CALIMERO_EVENT_LIMIT_EXCEEDED_FT);
+connector_base::impl_transfer_queue!(
+    FungibleTokenConnector,
+    U128,
+    CALIMERO_EVENT_QUEUE_CANCELLED_FT
+);
 
 #[near_bindgen]
 impl FungibleTokenConnector {
-    /// Emits a calimero lock event if transfer is successful
-    pub fn ft_on_transfer(
-        &mut self,
-        sender_id: AccountId,
-        amount: U128,
-        #[allow(unused_variables)] msg: String,
-    ) {
+    /// Emits a calimero lock event if transfer is successful. `msg` may set the receiver on the
+    /// other network and a memo, see `TransferMessage`.
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) {
+        let message = TransferMessage::parse(&msg)
+            .unwrap_or_else(|| panic_err(BridgeError::InvalidTransferMessage));
+        let receiver_id = message.receiver_id.unwrap_or_else(|| sender_id.clone());
+
         let permission_promise = env::promise_create(
             self.connector_permissions_account.clone(),
             "can_bridge",
@@ -82,20 +117,35 @@ impl FungibleTokenConnector {
         );
 
         self.assert_not_paused(PAUSE_LOCK);
+        connector_base::require_domain(&self.chain_domain);
 
         env::promise_return(env::promise_then(
             permission_promise,
             env::current_account_id(),
             "lock",
-            &serde_json::to_vec(&(sender_id, env::predecessor_account_id(), amount)).unwrap(),
+            &serde_json::to_vec(&(
+                sender_id,
+                env::predecessor_account_id(),
+                amount,
+                receiver_id,
+                message.memo,
+            ))
+            .unwrap(),
             NO_DEPOSIT,
             PERMISSIONS_VERIFICATION_GAS,
         ));
     }
 
     #[private]
-    pub fn lock(&mut self, sender_id: AccountId, ft_contract_id: AccountId, amount: U128) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+    pub fn lock(
+        &mut self,
+        sender_id: AccountId,
+        ft_contract_id: AccountId,
+        amount: U128,
+        receiver_id: AccountId,
+        memo: Option<String>,
+    ) -> U128 {
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         let verification_success = match env::promise_result(0) {
             PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x).unwrap(),
@@ -103,37 +153,114 @@ impl FungibleTokenConnector {
         };
 
         if verification_success {
+            require_err!(
+                self.limits.allows_transfer(&ft_contract_id, amount.0),
+                BridgeError::TransferLimitExceeded
+            );
+            // the fee stays with the connector, only the rest of the amount is bridged
+            let fee = self.fees.fee(&ft_contract_id, amount.0);
+            require_err!(fee < amount.0, BridgeError::FeeNotCovered);
+            let initial_storage = env::storage_usage();
+            self.fees.accrue(&ft_contract_id, fee);
+            let amount = U128(amount.0 - fee);
+
+            self.locked.lock(&ft_contract_id, amount.0);
+            let nonce = self.transfers.lock(&sender_id, &ft_contract_id, amount);
+            // the storage of the lock is paid from the fee deposit of the sender
+            let storage_cost = Balance::from(env::storage_usage().saturating_sub(initial_storage))
+                * env::storage_byte_cost();
+            require_err!(
+                self.fees.take_deposit(&sender_id, storage_cost),
+                BridgeError::LockStorageNotCovered
+            );
             env::log_str(&format!(
-                "{}:{}:{}:{}",
+                "{}:{}:{}:{}:{}:{}:{}:{}",
                 CALIMERO_EVENT_LOCK_FT,
-                ft_contract_id, 
-                sender_id, 
-                amount.0
+                ft_contract_id,
+                sender_id,
+                amount.0,
+                receiver_id,
+                base64::encode(memo.unwrap_or_default()),
+                nonce,
+                connector_base::require_domain(&self.chain_domain).event_suffix()
             ));
 
-            env::value_return(&serde_json::to_vec(&U128(0).0.to_string()).unwrap());
+            U128(0)
         } else {
-            env::value_return(&serde_json::to_vec(&amount.0.to_string()).unwrap());
+            amount
         }
     }
 
+    pub fn locked_balance(&self, token: AccountId) -> U128 {
+        U128(self.locked.balance(&token))
+    }
+
+    /// Locked balances of all tokens, to reconcile with the supply of their bridge tokens.
+    pub fn all_locked_tokens(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, U128)> {
+        self.locked.list(from_index.unwrap_or(0), limit.unwrap_or(u64::MAX))
+    }
+
+    /// Releases an unlocked or refunded amount, before fees, from the locked balance.
+    fn release_locked(&mut self, token: &AccountId, amount: &U128) {
+        require_err!(self.locked.release(token, amount.0), BridgeError::UnlockExceedsLocked);
+    }
+
+    /// Deducts the fee of `token` from an unlocked amount.
+    fn charge_unlock_fee(&mut self, token: &AccountId, amount: U128) -> U128 {
+        let fee = self.fees.fee(token, amount.0).min(amount.0);
+        self.fees.accrue(token, fee);
+        U128(amount.0 - fee)
+    }
+
+    /// Fees are accrued in the token charged.
+    fn transfer_fees(
+        token: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+    ) -> near_sdk::PromiseIndex {
+        env::promise_create(
+            token.clone(),
+            "ft_transfer",
+            &serde_json::to_vec(&Self::token_unlock_params(
+                receiver_id.clone(),
+                U128(amount),
+                "Bridge fees".to_string(),
+            ))
+            .unwrap(),
+            near_sdk::ONE_YOCTO,
+            MINT_GAS,
+        )
+    }
+
+    fn transfer_volume(amount: &U128) -> u128 {
+        amount.0
+    }
+
     fn transform_transferable(amount: U128) -> u128 {
         amount.0
     }
 
-    fn parse_transferable(amount: String) -> U128 {
-        U128(amount.parse::<u128>().unwrap())
+    fn parse_transferable(amount: &str) -> Result<U128, EventError> {
+        events::parse_number("amount", amount).map(U128)
     }
 
-    fn verify_mint_params(params: Vec<String>) {
-        require!(
-            params.len() == 4 && params[0] == CALIMERO_EVENT_LOCK_FT,
-            "Untrusted proof, lock receipt proof required"
-        );
+    /// Params of the lock event, checked to be valid for `token_mint_params`.
+    fn mint_params(logs: &[Vec<u8>], domain: &ChainDomain) -> Result<Vec<String>, EventError> {
+        let params = events::domain_event_params(logs, CALIMERO_EVENT_LOCK_FT, 7, domain)?;
+        Self::token_mint_params(params.clone())?;
+        events::decode_text("memo", &params[5])?;
+        events::parse_number::<u64>("nonce", &params[6])?;
+        Ok(params)
     }
 
-    fn token_mint_params(params: Vec<String>) -> near_sdk::serde_json::Value {
-        serde_json::json!({ "account_id": params[2], "amount": U128(params[3].parse::<u128>().unwrap())})
+    fn token_mint_params(params: Vec<String>) -> Result<near_sdk::serde_json::Value, EventError> {
+        let receiver = events::parse_account("receiver", &params[4])?;
+        let amount = Self::parse_transferable(&params[3])?;
+        Ok(serde_json::json!({ "account_id": receiver, "amount": amount }))
     }
 
     fn token_unlock_params(
@@ -5,7 +5,7 @@ mod connector {
         use near_sdk::serde_json;
         use near_sdk::serde_json::json;
         use near_units::{parse_gas, parse_near};
-        use test_utils::file_as_json;
+        use test_utils::{file_as_json, with_chain_ids, with_nonce, with_receiver};
         use utils::hashes::decode_hex;
         use types::FullOutcomeProof;
         use workspaces::prelude::*;
@@ -148,6 +148,17 @@ mod connector {
                 .await
                 .unwrap();
 
+            connector
+                .call(&worker, "set_chain_domain")
+                .args_json(json!({
+                    "chain_id": "calimero",
+                    "peer_chain_id": "testnet",
+                }))
+                .unwrap()
+                .transact()
+                .await
+                .unwrap();
+
 
             let deploy_token_execution_details = connector
                 .call(&worker, "deploy_bridge_token")
@@ -234,11 +245,13 @@ mod connector {
 
             // verify burn event happened, this event is emitted from the ft_connector contract
             let parts: Vec<&str> = logs_from_withdraw[0].split(":").collect();
-            assert!(parts.len() == 4);
+            assert!(parts.len() == 6);
             assert!(parts[0] == "CALIMERO_EVENT_BURN_FT");
             assert!(parts[1] == bridged_ft_contract_id_str);
             assert!(parts[2] == alice_account.id().to_string());
             assert!(parts[3] == withdraw_value.to_string());
+            assert!(parts[4] == "calimero");
+            assert!(parts[5] == "testnet");
 
             let balance_after_burn: String = worker.view(
                 bridged_ft_contract_id,
@@ -254,7 +267,7 @@ mod connector {
 
         async fn mint_case1() -> (Worker<Sandbox>, Contract, Contract, Contract, FullOutcomeProof) {
             let (worker, prover, connector, deployer) = init().await;
-            let proof = file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof.json").unwrap();
+            let proof = with_chain_ids(with_nonce(with_receiver(file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof.json").unwrap()), 0), "testnet", "calimero");
             mint(
                 &worker, 
                 &prover, 
@@ -270,7 +283,7 @@ mod connector {
 
         async fn mint_case2() -> (Worker<Sandbox>, Contract, Contract, Contract, FullOutcomeProof) {
             let (worker, prover, connector, deployer) = init().await;
-            let proof = file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof2.json").unwrap();
+            let proof = with_chain_ids(with_nonce(with_receiver(file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof2.json").unwrap()), 0), "testnet", "calimero");
             mint(
                 &worker, 
                 &prover, 
@@ -286,7 +299,7 @@ mod connector {
 
         async fn mint_case3() -> (Worker<Sandbox>, Contract, Contract, Contract, FullOutcomeProof) {
             let (worker, prover, connector, deployer) = init().await;
-            let proof = file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof3.json").unwrap();
+            let proof = with_chain_ids(with_nonce(with_receiver(file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof3.json").unwrap()), 0), "testnet", "calimero");
             mint(
                 &worker, 
                 &prover, 
@@ -337,7 +350,7 @@ mod connector {
         #[should_panic(expected = "paused")]
         async fn test_mint_paused() {
             let (worker, prover, connector, _deployer) = init().await;
-            let proof = file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof.json").unwrap();
+            let proof = with_chain_ids(with_nonce(with_receiver(file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof.json").unwrap()), 0), "testnet", "calimero");
 
             // pause minting on connector
             connector
@@ -366,7 +379,7 @@ mod connector {
         #[should_panic(expected = "paused")]
         async fn test_deploy_bridge_token_paused() {
             let (worker, prover, connector, _deployer) = init().await;
-            let proof = file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof.json").unwrap();
+            let proof = with_chain_ids(with_nonce(with_receiver(file_as_json::<FullOutcomeProof>("destination_test_assets/lock_proof.json").unwrap()), 0), "testnet", "calimero");
 
             // pause minting on connector
             connector
@@ -4,7 +4,7 @@ mod connector {
         use near_sdk::serde_json;
         use near_sdk::serde_json::json;
         use near_units::{parse_gas, parse_near};
-        use test_utils::file_as_json;
+        use test_utils::{file_as_json, with_chain_ids};
         use utils::hashes::{decode_hex, encode_hex};
         use utils::Hash;
         use types::{FullOutcomeProof, ConnectorType};
@@ -103,6 +103,17 @@ mod connector {
                 .await
                 .unwrap();
 
+            connector_contract
+                .call(&worker, "set_chain_domain")
+                .args_json(json!({
+                    "chain_id": "testnet",
+                    "peer_chain_id": "calimero",
+                }))
+                .unwrap()
+                .transact()
+                .await
+                .unwrap();
+
             fungible_token_contract
                 .call(&worker, "new")
                 .args_json(json!({
@@ -181,6 +192,15 @@ mod connector {
         }
 
         async fn lock_ft(worker: &Worker<Sandbox>, _prover: &Contract, connector: &Contract, fungible_token: &Contract, account: &Account) -> CallExecutionDetails {
+            // the storage of the lock is paid from the fee deposit
+            account.call(&worker, connector.id(), "deposit_fees")
+                .args_json(json!({}))
+                .unwrap()
+                .deposit(parse_near!("0.1 NEAR") as u128)
+                .transact()
+                .await
+                .unwrap();
+
             account.call(&worker, fungible_token.id(), "ft_transfer_call")
                 .args_json(json!({
                 "receiver_id": connector.id(),
@@ -208,7 +228,7 @@ mod connector {
                 .await
                 .unwrap();
 
-            let deploy_proof = &file_as_json::<FullOutcomeProof>("source_test_assets/deploy_proof.json").unwrap();
+            let deploy_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/deploy_proof.json").unwrap(), "calimero", "testnet");
 
             prover
                 .call(&worker, "prove_outcome")
@@ -295,7 +315,7 @@ mod connector {
             let register_execution_details = register_ft(&worker, &prover, &connector, &fungible_token).await;
             assert!(register_execution_details.is_success());
 
-            let burn_proof = &file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap();
+            let burn_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap(), "calimero", "testnet");
             let unlock_execution_details = unlock_ft(&worker, &prover, &connector, &fungible_token, &burn_proof).await;
             assert!(unlock_execution_details.is_success());
 
@@ -330,11 +350,16 @@ mod connector {
 
             // verify lock event happened, this event is emitted from the ft_connector contract
             let parts: Vec<&str> = lock_execution_details.logs()[1].split(":").collect();
-            assert!(parts.len() == 4);
+            assert!(parts.len() == 9);
             assert!(parts[0] == "CALIMERO_EVENT_LOCK_FT");
             assert!(parts[1] == fungible_token.id().to_string());
             assert!(parts[2] == ALICE_ACCOUNT_ID);
             assert!(parts[3] == "12345");
+            assert!(parts[4] == ALICE_ACCOUNT_ID);
+            assert!(parts[5] == "");
+            assert!(parts[6] == "0");
+            assert!(parts[7] == "testnet");
+            assert!(parts[8] == "calimero");
         }
 
         #[tokio::test]
@@ -353,6 +378,18 @@ mod connector {
             unlock_ft(&worker, &prover, &connector, &fungible_token, &used_proof).await;
         }
 
+        #[tokio::test]
+        #[should_panic(expected = "Unlock exceeds the locked balance")]
+        async fn test_unlock_exceeding_locked_panics() {
+            let (worker, prover, connector, fungible_token, _connector_permissions) = init().await;
+            let register_execution_details = register_ft(&worker, &prover, &connector, &fungible_token).await;
+            assert!(register_execution_details.is_success());
+
+            // should panic since nothing was locked for the burnt tokens
+            let burn_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap(), "calimero", "testnet");
+            unlock_ft(&worker, &prover, &connector, &fungible_token, &burn_proof).await;
+        }
+
         #[tokio::test]
         async fn test_on_lock_paused_should_refund() {
             let (worker, prover, connector, fungible_token, _connector_permissions) = init().await;
@@ -457,11 +494,16 @@ mod connector {
 
             // verify lock event passed, this event is emitted from the ft_connector contract
             let parts: Vec<&str> = second_lock_result.logs()[1].split(":").collect();
-            assert!(parts.len() == 4);
+            assert!(parts.len() == 9);
             assert!(parts[0] == "CALIMERO_EVENT_LOCK_FT");
             assert!(parts[1] == fungible_token.id().to_string());
             assert!(parts[2] == ALICE_ACCOUNT_ID);
             assert!(parts[3] == "12345");
+            assert!(parts[4] == ALICE_ACCOUNT_ID);
+            assert!(parts[5] == "");
+            assert!(parts[6] == "0");
+            assert!(parts[7] == "testnet");
+            assert!(parts[8] == "calimero");
         }
     }
 }
@@ -0,0 +1,41 @@
+#![no_main]
+
+use connector_base::events;
+use libfuzzer_sys::fuzz_target;
+use near_sdk::serde_json::Value;
+
+// every event consumed by the connectors, with its number of fields before the chain ids
+const EVENTS: [(&str, usize); 12] = [
+    ("CALIMERO_EVENT_DEPLOY_FT", 3),
+    ("CALIMERO_EVENT_LOCK_FT", 7),
+    ("CALIMERO_EVENT_BURN_FT", 4),
+    ("CALIMERO_EVENT_LOCK_NFT", 8),
+    ("CALIMERO_EVENT_MINTED_FT", 5),
+    ("CALIMERO_EVENT_MINT_FAILED_FT", 5),
+    ("CALIMERO_EVENT_MINTED_NFT", 5),
+    ("CALIMERO_EVENT_MINT_FAILED_NFT", 5),
+    ("CALIMERO_EVENT_CANCEL_FT", 5),
+    ("CALIMERO_EVENT_CANCEL_NFT", 5),
+    ("CALIMERO_EVENT_CROSS_CALL", 8),
+    ("CALIMERO_EVENT_CROSS_RESPONSE", 5),
+];
+
+fuzz_target!(|log: &[u8]| {
+    let logs = vec![log.to_vec()];
+    let domain = events::ChainDomain {
+        chain_id: "calimero-1".to_string(),
+        peer_chain_id: "testnet".to_string(),
+    };
+    for (event, count) in EVENTS {
+        let _ = events::event_params(&logs, event, count + 2);
+        if let Ok(params) = events::domain_event_params(&logs, event, count, &domain) {
+            for param in &params[1..] {
+                let _ = events::parse_account("account", param);
+                let _ = events::parse_number::<u128>("amount", param);
+                let _ = events::parse_number::<u64>("gas", param);
+                let _ = events::decode_text("token id", param);
+                let _ = events::decode_json::<Value>("token metadata", param);
+            }
+        }
+    }
+});
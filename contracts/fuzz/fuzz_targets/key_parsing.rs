@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use types::signature::{KeyType, PublicKey, SecretKey, Signature};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = s.parse::<PublicKey>();
+        let _ = s.parse::<SecretKey>();
+        let _ = s.parse::<Signature>();
+    }
+    for key_type in [KeyType::ED25519, KeyType::SECP256K1] {
+        if let Ok(signature) = Signature::from_parts(key_type, data) {
+            for public_key in
+                [PublicKey::empty(KeyType::ED25519), PublicKey::empty(KeyType::SECP256K1)]
+            {
+                let _ = signature.verify(&[0; 32], &public_key);
+                let _ = signature.verify(data, &public_key);
+            }
+        }
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::serde_json;
+use utils::merkle::MerklePath;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(path) = MerklePath::try_from_slice(data) {
+        let _ = path.compute_root(&Default::default());
+        let _ = path.verify(&Default::default(), &Default::default());
+    }
+    if let Ok(path) = serde_json::from_slice::<MerklePath>(data) {
+        let _ = path.compute_root(&Default::default());
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use near_sdk::serde_json;
+use types::EncodedOutcomeProof;
+
+fuzz_target!(|data: &[u8]| {
+    let encoded = match serde_json::from_slice::<EncodedOutcomeProof>(data) {
+        Ok(encoded) => encoded,
+        Err(_) => EncodedOutcomeProof::Borsh(data.to_vec()),
+    };
+    if let Ok(proof) = encoded.decode() {
+        let _ = proof.outcome_root();
+        let _ = proof.block_merkle_root();
+    }
+});
@@ -5,10 +5,11 @@ extern crate near_sdk;
 use admin_controlled::Mask;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::Vector;
-use near_sdk::{env, near_bindgen, require, AccountId, PanicOnDefault};
+use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault};
 use std::collections::VecDeque;
 use types::signature::Signature;
-use types::{Block, Epoch, Validator};
+use types::errors::panic_err;
+use types::{require_err, Block, BridgeError, Epoch, Validator};
 use utils::{hashes, Hash, Hashable};
 
 // Current assumptions is that private shard only run max 100 block producers
@@ -102,9 +103,9 @@ impl LightClient {
     /// The first part of initialization -- setting the validators of the current epoch.
     #[private]
     pub fn init_with_validators(&mut self, initial_validators: Vec<Validator>) {
-        require!(
+        require_err!(
             !self.is_initialized() && self.epochs.is_empty(),
-            "Wrong initialization stage"
+            BridgeError::WrongInitializationStage
         );
         for _ in 0..NUM_OF_EPOCHS {
             self.epochs.push(&Epoch {
@@ -123,13 +124,13 @@ impl LightClient {
     /// The second part of the initialization
     #[private]
     pub fn init_with_block(&mut self, block: Block) {
-        require!(
+        require_err!(
             !self.is_initialized() && !self.epochs.is_empty(),
-            "Wrong initialization stage"
+            BridgeError::WrongInitializationStage
         );
-        require!(
+        require_err!(
             block.next_bps.is_some(),
-            "Initialization block must contain next_bps"
+            BridgeError::InitBlockWithoutNextBps
         );
         self.initialized = true;
 
@@ -178,13 +179,13 @@ impl LightClient {
     }
 
     pub fn add_light_client_block(&mut self, block: Block) {
-        require!(self.is_initialized(), "Contract is not initialized");
+        require_err!(self.is_initialized(), BridgeError::NotInitialized);
         self.assert_not_paused(PAUSE_ADD_BLOCK_HEADER);
 
         // Check that the new block's height is greater than the current one's.
-        require!(
+        require_err!(
             block.inner_lite.height > self.current_height,
-            "New block must have higher height"
+            BridgeError::BlockHeight
         );
 
         self.next_epoch = if block.inner_lite.epoch_id
@@ -207,8 +208,7 @@ impl LightClient {
             true
         } else {
             // in this case do a revert
-            require!(false, "Epoch id of the block is not valid");
-            false
+            panic_err(BridgeError::EpochId)
         };
 
         // Check that the new block is signed by more than 2/3 of the validators.
@@ -222,9 +222,9 @@ impl LightClient {
         };
 
         // Last block in the epoch might contain extra approvals that light client can ignore.
-        require!(
+        require_err!(
             block.approvals_after_next.len() >= this_epoch.keys.len(),
-            "Approval list is too short"
+            BridgeError::ApprovalsTooShort
         );
 
         // The sum of uint128 values cannot overflow.
@@ -234,15 +234,15 @@ impl LightClient {
                 voted_for += this_epoch.stakes[i];
             }
         }
-        require!(voted_for > this_epoch.stake_threshold, "Too few approvals");
+        require_err!(voted_for > this_epoch.stake_threshold, BridgeError::TooFewApprovals);
 
         // If the block is from the next epoch, make sure that next_bps is supplied and has a correct hash.
         if self.next_epoch {
-            require!(block.next_bps.is_some(), "Next next_bps should not be None");
-            require!(
+            require_err!(block.next_bps.is_some(), BridgeError::NextBpsMissing);
+            require_err!(
                 LightClient::hash_of_block_producers(block.next_bps.as_ref().unwrap())
                     == block.inner_lite.next_bp_hash,
-                "Hash of block producers does not match"
+                BridgeError::NextBpHash
             );
         }
 
@@ -273,9 +273,9 @@ impl LightClient {
                 }
             }
         }
-        require!(
+        require_err!(
             signature_stake > this_epoch.stake_threshold,
-            "Signature stake too low"
+            BridgeError::SignatureStake
         );
 
         if self.next_epoch {
@@ -306,9 +306,9 @@ impl LightClient {
     }
 
     pub fn check_block_producer_signature_in_head(&self, signature_index: usize) -> bool {
-        require!(
+        require_err!(
             self.signature_set & (1 << signature_index) != 0,
-            "No such signature"
+            BridgeError::NoSuchSignature
         );
         let untrusted_epoch = &self
             .epochs
@@ -330,10 +330,8 @@ impl LightClient {
         signature.verify(&message, &untrusted_epoch.keys[signature_index])
     }
 
-    fn hash_of_block_producers(block_producers: &Vec<Validator>) -> Hash {
-        env::sha256(&block_producers.try_to_vec().expect("Failed to serialize"))
-            .try_into()
-            .unwrap()
+    fn hash_of_block_producers(block_producers: &[Validator]) -> Hash {
+        types::block_producers_hash(block_producers)
     }
 }
 
@@ -344,9 +342,9 @@ impl NoBindgen for LightClient {
         mut epoch: Epoch,
         epoch_idx: u64,
     ) {
-        require!(
+        require_err!(
             (block_producers.len() as u32) <= MAX_BLOCK_PRODUCERS,
-            "It is not expected having that many block producers for the provided block"
+            BridgeError::TooManyBlockProducers
         );
 
         epoch.keys = Vec::new();
@@ -6,6 +6,7 @@ mod light_client {
         use light_client::{LightClient, PAUSE_ADD_BLOCK_HEADER};
         use near_sdk::test_utils::{accounts, VMContextBuilder};
         use near_sdk::{testing_env, AccountId};
+        use test_utils::chain::ChainGenerator;
         use test_utils::file_as_json;
         use types::signature::{ED25519SecretKey, SecretKey};
         use types::{Block, Signature, Validator};
@@ -733,5 +734,40 @@ mod light_client {
                     == "62766c842f2e34d3454e4a21c98c7b28b45dc102aeec5ef5ec3342f3669e68ec"
             );
         }
+
+        #[test]
+        fn synthetic_chain_across_epochs() {
+            let mut chain = ChainGenerator::new("near", 1000, 10);
+            chain.produce_blocks(45);
+
+            testing_env!(get_context(accounts(0), 1000, 1000).build());
+            let mut bridge = init(None);
+            bridge.init_with_validators(chain.validators(0));
+            bridge.init_with_block(chain.light_client_block(1002));
+
+            // one block per epoch, every one hands over to a new validator set
+            for height in [1015, 1023, 1031, 1040] {
+                testing_env!(get_context(accounts(0), height, height).build());
+                bridge.add_light_client_block(chain.light_client_block(height));
+                assert_eq!(bridge.block_hashes(height), Some(chain.block_hash(height)));
+                assert_eq!(
+                    bridge.block_merkle_roots(height),
+                    Some(chain.block_merkle_root(height))
+                );
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "Epoch id of the block is not valid")]
+        fn synthetic_chain_skip_epoch_panic() {
+            let mut chain = ChainGenerator::new("near", 1000, 10);
+            chain.produce_blocks(30);
+
+            testing_env!(get_context(accounts(0), 1000, 1000).build());
+            let mut bridge = init(None);
+            bridge.init_with_validators(chain.validators(0));
+            bridge.init_with_block(chain.light_client_block(1002));
+            bridge.add_light_client_block(chain.light_client_block(1025));
+        }
     }
 }
@@ -1,14 +1,50 @@
 extern crate near_sdk;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{near_bindgen, PanicOnDefault};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault};
 use std::collections::HashMap;
 use utils::Hash;
 
+/// How `block_merkle_roots` answers.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Behavior {
+    /// Returns the merkle root added for the height, if any.
+    Success,
+    /// Returns a merkle root no proof matches.
+    False,
+    /// The call fails.
+    Panic,
+    /// Returns `None` as the real light client does for heights it no longer keeps.
+    HeightPruned,
+}
+
+/// Most recent `block_merkle_roots` calls kept while recording.
+pub const MAX_CALLS: usize = 100;
+
+/// Storage key of the recorded calls, apart from the state as `block_merkle_roots` is a view and
+/// does not write it.
+const CALLS_KEY: &[u8] = b"calls";
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BlockMerkleRootsCall {
+    pub predecessor_id: AccountId,
+    pub height: u64,
+    pub behavior: Behavior,
+}
+
 #[near_bindgen]
 #[derive(PanicOnDefault, BorshDeserialize, BorshSerialize)]
 pub struct MockLightClient {
     merkle_roots: HashMap<u64, Hash>,
+    height_behaviors: HashMap<u64, Behavior>,
+    default_behavior: Behavior,
+    /// Whether `block_merkle_roots` records its calls, which makes it write to storage.
+    record_calls: bool,
 }
 
 #[near_bindgen]
@@ -17,6 +53,9 @@ impl MockLightClient {
     pub fn new() -> Self {
         MockLightClient {
             merkle_roots: HashMap::new(),
+            height_behaviors: HashMap::new(),
+            default_behavior: Behavior::Success,
+            record_calls: false,
         }
     }
 
@@ -24,7 +63,54 @@ impl MockLightClient {
         self.merkle_roots.insert(*height, *hash);
     }
 
+    pub fn set_height_behavior(&mut self, height: u64, behavior: Behavior) {
+        self.height_behaviors.insert(height, behavior);
+    }
+
+    pub fn set_default_behavior(&mut self, behavior: Behavior) {
+        self.default_behavior = behavior;
+    }
+
+    pub fn set_record_calls(&mut self, record_calls: bool) {
+        self.record_calls = record_calls;
+    }
+
+    pub fn clear_calls(&mut self) {
+        env::storage_remove(CALLS_KEY);
+    }
+
+    /// The last `MAX_CALLS` `block_merkle_roots` calls made while recording, in order, with the
+    /// behavior they got. Calls that panicked are missing, their changes are reverted.
+    pub fn get_calls(&self) -> Vec<BlockMerkleRootsCall> {
+        env::storage_read(CALLS_KEY)
+            .map(|calls| Vec::try_from_slice(&calls).unwrap())
+            .unwrap_or_default()
+    }
+
     pub fn block_merkle_roots(&self, height: u64) -> Option<Hash> {
-        self.merkle_roots.get(&height).copied()
+        let behavior = self
+            .height_behaviors
+            .get(&height)
+            .copied()
+            .unwrap_or(self.default_behavior);
+        if self.record_calls {
+            let mut calls = self.get_calls();
+            if calls.len() == MAX_CALLS {
+                calls.remove(0);
+            }
+            calls.push(BlockMerkleRootsCall {
+                predecessor_id: env::predecessor_account_id(),
+                height,
+                behavior,
+            });
+            env::storage_write(CALLS_KEY, &calls.try_to_vec().unwrap());
+        }
+
+        match behavior {
+            Behavior::Success => self.merkle_roots.get(&height).copied(),
+            Behavior::False => Some(Hash::default()),
+            Behavior::Panic => panic!("MockLightClient: configured to panic"),
+            Behavior::HeightPruned => None,
+        }
     }
 }
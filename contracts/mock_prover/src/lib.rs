@@ -1,15 +1,54 @@
 extern crate near_sdk;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{env, near_bindgen, serde_json, PanicOnDefault};
-use std::collections::HashSet;
-use types::FullOutcomeProof;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, serde_json, AccountId, PanicOnDefault};
+use std::collections::{HashMap, HashSet};
+use types::errors::panic_err;
+use types::{BridgeError, EncodedOutcomeProof};
 use utils::Hash;
 
+/// How `prove_outcome` answers.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Behavior {
+    /// Resolves to `true`.
+    Success,
+    /// Resolves to `false`.
+    False,
+    /// The promise fails.
+    Panic,
+    /// The promise fails as the real prover does when the light client no longer has the height.
+    HeightPruned,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProveOutcomeCall {
+    pub predecessor_id: AccountId,
+    pub outcome_id: Hash,
+    pub block_hash: Hash,
+    pub block_height: u64,
+    /// `None` when the block hash was not approved and no behavior applied.
+    pub behavior: Option<Behavior>,
+}
+
+/// Most recent `prove_outcome` calls kept.
+pub const MAX_CALLS: usize = 100;
+
 #[near_bindgen]
 #[derive(PanicOnDefault, BorshDeserialize, BorshSerialize)]
 pub struct MockProver {
     approved_hashes: HashSet<Hash>,
+    /// Behaviors per outcome id, checked first.
+    proof_behaviors: HashMap<Hash, Behavior>,
+    /// Behaviors per block height, checked after the outcome id.
+    height_behaviors: HashMap<u64, Behavior>,
+    /// Used for proofs that have no behavior and whose block hash is not approved.
+    default_behavior: Option<Behavior>,
+    calls: Vec<ProveOutcomeCall>,
 }
 
 #[near_bindgen]
@@ -18,6 +57,10 @@ impl MockProver {
     pub fn new() -> Self {
         MockProver {
             approved_hashes: HashSet::new(),
+            proof_behaviors: HashMap::new(),
+            height_behaviors: HashMap::new(),
+            default_behavior: None,
+            calls: Vec::new(),
         }
     }
 
@@ -25,21 +68,71 @@ impl MockProver {
         self.approved_hashes.insert(*hash);
     }
 
-    pub fn prove_outcome(&self, full_outcome_proof: FullOutcomeProof, block_height: u64) {
+    pub fn set_proof_behavior(&mut self, outcome_id: Hash, behavior: Behavior) {
+        self.proof_behaviors.insert(outcome_id, behavior);
+    }
+
+    pub fn set_height_behavior(&mut self, height: u64, behavior: Behavior) {
+        self.height_behaviors.insert(height, behavior);
+    }
+
+    pub fn set_default_behavior(&mut self, behavior: Option<Behavior>) {
+        self.default_behavior = behavior;
+    }
+
+    pub fn clear_calls(&mut self) {
+        self.calls.clear();
+    }
+
+    /// The last `MAX_CALLS` `prove_outcome` calls in order, with the behavior they got.
+    pub fn get_calls(&self) -> Vec<ProveOutcomeCall> {
+        self.calls.clone()
+    }
+
+    pub fn prove_outcome(&mut self, full_outcome_proof: EncodedOutcomeProof, block_height: u64) {
+        let full_outcome_proof = full_outcome_proof.decode().unwrap();
+        let outcome_id = full_outcome_proof.outcome_proof.outcome_with_id.id;
+        let block_hash = full_outcome_proof.outcome_proof.block_hash;
+
+        let behavior = self
+            .proof_behaviors
+            .get(&outcome_id)
+            .or_else(|| self.height_behaviors.get(&block_height))
+            .copied()
+            .or_else(|| {
+                if self.approved_hashes.contains(&block_hash) {
+                    Some(Behavior::Success)
+                } else {
+                    self.default_behavior
+                }
+            });
+        if self.calls.len() == MAX_CALLS {
+            self.calls.remove(0);
+        }
+        self.calls.push(ProveOutcomeCall {
+            predecessor_id: env::predecessor_account_id(),
+            outcome_id,
+            block_hash,
+            block_height,
+            behavior,
+        });
+
         env::promise_return(env::promise_create(
             env::current_account_id(),
             "check_hash",
-            &serde_json::to_vec(&(full_outcome_proof.outcome_proof.block_hash, block_height))
-                .unwrap(),
+            &serde_json::to_vec(&(behavior,)).unwrap(),
             0,
             env::prepaid_gas() / 2,
         ));
     }
 
     #[private]
-    pub fn check_hash(&self, hash: Hash, _height: u64) {
-        match self.approved_hashes.get(&hash) {
-            Some(_) => env::value_return(&serde_json::to_vec(&true).unwrap()),
+    pub fn check_hash(&self, behavior: Option<Behavior>) -> bool {
+        match behavior {
+            Some(Behavior::Success) => true,
+            Some(Behavior::False) => false,
+            Some(Behavior::Panic) => panic!("MockProver: configured to panic"),
+            Some(Behavior::HeightPruned) => panic_err(BridgeError::BlockProofInvalid),
             None => panic!("Not approved hash"),
         }
     }
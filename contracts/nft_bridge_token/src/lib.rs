@@ -10,6 +10,7 @@ use near_sdk::{
     assert_one_yocto, env, ext_contract, near_bindgen, AccountId, Gas, PanicOnDefault, Promise,
     PromiseOrValue,
 };
+use types::{require_err, BridgeError};
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -40,7 +41,7 @@ const PAUSE_WITHDRAW: Mask = 1 << 0;
 impl BridgeToken {
     #[init]
     pub fn new(controller: AccountId) -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+        require_err!(!env::state_exists(), BridgeError::AlreadyInitialized);
         Self {
             controller,
             token: NonFungibleToken::new(
@@ -71,8 +72,12 @@ impl BridgeToken {
         base_uri: Option<String>,
     ) {
         // Only owner can change the metadata
-        assert!(self.controller_or_self(), "Only owner can change NFT contract metadata");
-        assert!(!self.metadata_set, "Metadata was already set");
+        require_err!(
+            self.controller_or_self(),
+            BridgeError::Unauthorized,
+            "only owner can change NFT contract metadata"
+        );
+        require_err!(!self.metadata_set, BridgeError::MetadataAlreadySet);
 
         self.name = name;
         self.symbol = symbol;
@@ -90,10 +95,10 @@ impl BridgeToken {
         token_id: TokenId,
         token_metadata: TokenMetadata,
     ) {
-        assert_eq!(
-            env::predecessor_account_id(),
-            self.controller,
-            "Only controller can call mint"
+        require_err!(
+            env::predecessor_account_id() == self.controller,
+            BridgeError::Unauthorized,
+            "only controller can call mint"
         );
         self.token
             .internal_mint(token_id, account_id, Some(token_metadata));
@@ -105,10 +110,10 @@ impl BridgeToken {
         let owner = self.token.owner_by_id.get(&token_id);
 
         assert_one_yocto();
-        assert_eq!(
-            Some(env::predecessor_account_id()),
-            owner,
-            "Only owner can call withdraw"
+        require_err!(
+            Some(env::predecessor_account_id()) == owner,
+            BridgeError::Unauthorized,
+            "only owner can call withdraw"
         );
         let owner = owner.as_ref().unwrap();
 
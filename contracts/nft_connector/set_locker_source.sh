@@ -10,4 +10,8 @@ contract_id=$(cat ../nft_connector/neardev/dev-account)
 
 near call "$contract_id" set_locker \
   --accountId "$contract_id" \
-  --args  "{\"locker_account\":\"$locker\"}" \
+  --args  "{\"locker_account\":\"$locker\"}"
+
+near call "$contract_id" set_chain_domain \
+  --accountId "$contract_id" \
+  --args  "{\"chain_id\":\"testnet\",\"peer_chain_id\":\"$1-calimero-testnet\"}"
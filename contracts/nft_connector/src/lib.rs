@@ -1,17 +1,21 @@
 use admin_controlled::Mask;
+use connector_base::events::{self, ChainDomain, EventError};
+use connector_base::locked::LockedToken;
+use connector_base::transfers::TransferMessage;
 use connector_base::{
-    DeployerAware, OtherNetworkAware, OtherNetworkTokenAware, TokenMint, TokenUnlock,
+    DeployerAware, FeeAware, LimitAware, OtherNetworkAware, OtherNetworkTokenAware, TokenMint,
+    TokenUnlock, TransferQueue,
 };
 use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
 use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::json_types::U128;
 use near_sdk::serde_json;
-use near_sdk::{
-    env, near_bindgen, require, AccountId, Balance, Gas, PanicOnDefault, PromiseResult,
-};
-use types::{ConnectorType, FullOutcomeProof};
-use utils::{hashes, Hash};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Gas, PanicOnDefault, PromiseResult};
+use types::errors::panic_err;
+use types::{require_err, BridgeError, ConnectorType, EncodedOutcomeProof, FullOutcomeProof};
+use utils::hashes;
 
 use near_sdk::PublicKey;
 
@@ -24,6 +28,12 @@ const BRIDGE_TOKEN_COMPLETE: Gas = Gas(20_000_000_000_000);
 /// Gas to call mint method on bridge token.
 const MINT_GAS: Gas = Gas(30_000_000_000_000);
 
+/// Gas to call resolve_mint method.
+const RESOLVE_MINT_GAS: Gas = Gas(10_000_000_000_000);
+
+/// Gas to call resolve_unlock and resolve_claim methods.
+const RESOLVE_UNLOCK_GAS: Gas = Gas(10_000_000_000_000);
+
 /// Gas to call finish deposit method.
 /// This doesn't cover the gas required for calling mint method.
 const FINISH_DEPOSIT_GAS: Gas = Gas(230_000_000_000_000);
@@ -49,29 +59,53 @@ const PERMISSIONS_VERIFICATION_GAS: Gas = Gas(40_000_000_000_000);
 pub const PAUSE_DEPLOY_TOKEN: Mask = 1 << 0;
 pub const PAUSE_MINT: Mask = 1 << 1;
 pub const PAUSE_LOCK: Mask = 1 << 2;
+pub const PAUSE_UNLOCK: Mask = 1 << 3;
 
 const CALIMERO_EVENT_DEPLOY_NFT: &str = "CALIMERO_EVENT_DEPLOY_NFT";
 const CALIMERO_EVENT_BURN_NFT: &str = "CALIMERO_EVENT_BURN_NFT";
 const CALIMERO_EVENT_LOCK_NFT: &str = "CALIMERO_EVENT_LOCK_NFT";
+const CALIMERO_EVENT_MINTED_NFT: &str = "CALIMERO_EVENT_MINTED_NFT";
+const CALIMERO_EVENT_MINT_FAILED_NFT: &str = "CALIMERO_EVENT_MINT_FAILED_NFT";
+const CALIMERO_EVENT_CANCEL_NFT: &str = "CALIMERO_EVENT_CANCEL_NFT";
+/// Alert logged when a mint or unlock exceeds a limit and pauses the connector
+const CALIMERO_EVENT_LIMIT_EXCEEDED_NFT: &str = "CALIMERO_EVENT_LIMIT_EXCEEDED_NFT";
+/// Logged when the guardian drops a queued mint or unlock
+const CALIMERO_EVENT_QUEUE_CANCELLED_NFT: &str = "CALIMERO_EVENT_QUEUE_CANCELLED_NFT";
 
 connector_base::impl_deployer_aware!(NonFungibleTokenConnector, CALIMERO_EVENT_DEPLOY_NFT);
 connector_base::impl_other_network_aware!(NonFungibleTokenConnector);
 connector_base::impl_other_network_token_aware!(
     NonFungibleTokenConnector,
-    CALIMERO_EVENT_DEPLOY_NFT
+    CALIMERO_EVENT_DEPLOY_NFT,
+    TokenId
+);
+connector_base::impl_token_mint!(
+    NonFungibleTokenConnector,
+    CALIMERO_EVENT_MINTED_NFT,
+    CALIMERO_EVENT_MINT_FAILED_NFT,
+    CALIMERO_EVENT_CANCEL_NFT
 );
-connector_base::impl_token_mint!(NonFungibleTokenConnector);
 connector_base::impl_token_unlock!(
     NonFungibleTokenConnector,
     CALIMERO_EVENT_BURN_NFT,
+    CALIMERO_EVENT_MINTED_NFT,
+    CALIMERO_EVENT_MINT_FAILED_NFT,
+    CALIMERO_EVENT_CANCEL_NFT,
     TokenId,
     "nft_transfer"
 );
+connector_base::impl_fee_aware!(NonFungibleTokenConnector);
+connector_base::impl_limit_aware!(NonFungibleTokenConnector, CALIMERO_EVENT_LIMIT_EXCEEDED_NFT);
+connector_base::impl_transfer_queue!(
+    NonFungibleTokenConnector,
+    TokenId,
+    CALIMERO_EVENT_QUEUE_CANCELLED_NFT
+);
 
 #[near_bindgen]
 impl NonFungibleTokenConnector {
-    /// Used when sending NFT to other network
-    /// `msg` is expected to contain valid other network id
+    /// Used when sending NFT to other network. `msg` may set the receiver on the other network
+    /// and a memo, see `TransferMessage`.
     pub fn nft_on_transfer(
         &mut self,
         sender_id: String,
@@ -79,6 +113,11 @@ impl NonFungibleTokenConnector {
         token_id: String,
         msg: String,
     ) {
+        let message = TransferMessage::parse(&msg)
+            .unwrap_or_else(|| panic_err(BridgeError::InvalidTransferMessage));
+        let receiver_id =
+            message.receiver_id.map_or_else(|| previous_owner_id.clone(), String::from);
+
         let promise_nft_token = env::promise_create(
             env::predecessor_account_id(),
             "nft_token",
@@ -88,6 +127,7 @@ impl NonFungibleTokenConnector {
         );
 
         self.assert_not_paused(PAUSE_LOCK);
+        connector_base::require_domain(&self.chain_domain);
 
         env::promise_return(env::promise_then(
             promise_nft_token,
@@ -98,7 +138,8 @@ impl NonFungibleTokenConnector {
                 sender_id,
                 previous_owner_id,
                 token_id,
-                msg,
+                receiver_id,
+                message.memo,
             ))
             .unwrap(),
             NO_DEPOSIT,
@@ -113,9 +154,10 @@ impl NonFungibleTokenConnector {
         #[allow(unused_variables)] sender_id: String,
         previous_owner_id: String,
         token_id: String,
-        #[allow(unused_variables)] msg: String,
+        receiver_id: String,
+        memo: Option<String>,
     ) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         let promise_result = match env::promise_result(0) {
             PromiseResult::Successful(x) => serde_json::from_slice::<Option<Token>>(&x).unwrap(),
@@ -157,7 +199,15 @@ impl NonFungibleTokenConnector {
             permission_promise,
             env::current_account_id(),
             "lock_with_metadata",
-            &serde_json::to_vec(&(token_account, previous_owner_id, token_id, metadata)).unwrap(),
+            &serde_json::to_vec(&(
+                token_account,
+                previous_owner_id,
+                token_id,
+                metadata,
+                receiver_id,
+                memo,
+            ))
+            .unwrap(),
             NO_DEPOSIT,
             env::prepaid_gas() / 3,
         ));
@@ -170,8 +220,10 @@ impl NonFungibleTokenConnector {
         previous_owner_id: String,
         token_id: String,
         metadata: Option<TokenMetadata>,
-    ) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+        receiver_id: String,
+        memo: Option<String>,
+    ) -> bool {
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         let can_bridge_promise_result = match env::promise_result(0) {
             PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x).unwrap(),
@@ -179,47 +231,121 @@ impl NonFungibleTokenConnector {
         };
 
         if can_bridge_promise_result {
+            let token: AccountId = token_account.parse().unwrap();
+            let previous_owner: AccountId = previous_owner_id.parse().unwrap();
+            // fees of NFTs are paid in NEAR, from the fee deposit of the previous owner
+            let fee = self.fees.fee(&token, 0);
+            require_err!(self.fees.take_deposit(&previous_owner, fee), BridgeError::FeeNotCovered);
+            let initial_storage = env::storage_usage();
+            self.fees.accrue(&token, fee);
+
+            self.locked.lock_token(
+                &token,
+                LockedToken {
+                    token_id: token_id.clone(),
+                    owner: previous_owner.clone(),
+                    locked_at: env::block_timestamp(),
+                },
+            );
+            let nonce = self.transfers.lock(&previous_owner, &token, token_id.clone());
+            // and so is the storage of the lock
+            let storage_cost = Balance::from(env::storage_usage().saturating_sub(initial_storage))
+                * env::storage_byte_cost();
+            require_err!(
+                self.fees.take_deposit(&previous_owner, storage_cost),
+                BridgeError::LockStorageNotCovered
+            );
             env::log_str(&format!(
-                "{}:{}:{}:{}:{}",
+                "{}:{}:{}:{}:{}:{}:{}:{}:{}",
                 CALIMERO_EVENT_LOCK_NFT,
                 token_account,
                 previous_owner_id,
                 base64::encode(token_id),
                 base64::encode(serde_json::to_string(&metadata.unwrap()).unwrap()),
+                receiver_id,
+                base64::encode(memo.unwrap_or_default()),
+                nonce,
+                connector_base::require_domain(&self.chain_domain).event_suffix(),
             ));
-            env::value_return(&serde_json::to_vec(&false).unwrap());
+            false
         } else {
-            env::value_return(&serde_json::to_vec(&true).unwrap());
+            true
         }
     }
 
+    pub fn locked_token(&self, collection: AccountId, token_id: TokenId) -> Option<LockedToken> {
+        self.locked.token(&collection, &token_id)
+    }
+
+    /// NFTs held of `collection`.
+    pub fn locked_tokens(
+        &self,
+        collection: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<LockedToken> {
+        self.locked.tokens(&collection, from_index.unwrap_or(0), limit.unwrap_or(u64::MAX))
+    }
+
+    /// Number of NFTs held of every collection.
+    pub fn locked_collections(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, U128)> {
+        self.locked.list(from_index.unwrap_or(0), limit.unwrap_or(u64::MAX))
+    }
+
+    /// Releases an unlocked or refunded NFT, which has to be held.
+    fn release_locked(&mut self, collection: &AccountId, token_id: &TokenId) {
+        require_err!(
+            self.locked.release_token(collection, token_id).is_some(),
+            BridgeError::TokenNotLocked
+        );
+    }
+
+    /// Fees are charged when locking only.
+    fn charge_unlock_fee(&mut self, _token: &AccountId, token_id: TokenId) -> TokenId {
+        token_id
+    }
+
+    /// Fees are accrued in NEAR.
+    fn transfer_fees(
+        _token: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+    ) -> near_sdk::PromiseIndex {
+        let transfer_promise = env::promise_batch_create(receiver_id);
+        env::promise_batch_action_transfer(transfer_promise, amount);
+        transfer_promise
+    }
+
+    fn transfer_volume(_token_id: &TokenId) -> u128 {
+        1
+    }
+
     fn transform_transferable(token_id: String) -> String {
         base64::encode(token_id)
     }
 
-    fn parse_transferable(encoded_token_id: String) -> String {
-        std::str::from_utf8(&base64::decode(encoded_token_id).unwrap())
-            .unwrap()
-            .to_owned()
+    fn parse_transferable(encoded_token_id: &str) -> Result<TokenId, EventError> {
+        events::decode_text("token id", encoded_token_id)
     }
 
-    fn verify_mint_params(params: Vec<String>) {
-        require!(
-            params.len() == 5 && params[0] == CALIMERO_EVENT_LOCK_NFT,
-            "Untrusted proof, lock receipt proof required"
-        );
+    /// Params of the lock event, checked to be valid for `token_mint_params`.
+    fn mint_params(logs: &[Vec<u8>], domain: &ChainDomain) -> Result<Vec<String>, EventError> {
+        let params = events::domain_event_params(logs, CALIMERO_EVENT_LOCK_NFT, 8, domain)?;
+        Self::token_mint_params(params.clone())?;
+        events::decode_text("memo", &params[6])?;
+        events::parse_number::<u64>("nonce", &params[7])?;
+        Ok(params)
     }
 
-    fn token_mint_params(params: Vec<String>) -> near_sdk::serde_json::Value {
-        let nft_token_receiver_account = params[2].clone();
-        let token_id = std::str::from_utf8(&base64::decode(params[3].clone()).unwrap())
-            .unwrap()
-            .to_owned();
-        let token_metadata: TokenMetadata = serde_json::from_str(
-            std::str::from_utf8(&base64::decode(params[4].clone()).unwrap()).unwrap(),
-        )
-        .unwrap();
-        serde_json::json!({ "account_id": nft_token_receiver_account, "token_id": token_id, "token_metadata": token_metadata })
+    fn token_mint_params(params: Vec<String>) -> Result<near_sdk::serde_json::Value, EventError> {
+        let nft_token_receiver_account = events::parse_account("receiver", &params[5])?;
+        let token_id = Self::parse_transferable(&params[3])?;
+        let token_metadata: TokenMetadata = events::decode_json("token metadata", &params[4])?;
+        Ok(serde_json::json!({ "account_id": nft_token_receiver_account, "token_id": token_id, "token_metadata": token_metadata }))
     }
 
     fn token_unlock_params(
@@ -5,7 +5,7 @@ mod connector {
         use near_sdk::json_types::U128;
         use near_sdk::serde_json::json;
         use near_units::{parse_gas, parse_near};
-        use test_utils::file_as_json;
+        use test_utils::{file_as_json, with_chain_ids, with_nonce, with_receiver};
         use utils::hashes::decode_hex;
         use utils::Hash;
         use types::FullOutcomeProof;
@@ -130,7 +130,7 @@ mod connector {
                 .await
                 .unwrap();
 
-            let proof = &file_as_json::<FullOutcomeProof>(&format!("destination_test_assets/{}{}", file_prefix, "proof.json")).unwrap();
+            let proof = &with_chain_ids(with_nonce(with_receiver(file_as_json::<FullOutcomeProof>(&format!("destination_test_assets/{}{}", file_prefix, "proof.json")).unwrap()), 0), "testnet", "calimero");
 
             connector
                 .call(&worker, "set_locker")
@@ -143,6 +143,17 @@ mod connector {
                 .await
                 .unwrap();
 
+            connector
+                .call(&worker, "set_chain_domain")
+                .args_json(json!({
+                    "chain_id": "calimero",
+                    "peer_chain_id": "testnet",
+                }))
+                .unwrap()
+                .transact()
+                .await
+                .unwrap();
+
             connector
                 .call(&worker, "deploy_bridge_token")
                 .args_json(json!({
@@ -278,11 +289,13 @@ mod connector {
 
             // verify burn event happened, this event is emitted from the nft_connector_destination contract
             let parts: Vec<&str> = logs_from_withdraw[1].split(":").collect();
-            assert!(parts.len() == 4);
+            assert!(parts.len() == 6);
             assert!(parts[0] == "CALIMERO_EVENT_BURN_NFT");
             assert!(parts[1] == bridged_nft_contract_id_str);
             assert!(parts[2] == account_with_bridged_nfts.id().to_string());
             assert!(parts[3] == "MA==");
+            assert!(parts[4] == "calimero");
+            assert!(parts[5] == "testnet");
 
             let nft_total_supply_after_burn: U128 = worker.view(
                 bridged_nft_contract_id,
@@ -5,7 +5,7 @@ mod connector {
         use near_sdk::serde_json::json;
         use near_sdk::json_types::U128;
         use near_units::{parse_gas, parse_near};
-        use test_utils::file_as_json;
+        use test_utils::{file_as_json, with_chain_ids};
         use utils::hashes::{decode_hex, encode_hex};
         use utils::Hash;
         use types::{FullOutcomeProof, ConnectorType};
@@ -103,6 +103,17 @@ mod connector {
                 .await
                 .unwrap();
 
+            connector_contract
+                .call(&worker, "set_chain_domain")
+                .args_json(json!({
+                    "chain_id": "testnet",
+                    "peer_chain_id": "calimero",
+                }))
+                .unwrap()
+                .transact()
+                .await
+                .unwrap();
+
             non_fungible_token_contract
                 .call(&worker, "new_default_meta")
                 .args_json(json!({
@@ -162,6 +173,15 @@ mod connector {
         }
 
         async fn lock_nft(worker: &Worker<Sandbox>, _prover: &Contract, connector: &Contract, non_fungible_token: &Contract, account: &Account) -> CallExecutionDetails {
+            // the storage of the lock is paid from the fee deposit
+            account.call(&worker, connector.id(), "deposit_fees")
+                .args_json(json!({}))
+                .unwrap()
+                .deposit(parse_near!("0.1 NEAR") as u128)
+                .transact()
+                .await
+                .unwrap();
+
             account.call(&worker, non_fungible_token.id(), "nft_transfer_call")
                 .args_json(json!({
                     "receiver_id": connector.id(),
@@ -189,7 +209,7 @@ mod connector {
                 .await
                 .unwrap();
 
-            let deploy_proof = &file_as_json::<FullOutcomeProof>("source_test_assets/deploy_proof.json").unwrap();
+            let deploy_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/deploy_proof.json").unwrap(), "calimero", "testnet");
 
             prover
                 .call(&worker, "prove_outcome")
@@ -288,7 +308,7 @@ mod connector {
             let register_execution_details = register_nft(&worker, &prover, &connector, &non_fungible_token).await;
             assert!(register_execution_details.is_success());
 
-            let burn_proof = &file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap();
+            let burn_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap(), "calimero", "testnet");
             let unlock_execution_details = unlock_nft(&worker, &prover, &connector, &non_fungible_token, &burn_proof).await;
             assert!(unlock_execution_details.is_success());
 
@@ -334,12 +354,17 @@ mod connector {
 
             // verify lock event happened, this event is emitted from the nft_connector_source contract
             let parts: Vec<&str> = lock_execution_details.logs()[1].split(":").collect();
-            assert!(parts.len() == 5);
+            assert!(parts.len() == 10);
             assert!(parts[0] == "CALIMERO_EVENT_LOCK_NFT");
             assert!(parts[1] == non_fungible_token.id().to_string());
             assert!(parts[2] == ALICE_ACCOUNT_ID);
             assert!(parts[3] == base64::encode("0"));
             assert!(parts[4] == "eyJ0aXRsZSI6Ikx1a2EgTW9kcmljIiwiZGVzY3JpcHRpb24iOiJCZXN0IGZvb3RiYWwgcGxheWVyIGluIHRoZSB3b3JsZCIsIm1lZGlhIjoiaHR0cHM6Ly9zdGF0aWMwMS5ueXQuY29tL2ltYWdlcy8yMDE4LzEyLzA0L3Nwb3J0cy8wNFNPQ0NFUi13ZWIvbWVybGluXzE0NDE0ODM5OF9mMzgxNmVmNy02MDQ5LTQxNmMtOTEwZS04MWMzZDY2NTdkZTctc3VwZXJKdW1iby5qcGciLCJtZWRpYV9oYXNoIjpudWxsLCJjb3BpZXMiOjEsImlzc3VlZF9hdCI6bnVsbCwiZXhwaXJlc19hdCI6bnVsbCwic3RhcnRzX2F0IjpudWxsLCJ1cGRhdGVkX2F0IjpudWxsLCJleHRyYSI6bnVsbCwicmVmZXJlbmNlIjpudWxsLCJyZWZlcmVuY2VfaGFzaCI6bnVsbH0=");
+            assert!(parts[5] == ALICE_ACCOUNT_ID);
+            assert!(parts[6] == "");
+            assert!(parts[7] == "0");
+            assert!(parts[8] == "testnet");
+            assert!(parts[9] == "calimero");
         }
 
         #[tokio::test]
@@ -358,6 +383,82 @@ mod connector {
             unlock_nft(&worker, &prover, &connector, &non_fungible_token, &used_proof).await;
         }
 
+        #[tokio::test]
+        async fn test_locked_nfts_are_registered() {
+            let (worker, prover, connector, non_fungible_token, _connector_permissions) = init().await;
+            let alice_account = create_and_fund_alice_account(&worker, &prover, &connector, &non_fungible_token).await;
+            let lock_execution_details = lock_nft(&worker, &prover, &connector, &non_fungible_token, &alice_account).await;
+            assert!(lock_execution_details.is_success());
+
+            let locked_token: serde_json::Value = worker.view(
+                connector.id(),
+                "locked_token",
+                serde_json::to_vec(&serde_json::json!({
+                        "collection": non_fungible_token.id(),
+                        "token_id": "0"
+                    })).unwrap())
+                .await.unwrap()
+                .json().unwrap();
+            assert!(locked_token["token_id"] == "0");
+            assert!(locked_token["owner"] == ALICE_ACCOUNT_ID);
+
+            let locked_tokens: serde_json::Value = worker.view(
+                connector.id(),
+                "locked_tokens",
+                serde_json::to_vec(&serde_json::json!({
+                        "collection": non_fungible_token.id()
+                    })).unwrap())
+                .await.unwrap()
+                .json().unwrap();
+            assert!(locked_tokens.as_array().unwrap().len() == 1);
+            assert!(locked_tokens[0]["token_id"] == "0");
+
+            let locked_collections: serde_json::Value = worker.view(
+                connector.id(),
+                "locked_collections",
+                serde_json::to_vec(&serde_json::json!({})).unwrap())
+                .await.unwrap()
+                .json().unwrap();
+            assert!(locked_collections == json!([[non_fungible_token.id(), "1"]]));
+
+            // the unlocked nft is no longer held
+            let register_execution_details = register_nft(&worker, &prover, &connector, &non_fungible_token).await;
+            assert!(register_execution_details.is_success());
+            let burn_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap(), "calimero", "testnet");
+            let unlock_execution_details = unlock_nft(&worker, &prover, &connector, &non_fungible_token, &burn_proof).await;
+            assert!(unlock_execution_details.is_success());
+
+            let locked_token: serde_json::Value = worker.view(
+                connector.id(),
+                "locked_token",
+                serde_json::to_vec(&serde_json::json!({
+                        "collection": non_fungible_token.id(),
+                        "token_id": "0"
+                    })).unwrap())
+                .await.unwrap()
+                .json().unwrap();
+            assert!(locked_token.is_null());
+            let locked_collections: serde_json::Value = worker.view(
+                connector.id(),
+                "locked_collections",
+                serde_json::to_vec(&serde_json::json!({})).unwrap())
+                .await.unwrap()
+                .json().unwrap();
+            assert!(locked_collections == json!([]));
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "Token is not locked")]
+        async fn test_unlock_of_nft_not_held_panics() {
+            let (worker, prover, connector, non_fungible_token, _connector_permissions) = init().await;
+            let register_execution_details = register_nft(&worker, &prover, &connector, &non_fungible_token).await;
+            assert!(register_execution_details.is_success());
+
+            // should panic since the burnt nft was never locked
+            let burn_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("source_test_assets/burn_proof.json").unwrap(), "calimero", "testnet");
+            unlock_nft(&worker, &prover, &connector, &non_fungible_token, &burn_proof).await;
+        }
+
         #[tokio::test]
         async fn test_lock_for_denied_account() {
             let (worker, prover, connector, non_fungible_token, connector_permissions) = init().await;
@@ -428,6 +529,11 @@ mod connector {
             assert!(parts[2] == ALICE_ACCOUNT_ID);
             assert!(parts[3] == base64::encode("0"));
             assert!(parts[4] == "eyJ0aXRsZSI6Ikx1a2EgTW9kcmljIiwiZGVzY3JpcHRpb24iOiJCZXN0IGZvb3RiYWwgcGxheWVyIGluIHRoZSB3b3JsZCIsIm1lZGlhIjoiaHR0cHM6Ly9zdGF0aWMwMS5ueXQuY29tL2ltYWdlcy8yMDE4LzEyLzA0L3Nwb3J0cy8wNFNPQ0NFUi13ZWIvbWVybGluXzE0NDE0ODM5OF9mMzgxNmVmNy02MDQ5LTQxNmMtOTEwZS04MWMzZDY2NTdkZTctc3VwZXJKdW1iby5qcGciLCJtZWRpYV9oYXNoIjpudWxsLCJjb3BpZXMiOjEsImlzc3VlZF9hdCI6bnVsbCwiZXhwaXJlc19hdCI6bnVsbCwic3RhcnRzX2F0IjpudWxsLCJ1cGRhdGVkX2F0IjpudWxsLCJleHRyYSI6bnVsbCwicmVmZXJlbmNlIjpudWxsLCJyZWZlcmVuY2VfaGFzaCI6bnVsbH0=");
+            assert!(parts[5] == ALICE_ACCOUNT_ID);
+            assert!(parts[6] == "");
+            assert!(parts[7] == "0");
+            assert!(parts[8] == "testnet");
+            assert!(parts[9] == "calimero");
 
         }
     }
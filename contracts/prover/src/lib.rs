@@ -1,8 +1,9 @@
 extern crate near_sdk;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{env, near_bindgen, require, serde_json, PanicOnDefault, PromiseResult};
-use types::{FullOutcomeProof, MerklePath};
+use near_sdk::{env, near_bindgen, serde_json, PanicOnDefault, PromiseResult};
+use types::errors::{panic_err, panic_err_with};
+use types::{require_err, BridgeError, EncodedOutcomeProof, MerklePath};
 pub use utils::{hashes, Hash, Hashable};
 
 #[near_bindgen]
@@ -22,20 +23,16 @@ impl Prover {
     }
 
     // cross contract calls used, hence this is not view method
-    pub fn prove_outcome(&self, full_outcome_proof: FullOutcomeProof, block_height: u64) {
-        let mut hash = Prover::compute_root(
-            &full_outcome_proof.outcome_proof.outcome_with_id.hash(),
-            full_outcome_proof.outcome_proof.proof,
-        );
-
-        hash = Prover::compute_root(
-            &env::sha256(&hash).try_into().unwrap(),
-            full_outcome_proof.outcome_root_proof,
-        );
-
-        require!(
-            hash == full_outcome_proof.block_header_lite.inner_lite.outcome_root,
-            "NearProver: outcome merkle proof is not valid",
+    pub fn prove_outcome(&self, full_outcome_proof: EncodedOutcomeProof, block_height: u64) {
+        let full_outcome_proof = full_outcome_proof
+            .decode()
+            .unwrap_or_else(|_| panic_err(BridgeError::InvalidProofEncoding));
+        let outcome_root = full_outcome_proof
+            .outcome_root()
+            .unwrap_or_else(|err| panic_err_with(BridgeError::MerklePathInvalid, &err.to_string()));
+        require_err!(
+            outcome_root == full_outcome_proof.block_header_lite.inner_lite.outcome_root,
+            BridgeError::OutcomeProofInvalid,
         );
 
         let promise_merkle_root = env::promise_create(
@@ -50,11 +47,12 @@ impl Prover {
             promise_merkle_root,
             env::current_account_id(),
             "merkle_root_callback",
-            &serde_json::to_vec(&(
+            &(
                 full_outcome_proof.block_header_lite.hash(),
                 full_outcome_proof.block_proof,
-            ))
-            .unwrap(),
+            )
+                .try_to_vec()
+                .unwrap(),
             0,
             env::prepaid_gas() / 3,
         );
@@ -63,32 +61,27 @@ impl Prover {
     }
 
     #[private]
-    pub fn merkle_root_callback(&self, block_header_lite_hash: Hash, block_proof: MerklePath) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+    pub fn merkle_root_callback(
+        &self,
+        #[serializer(borsh)] block_header_lite_hash: Hash,
+        #[serializer(borsh)] block_proof: MerklePath,
+    ) -> bool {
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         let expected_block_merkle_root = match env::promise_result(0) {
-            PromiseResult::Successful(x) => serde_json::from_slice::<Option<Hash>>(&x).unwrap(),
-            _ => env::panic_str("Merkle root promise failed"),
+            PromiseResult::Successful(x) => serde_json::from_slice::<Option<Hash>>(&x)
+                .unwrap_or_else(|_| panic_err(BridgeError::MerkleRootUnavailable)),
+            _ => panic_err(BridgeError::MerkleRootUnavailable),
         };
 
-        let computed_block_merkle_root = Prover::compute_root(&block_header_lite_hash, block_proof);
+        let computed_block_merkle_root = block_proof
+            .compute_root(&block_header_lite_hash)
+            .unwrap_or_else(|err| panic_err_with(BridgeError::MerklePathInvalid, &err.to_string()));
 
-        require!(
+        require_err!(
             expected_block_merkle_root == Some(computed_block_merkle_root),
-            "NearProver: block proof is not valid"
+            BridgeError::BlockProofInvalid
         );
-        env::value_return(&serde_json::to_vec(&true).unwrap());
-    }
-
-    fn compute_root(node: &Hash, path: MerklePath) -> Hash {
-        let mut hash: Hash = *node;
-        for item in path.items {
-            hash = match item.direction {
-                types::MERKLE_PATH_LEFT => hashes::combine_hash2(item.hash(), hash),
-                types::MERKLE_PATH_RIGHT => hashes::combine_hash2(hash, item.hash()),
-                _ => panic!("NearProver: unknown merkle path"),
-            }
-        }
-        hash
+        true
     }
 }
@@ -3,7 +3,7 @@ mod prover {
     mod test {
         use near_sdk::serde_json::json;
         use near_units::parse_gas;
-        use types::FullOutcomeProof;
+        use types::{EncodedOutcomeProof, FullOutcomeProof};
         use test_utils::file_as_json;
         use utils::hashes::decode_hex;
         use utils::Hash;
@@ -48,6 +48,24 @@ mod prover {
 
         // if call does not panic, it is considered valid, since return is always true
         async fn proof_valid(filename: &str, block_height: u64, block_merkle_root: Hash) {
+            let proof = file_as_json::<FullOutcomeProof>(filename).unwrap();
+            encoded_proof_valid(proof.into(), block_height, block_merkle_root).await;
+        }
+
+        async fn borsh_proof_valid(filename: &str, block_height: u64, block_merkle_root: Hash) {
+            let proof = file_as_json::<FullOutcomeProof>(filename).unwrap();
+            encoded_proof_valid(
+                EncodedOutcomeProof::borsh(&proof),
+                block_height,
+                block_merkle_root,
+            ).await;
+        }
+
+        async fn encoded_proof_valid(
+            proof: EncodedOutcomeProof,
+            block_height: u64,
+            block_merkle_root: Hash,
+        ) {
             let (worker, bridge, prover) = init().await;
             bridge
             .call(&worker, "add_merkle_root")
@@ -59,8 +77,7 @@ mod prover {
             .transact()
             .await
             .unwrap();
-            
-            let proof = &file_as_json::<FullOutcomeProof>(filename).unwrap();
+
             let execution_details = prover
             .call(&worker, "prove_outcome")
             .args_json(json!({
@@ -175,6 +192,40 @@ mod prover {
             ).await;
         }
 
+        #[tokio::test]
+        async fn proof2_borsh() {
+            borsh_proof_valid(
+                "proof2.json",
+                498,
+                decode_hex("22f00dd154366d758cd3e4fe81c1caed8e0db6227fe4b2b52a8e5a468aa0a723")
+                    .try_into()
+                    .unwrap(),
+                ).await;
+        }
+
+        #[tokio::test]
+        async fn proof10_borsh() {
+            borsh_proof_valid(
+                "proof10.json",
+                95868967,
+                decode_hex("685eba49d8de4d6020db910f2f982305b546f85b151ba9b78c281785d0731475")
+                    .try_into()
+                    .unwrap(),
+            ).await;
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "block proof is not valid")]
+        async fn proof10_borsh_fail() {
+            borsh_proof_valid(
+                "proof10.json",
+                95868967,
+                decode_hex("685eba49d8de4d6020db910f2f982305b546f85b151ba9b78c281785d0731476")
+                    .try_into()
+                    .unwrap(),
+            ).await;
+        }
+
         #[tokio::test]
         #[should_panic(expected = "block proof is not valid")]
         async fn proof2_fail() {
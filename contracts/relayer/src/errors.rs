@@ -0,0 +1,51 @@
+use rpc_types::errors::ConversionError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RelayerError {
+    #[error("rpc request failed: {0}")]
+    Transport(String),
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+    #[error("block {height} is not valid: {error}")]
+    InvalidBlock {
+        height: u64,
+        error: BlockValidationError,
+    },
+    #[error("transaction failed: {0}")]
+    Transaction(String),
+    #[error("failed to access the state file: {0}")]
+    State(#[from] std::io::Error),
+}
+
+impl RelayerError {
+    /// Whether sending the same request again may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            RelayerError::Transport(_) | RelayerError::Conversion(ConversionError::Rpc(_))
+        )
+    }
+}
+
+/// Reasons for the light client contract to reject a block, with the contract's messages.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BlockValidationError {
+    #[error("Initialization block must contain next_bps")]
+    InitBlockWithoutNextBps,
+    #[error("New block must have higher height")]
+    Height,
+    #[error("Epoch id of the block is not valid")]
+    EpochId,
+    #[error("Approval list is too short")]
+    ApprovalsTooShort,
+    #[error("Too few approvals")]
+    TooFewApprovals,
+    #[error("Next next_bps should not be None")]
+    NextBpsMissing,
+    #[error("Hash of block producers does not match")]
+    NextBpHash,
+    #[error("Signature stake too low")]
+    SignatureStake,
+    #[error("It is not expected having that many block producers for the provided block")]
+    TooManyBlockProducers,
+}
@@ -0,0 +1,206 @@
+//! Reference relayer following a NEAR chain and submitting its light client blocks to the
+//! light client contract of another chain.
+//!
+//! Blocks are fetched with `next_light_client_block`, checked against a local replica of the
+//! contract state and only then submitted, so the relayer does not pay for blocks the
+//! contract would reject. RPC failures are retried with exponential backoff.
+
+pub mod errors;
+pub mod light_client;
+pub mod retry;
+pub mod rpc;
+pub mod transaction;
+
+use crate::errors::RelayerError;
+use crate::light_client::LightClientState;
+use crate::retry::RetryPolicy;
+use crate::rpc::RpcClient;
+use crate::transaction::{SignedTransaction, Transaction};
+use near_sdk::serde::Deserialize;
+use near_sdk::serde_json::{self, json};
+use near_sdk::Balance;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use types::signature::SecretKey;
+use types::{Block, FunctionCallAction};
+
+pub const DEFAULT_GAS: u64 = 300_000_000_000_000;
+
+/// Account signing the transactions sent to the other chain.
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Signer {
+    pub account_id: String,
+    #[serde(rename = "private_key")]
+    pub secret_key: SecretKey,
+}
+
+impl Signer {
+    /// Reads a credentials file as written by near-cli.
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Signs a call of `method_name` with the next nonce of the access key on `rpc`.
+    pub fn function_call(
+        &self,
+        rpc: &RpcClient,
+        receiver_id: &str,
+        method_name: &str,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: Balance,
+    ) -> Result<SignedTransaction, RelayerError> {
+        let public_key = self.secret_key.public_key();
+        let (nonce, block_hash) = rpc.access_key(&self.account_id, &public_key)?;
+        Ok(Transaction {
+            signer_id: self.account_id.clone(),
+            public_key,
+            nonce: nonce + 1,
+            receiver_id: receiver_id.to_string(),
+            block_hash,
+            actions: vec![FunctionCallAction {
+                method_name: method_name.to_string(),
+                args,
+                gas,
+                deposit,
+            }
+            .into()],
+        }
+        .sign(&self.secret_key))
+    }
+}
+
+pub struct HeaderRelayer {
+    source: RpcClient,
+    target: RpcClient,
+    light_client_account_id: String,
+    signer: Signer,
+    state: LightClientState,
+    state_file: Option<PathBuf>,
+    retry: RetryPolicy,
+    gas: u64,
+}
+
+impl HeaderRelayer {
+    pub fn new(
+        source: RpcClient,
+        target: RpcClient,
+        light_client_account_id: &str,
+        signer: Signer,
+        state: LightClientState,
+    ) -> Self {
+        Self {
+            source,
+            target,
+            light_client_account_id: light_client_account_id.to_string(),
+            signer,
+            state,
+            state_file: None,
+            retry: RetryPolicy::default(),
+            gas: DEFAULT_GAS,
+        }
+    }
+
+    /// Saves the state to `path` after every accepted block.
+    pub fn with_state_file(mut self, path: PathBuf) -> Self {
+        self.state_file = Some(path);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_gas(mut self, gas: u64) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    pub fn state(&self) -> &LightClientState {
+        &self.state
+    }
+
+    /// Relays the block following the local head. Returns its height, or `None` when the
+    /// source chain has nothing newer yet.
+    pub fn step(&mut self) -> Result<Option<u64>, RelayerError> {
+        let head_hash = self.state.head_hash();
+        let block = match self.retry.run("next_light_client_block", || {
+            self.source.next_light_client_block(&head_hash)
+        })? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let height = block.inner_lite.height;
+        self.state
+            .validate(&block)
+            .map_err(|error| RelayerError::InvalidBlock { height, error })?;
+
+        // another relayer may have submitted a later block already
+        if height > self.contract_height()? {
+            self.retry
+                .run("add_light_client_block", || self.submit(&block))?;
+            log::info!("submitted block {}", height);
+        } else {
+            log::info!("block {} is already behind the light client", height);
+        }
+
+        self.state
+            .accept(&block)
+            .expect("The block was validated just before");
+        if let Some(path) = &self.state_file {
+            self.state.save(path)?;
+        }
+        Ok(Some(height))
+    }
+
+    /// Relays blocks forever, polls the source every `poll_interval` once it caught up.
+    pub fn run(&mut self, poll_interval: Duration) -> ! {
+        loop {
+            match self.step() {
+                Ok(Some(_)) => continue,
+                Ok(None) => {}
+                Err(err) => log::error!("{}", err),
+            }
+            thread::sleep(poll_interval);
+        }
+    }
+
+    fn contract_height(&self) -> Result<u64, RelayerError> {
+        self.retry.run("current_height", || {
+            self.target
+                .view_call(&self.light_client_account_id, "current_height", json!({}))
+        })
+    }
+
+    fn submit(&self, block: &Block) -> Result<(), RelayerError> {
+        let transaction = self.signer.function_call(
+            &self.target,
+            &self.light_client_account_id,
+            "add_light_client_block",
+            serde_json::to_vec(&json!({ "block": block })).unwrap(),
+            self.gas,
+            0,
+        )?;
+
+        match self.target.broadcast_tx_commit(&transaction) {
+            Err(err) if err.is_transient() => {
+                // the response may have been lost after the transaction was executed
+                let contract_height: u64 = self.target.view_call(
+                    &self.light_client_account_id,
+                    "current_height",
+                    json!({}),
+                )?;
+                if contract_height >= block.inner_lite.height {
+                    Ok(())
+                } else {
+                    Err(err)
+                }
+            }
+            result => result.map(|_| ()),
+        }
+    }
+}
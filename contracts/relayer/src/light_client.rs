@@ -0,0 +1,181 @@
+//! Replica of the light client contract state, used to check blocks before paying for their
+//! submission. It applies the rules of `LightClient::add_light_client_block` in the same order.
+
+use crate::errors::BlockValidationError;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json;
+use std::fs;
+use std::path::Path;
+use types::{Block, Epoch, Validator};
+use utils::{base_hash_format, hashes, Hash, Hashable};
+
+// same limits as the contract
+const MAX_BLOCK_PRODUCERS: usize = 100;
+const NUM_OF_EPOCHS: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LightClientState {
+    epochs: Vec<Epoch>,
+    current_epoch_index: usize,
+    current_height: u64,
+    #[serde(with = "base_hash_format")]
+    head_hash: Hash,
+}
+
+impl LightClientState {
+    /// State of the contract after `init_with_validators` and `init_with_block`.
+    pub fn new(
+        initial_validators: &[Validator],
+        block: &Block,
+    ) -> Result<Self, BlockValidationError> {
+        let next_bps = block
+            .next_bps
+            .as_ref()
+            .ok_or(BlockValidationError::InitBlockWithoutNextBps)?;
+        let epochs = vec![
+            epoch(block.inner_lite.epoch_id, initial_validators)?,
+            epoch(block.inner_lite.next_epoch_id, next_bps)?,
+            epoch(Hash::default(), &[])?,
+        ];
+
+        Ok(Self {
+            epochs,
+            current_epoch_index: 0,
+            current_height: block.inner_lite.height,
+            head_hash: block.hash(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
+        // written aside first, so a crash never leaves a truncated state behind
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, path)
+    }
+
+    pub fn current_height(&self) -> u64 {
+        self.current_height
+    }
+
+    /// Hash of the last accepted block, the next block is requested after this one.
+    pub fn head_hash(&self) -> Hash {
+        self.head_hash
+    }
+
+    /// Checks `block` as the contract would, returns whether it belongs to the next epoch.
+    pub fn validate(&self, block: &Block) -> Result<bool, BlockValidationError> {
+        if block.inner_lite.height <= self.current_height {
+            return Err(BlockValidationError::Height);
+        }
+
+        let next_epoch_index = (self.current_epoch_index + 1) % NUM_OF_EPOCHS;
+        let next_epoch =
+            if block.inner_lite.epoch_id == self.epochs[self.current_epoch_index].epoch_id {
+                false
+            } else if block.inner_lite.epoch_id == self.epochs[next_epoch_index].epoch_id {
+                true
+            } else {
+                return Err(BlockValidationError::EpochId);
+            };
+        let this_epoch = if next_epoch {
+            &self.epochs[next_epoch_index]
+        } else {
+            &self.epochs[self.current_epoch_index]
+        };
+
+        if block.approvals_after_next.len() < this_epoch.keys.len() {
+            return Err(BlockValidationError::ApprovalsTooShort);
+        }
+        let voted_for: u128 = this_epoch
+            .stakes
+            .iter()
+            .zip(&block.approvals_after_next)
+            .filter(|(_, approval)| approval.is_some())
+            .map(|(stake, _)| stake)
+            .sum();
+        if voted_for <= this_epoch.stake_threshold {
+            return Err(BlockValidationError::TooFewApprovals);
+        }
+
+        if next_epoch {
+            let next_bps = block
+                .next_bps
+                .as_ref()
+                .ok_or(BlockValidationError::NextBpsMissing)?;
+            if types::block_producers_hash(next_bps) != block.inner_lite.next_bp_hash {
+                return Err(BlockValidationError::NextBpHash);
+            }
+        }
+
+        let next_hash = hashes::combine_hash2(block.next_block_inner_hash, block.hash());
+        let message = [
+            &[0],
+            &next_hash as &[_],
+            &utils::swap_bytes8(block.inner_lite.height + 2).to_be_bytes() as &[_],
+        ]
+        .concat();
+        let mut signature_stake: u128 = 0;
+        for (i, approval) in block.approvals_after_next[..this_epoch.keys.len()]
+            .iter()
+            .enumerate()
+        {
+            if let Some(signature) = approval {
+                if signature.verify(&message, &this_epoch.keys[i]) {
+                    signature_stake += this_epoch.stakes[i];
+                }
+                if signature_stake > this_epoch.stake_threshold {
+                    break;
+                }
+            }
+        }
+        if signature_stake <= this_epoch.stake_threshold {
+            return Err(BlockValidationError::SignatureStake);
+        }
+
+        if next_epoch && block.next_bps.as_ref().unwrap().len() > MAX_BLOCK_PRODUCERS {
+            return Err(BlockValidationError::TooManyBlockProducers);
+        }
+        Ok(next_epoch)
+    }
+
+    /// Validates `block` and makes it the head, as a successful `add_light_client_block` does.
+    pub fn accept(&mut self, block: &Block) -> Result<(), BlockValidationError> {
+        if self.validate(block)? {
+            let epoch_index = (self.current_epoch_index + 2) % NUM_OF_EPOCHS;
+            self.epochs[epoch_index] = epoch(
+                block.inner_lite.next_epoch_id,
+                block.next_bps.as_ref().unwrap(),
+            )?;
+            self.current_epoch_index = (self.current_epoch_index + 1) % NUM_OF_EPOCHS;
+        }
+        self.current_height = block.inner_lite.height;
+        self.head_hash = block.hash();
+        Ok(())
+    }
+}
+
+fn epoch(epoch_id: Hash, block_producers: &[Validator]) -> Result<Epoch, BlockValidationError> {
+    if block_producers.len() > MAX_BLOCK_PRODUCERS {
+        return Err(BlockValidationError::TooManyBlockProducers);
+    }
+    let keys = block_producers
+        .iter()
+        .map(|block_producer| block_producer.public_key().clone())
+        .collect();
+    let stakes: Vec<u128> = block_producers
+        .iter()
+        .map(|block_producer| *block_producer.stake())
+        .collect();
+    let total_stake: u128 = stakes.iter().sum();
+    Ok(Epoch {
+        epoch_id,
+        keys,
+        stake_threshold: (total_stake * 2) / 3,
+        stakes,
+    })
+}
@@ -0,0 +1,91 @@
+use clap::Parser;
+use near_sdk::serde_json::{self, Value};
+use relayer::light_client::LightClientState;
+use relayer::retry::RetryPolicy;
+use relayer::rpc::RpcClient;
+use relayer::{HeaderRelayer, Signer, DEFAULT_GAS};
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use types::Validator;
+
+/// Submits light client blocks of a NEAR chain to the light client contract of another chain.
+#[derive(Parser)]
+struct Args {
+    /// JSON-RPC endpoint of the chain the blocks are taken from
+    #[clap(long)]
+    source_rpc: String,
+    /// JSON-RPC endpoint of the chain the light client contract runs on
+    #[clap(long)]
+    target_rpc: String,
+    /// Account of the light client contract
+    #[clap(long)]
+    light_client: String,
+    /// Credentials file of the submitting account, as written by near-cli
+    #[clap(long)]
+    key_file: PathBuf,
+    /// Replica of the light client state, created from the init files when missing
+    #[clap(long)]
+    state_file: PathBuf,
+    /// Validators the light client was initialized with
+    #[clap(long, requires = "init-block")]
+    init_validators: Option<PathBuf>,
+    /// Block the light client was initialized with, in the RPC or the contract format
+    #[clap(long, requires = "init-validators")]
+    init_block: Option<PathBuf>,
+    #[clap(long, default_value = "1000")]
+    poll_interval_ms: u64,
+    #[clap(long, default_value = "10000")]
+    rpc_timeout_ms: u64,
+    #[clap(long, default_value = "5")]
+    max_attempts: u32,
+    #[clap(long, default_value_t = DEFAULT_GAS)]
+    gas: u64,
+}
+
+fn load_state(args: &Args) -> Result<LightClientState, Box<dyn Error>> {
+    if args.state_file.exists() {
+        return Ok(LightClientState::load(&args.state_file)?);
+    }
+    let (validators, block) = match (&args.init_validators, &args.init_block) {
+        (Some(validators), Some(block)) => (validators, block),
+        _ => return Err("the state file does not exist, the init files are required".into()),
+    };
+    let validators: Vec<Validator> = serde_json::from_slice(&fs::read(validators)?)?;
+    let block: Value = serde_json::from_slice(&fs::read(block)?)?;
+    let block = rpc_types::light_client_block_from_rpc(block)?;
+    let state = LightClientState::new(&validators, &block)?;
+    state.save(&args.state_file)?;
+    Ok(state)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let signer = Signer::load(&args.key_file)?;
+    let state = load_state(&args)?;
+    log::info!(
+        "relaying from {} to {} starting after block {}",
+        args.source_rpc,
+        args.light_client,
+        state.current_height()
+    );
+
+    let timeout = Duration::from_millis(args.rpc_timeout_ms);
+    let mut relayer = HeaderRelayer::new(
+        RpcClient::new(&args.source_rpc, timeout),
+        RpcClient::new(&args.target_rpc, timeout),
+        &args.light_client,
+        signer,
+        state,
+    )
+    .with_state_file(args.state_file.clone())
+    .with_retry(RetryPolicy {
+        max_attempts: args.max_attempts,
+        ..Default::default()
+    })
+    .with_gas(args.gas);
+    relayer.run(Duration::from_millis(args.poll_interval_ms))
+}
@@ -0,0 +1,51 @@
+use crate::errors::RelayerError;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given retry, doubling from `initial_backoff` up to `max_backoff`.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Runs `operation` until it succeeds, fails with an error that is not transient or runs
+    /// out of attempts. The last error is returned.
+    pub fn run<T>(
+        &self,
+        what: &str,
+        mut operation: impl FnMut() -> Result<T, RelayerError>,
+    ) -> Result<T, RelayerError> {
+        let mut retry = 0;
+        loop {
+            match operation() {
+                Err(err) if err.is_transient() && retry + 1 < self.max_attempts => {
+                    let backoff = self.backoff(retry);
+                    log::warn!("{} failed: {}, retrying in {:?}", what, err, backoff);
+                    thread::sleep(backoff);
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
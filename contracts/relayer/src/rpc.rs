@@ -0,0 +1,184 @@
+//! Blocking client for the few NEAR JSON-RPC methods the relayer and the bridge service need.
+
+use crate::errors::RelayerError;
+use crate::transaction::SignedTransaction;
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json::{self, json, Value};
+use rpc_types::errors::ConversionError;
+use rpc_types::views::{BlockView, ChunkView};
+use std::time::Duration;
+use types::{Block, FullOutcomeProof, PublicKey};
+use utils::{hashes, Hash};
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcClient {
+    pub fn new(url: &str, timeout: Duration) -> Self {
+        Self {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Sends a request and returns its `result`.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, RelayerError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "relayer",
+            "method": method,
+            "params": params,
+        });
+        let body = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .map_err(|err| RelayerError::Transport(err.to_string()))?
+            .into_string()
+            .map_err(|err| RelayerError::Transport(err.to_string()))?;
+        let response: Value = serde_json::from_str(&body).map_err(ConversionError::from)?;
+        Ok(rpc_types::from_rpc_response(response)?)
+    }
+
+    /// Light client block following `last_block_hash`, `None` while the chain has no newer
+    /// final block.
+    pub fn next_light_client_block(
+        &self,
+        last_block_hash: &Hash,
+    ) -> Result<Option<Block>, RelayerError> {
+        let result = self.call(
+            "next_light_client_block",
+            json!({ "last_block_hash": utils::to_base(last_block_hash) }),
+        )?;
+        // nearcore answers with an empty object when there is no newer block
+        if result.is_null() || result == json!({}) {
+            return Ok(None);
+        }
+        Ok(Some(rpc_types::light_client_block_from_rpc(result)?))
+    }
+
+    /// Latest final block.
+    pub fn final_block(&self) -> Result<BlockView, RelayerError> {
+        let result = self.call("block", json!({ "finality": "final" }))?;
+        Ok(rpc_types::from_rpc_response(result)?)
+    }
+
+    /// Block at `height`, `None` if no block was produced at that height.
+    pub fn block(&self, height: u64) -> Result<Option<BlockView>, RelayerError> {
+        match self.call("block", json!({ "block_id": height })) {
+            Ok(result) => Ok(Some(rpc_types::from_rpc_response(result)?)),
+            Err(RelayerError::Conversion(ConversionError::Rpc(error)))
+                if error.contains("UNKNOWN_BLOCK") =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Chunk of `shard_id` in the block at `height`.
+    pub fn chunk(&self, height: u64, shard_id: u64) -> Result<ChunkView, RelayerError> {
+        let result = self.call("chunk", json!({ "block_id": height, "shard_id": shard_id }))?;
+        Ok(rpc_types::from_rpc_response(result)?)
+    }
+
+    /// Proof of the outcome of a receipt against the block `light_client_head`, which must
+    /// come after the block the outcome was included in.
+    pub fn light_client_proof(
+        &self,
+        receipt_id: &Hash,
+        receiver_id: &str,
+        light_client_head: &Hash,
+    ) -> Result<FullOutcomeProof, RelayerError> {
+        let result = self.call(
+            "EXPERIMENTAL_light_client_proof",
+            json!({
+                "type": "receipt",
+                "receipt_id": utils::to_base(receipt_id),
+                "receiver_id": receiver_id,
+                "light_client_head": utils::to_base(light_client_head),
+            }),
+        )?;
+        Ok(rpc_types::outcome_proof_from_rpc(result)?)
+    }
+
+    /// Calls a view method on the final state and parses its JSON result.
+    pub fn view_call<T: DeserializeOwned>(
+        &self,
+        account_id: &str,
+        method_name: &str,
+        args: Value,
+    ) -> Result<T, RelayerError> {
+        let result = self.call(
+            "query",
+            json!({
+                "request_type": "call_function",
+                "finality": "final",
+                "account_id": account_id,
+                "method_name": method_name,
+                "args_base64": utils::to_base64(args.to_string()),
+            }),
+        )?;
+        let bytes: Vec<u8> =
+            serde_json::from_value(result["result"].clone()).map_err(ConversionError::from)?;
+        Ok(serde_json::from_slice(&bytes).map_err(ConversionError::from)?)
+    }
+
+    /// Nonce of the access key and the final block hash to sign the next transaction with.
+    pub fn access_key(
+        &self,
+        account_id: &str,
+        public_key: &PublicKey,
+    ) -> Result<(u64, Hash), RelayerError> {
+        let result = self.call(
+            "query",
+            json!({
+                "request_type": "view_access_key",
+                "finality": "final",
+                "account_id": account_id,
+                "public_key": public_key,
+            }),
+        )?;
+        let nonce = result["nonce"].as_u64();
+        let block_hash = result["block_hash"]
+            .as_str()
+            .and_then(|hash| hashes::deserialize_hash(&hash.to_string()));
+        match (nonce, block_hash) {
+            (Some(nonce), Some(block_hash)) => Ok((nonce, block_hash)),
+            _ => Err(RelayerError::Conversion(ConversionError::Rpc(format!(
+                "unexpected access key: {}",
+                result
+            )))),
+        }
+    }
+
+    /// Sends the transaction and waits for its execution, failed executions are errors.
+    pub fn broadcast_tx_commit(
+        &self,
+        transaction: &SignedTransaction,
+    ) -> Result<Value, RelayerError> {
+        let outcome = self.send_transaction(transaction)?;
+        if let Some(failure) = outcome["status"].get("Failure") {
+            return Err(RelayerError::Transaction(failure.to_string()));
+        }
+        Ok(outcome)
+    }
+
+    /// Sends the transaction and returns its final outcome, whether the execution succeeded
+    /// or not.
+    pub fn send_transaction(&self, transaction: &SignedTransaction) -> Result<Value, RelayerError> {
+        let transaction = transaction.try_to_vec().expect("Failed to serialize");
+        self.call(
+            "broadcast_tx_commit",
+            json!([utils::to_base64(transaction)]),
+        )
+    }
+}
@@ -0,0 +1,47 @@
+//! Transactions in the form they are signed and broadcast, taken from nearcore primitives.
+//! `types::Transaction` is the view returned by the RPC, which carries the hash and signature.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use types::signature::SecretKey;
+use types::{Action, PublicKey, Signature};
+use utils::{hashes, Hash, Hashable};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub signer_id: String,
+    pub public_key: PublicKey,
+    pub nonce: u64,
+    pub receiver_id: String,
+    /// Recent block hash, the transaction expires some time after this block.
+    pub block_hash: Hash,
+    pub actions: Vec<Action>,
+}
+
+impl Hashable for Transaction {
+    fn hash(&self) -> Hash {
+        hashes::sha256(&self.try_to_vec().expect("Failed to serialize"))
+    }
+}
+
+impl Transaction {
+    pub fn sign(self, secret_key: &SecretKey) -> SignedTransaction {
+        let signature = secret_key.sign(&self.hash());
+        SignedTransaction {
+            transaction: self,
+            signature,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub transaction: Transaction,
+    pub signature: Signature,
+}
+
+impl SignedTransaction {
+    pub fn verify(&self) -> bool {
+        self.signature
+            .verify(&self.transaction.hash(), &self.transaction.public_key)
+    }
+}
@@ -0,0 +1,370 @@
+use light_client::LightClient;
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::testing_env;
+use relayer::errors::{BlockValidationError, RelayerError};
+use relayer::light_client::LightClientState;
+use relayer::retry::RetryPolicy;
+use relayer::rpc::RpcClient;
+use relayer::transaction::SignedTransaction;
+use relayer::{HeaderRelayer, Signer};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+use test_utils::chain::secret_key_from_seed;
+use test_utils::rpc::{MockResponse, MockRpcServer};
+use types::{Action, Block, Validator};
+use utils::{hashes, Hashable};
+
+const LIGHT_CLIENT: &str = "light_client.calimero";
+const RELAYER: &str = "relayer.calimero";
+
+/// Captured mainnet blocks, each one from the epoch after the previous one.
+const BLOCKS: &[&str] = &[
+    "block_105233559.json",
+    "block_105276759.json",
+    "block_105319959.json",
+    "block_105363159.json",
+    "block_105406359.json",
+    "block_105449559.json",
+    "block_105492759.json",
+    "block_105535959.json",
+    "block_105579159.json",
+    "block_105622359.json",
+    "block_105665559.json",
+    "block_105708759.json",
+    "block_105738334.json",
+    "block_105738335.json",
+];
+
+fn fixture(name: &str) -> Value {
+    let content = std::fs::read_to_string(format!("../light_client/tests/{}", name)).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+fn block(name: &str) -> Block {
+    rpc_types::light_client_block_from_rpc(fixture(name)).unwrap()
+}
+
+/// Validators of the epoch of the first block, the light client is initialized with them.
+fn initial_validators() -> Vec<Validator> {
+    block("block_105190359.json").next_bps.unwrap()
+}
+
+fn fast_retry(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+    }
+}
+
+/// Node of the source chain serving `blocks` in order. The first `unavailable` requests are
+/// answered with HTTP 503.
+fn source_node(blocks: Vec<Value>, mut unavailable: u32) -> MockRpcServer {
+    let hashes: Vec<String> = blocks
+        .iter()
+        .map(|block| {
+            let block = rpc_types::light_client_block_from_rpc(block.clone()).unwrap();
+            utils::to_base(block.hash())
+        })
+        .collect();
+    MockRpcServer::start(move |method, params| {
+        if unavailable > 0 {
+            unavailable -= 1;
+            return MockResponse::Status(503);
+        }
+        assert_eq!(method, "next_light_client_block");
+        let last_block_hash = params["last_block_hash"].as_str().unwrap();
+        let next = hashes
+            .iter()
+            .position(|hash| hash == last_block_hash)
+            .and_then(|index| blocks.get(index + 1));
+        MockResponse::Result(next.cloned().unwrap_or_else(|| json!({})))
+    })
+}
+
+fn init_light_client(validators: Vec<Validator>, init_block: Block) -> LightClient {
+    let account = LIGHT_CLIENT.parse().unwrap();
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(account)
+        .predecessor_account_id(LIGHT_CLIENT.parse().unwrap())
+        .build());
+    let mut light_client = LightClient::new(None);
+    light_client.init_with_validators(validators);
+    light_client.init_with_block(init_block);
+    light_client
+}
+
+/// Message the light client contract fails with when adding `block`, if it does.
+fn add_block(light_client: &mut LightClient, block: Block) -> Option<String> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        light_client.add_light_client_block(block)
+    }));
+    result.err().map(|err| {
+        err.downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|err| err.to_string()))
+            .unwrap_or_default()
+    })
+}
+
+fn view_result(value: Value) -> MockResponse {
+    MockResponse::Result(json!({ "result": serde_json::to_vec(&value).unwrap(), "logs": [] }))
+}
+
+/// Node of the target chain with the light client contract running natively on the server
+/// thread. `already_added` blocks are added by another relayer before the first request, the
+/// responses to the first `lost_responses` transactions are dropped after their execution.
+fn target_node(
+    validators: Vec<Validator>,
+    init_block: Block,
+    already_added: Vec<Block>,
+    mut lost_responses: u32,
+) -> MockRpcServer {
+    let mut setup = Some((validators, init_block, already_added));
+    let mut light_client = None;
+    let mut nonce: u64 = 0;
+    MockRpcServer::start(move |method, params| {
+        let light_client = light_client.get_or_insert_with(|| {
+            let (validators, init_block, already_added) = setup.take().unwrap();
+            let mut light_client = init_light_client(validators, init_block);
+            for block in already_added {
+                assert_eq!(add_block(&mut light_client, block), None);
+            }
+            light_client
+        });
+
+        match (method, params["request_type"].as_str()) {
+            ("query", Some("view_access_key")) => MockResponse::Result(json!({
+                "nonce": nonce,
+                "block_hash": utils::to_base(hashes::sha256(&nonce.to_le_bytes())),
+                "permission": "FullAccess",
+            })),
+            ("query", Some("call_function")) => {
+                let args = utils::from_base64(params["args_base64"].as_str().unwrap()).unwrap();
+                let args: Value = serde_json::from_slice(&args).unwrap();
+                match params["method_name"].as_str().unwrap() {
+                    "current_height" => view_result(json!(light_client.current_height())),
+                    "block_hashes" => view_result(json!(light_client
+                        .block_hashes(args["height"].as_u64().unwrap())
+                        .map(utils::to_base))),
+                    method => panic!("unexpected view {}", method),
+                }
+            }
+            ("broadcast_tx_commit", _) => {
+                let transaction = utils::from_base64(params[0].as_str().unwrap()).unwrap();
+                let transaction = SignedTransaction::try_from_slice(&transaction).unwrap();
+                assert!(transaction.verify());
+                let transaction = transaction.transaction;
+                if transaction.nonce <= nonce {
+                    return MockResponse::Error(json!({ "name": "InvalidNonce" }));
+                }
+                nonce = transaction.nonce;
+
+                let block = match &transaction.actions[..] {
+                    [Action::FunctionCall(call)]
+                        if call.method_name == "add_light_client_block" =>
+                    {
+                        let args: Value = serde_json::from_slice(&call.args).unwrap();
+                        serde_json::from_value(args["block"].clone()).unwrap()
+                    }
+                    actions => panic!("unexpected actions {:?}", actions),
+                };
+                let status = match add_block(light_client, block) {
+                    None => json!({ "SuccessValue": "" }),
+                    Some(err) => json!({ "Failure": { "ActionError": { "index": 0, "kind": {
+                        "FunctionCallError": { "ExecutionError": format!("Smart contract panicked: {}", err) }
+                    }}}}),
+                };
+                if lost_responses > 0 {
+                    lost_responses -= 1;
+                    return MockResponse::Status(504);
+                }
+                MockResponse::Result(json!({ "status": status }))
+            }
+            _ => panic!("unexpected request {} {}", method, params),
+        }
+    })
+}
+
+fn relayer(source: &MockRpcServer, target: &MockRpcServer, retry: RetryPolicy) -> HeaderRelayer {
+    let state = LightClientState::new(&initial_validators(), &block(BLOCKS[0])).unwrap();
+    HeaderRelayer::new(
+        RpcClient::new(source.url(), Duration::from_secs(5)),
+        RpcClient::new(target.url(), Duration::from_secs(5)),
+        LIGHT_CLIENT,
+        Signer {
+            account_id: RELAYER.to_string(),
+            secret_key: secret_key_from_seed(RELAYER),
+        },
+        state,
+    )
+    .with_retry(retry)
+}
+
+fn relay_all(relayer: &mut HeaderRelayer) -> Vec<u64> {
+    let mut heights = vec![];
+    while let Some(height) = relayer.step().unwrap() {
+        heights.push(height);
+    }
+    heights
+}
+
+fn submitted(target: &MockRpcServer) -> Vec<SignedTransaction> {
+    target
+        .requests_for("broadcast_tx_commit")
+        .iter()
+        .map(|params| {
+            let transaction = utils::from_base64(params[0].as_str().unwrap()).unwrap();
+            SignedTransaction::try_from_slice(&transaction).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn relays_blocks_across_epochs() {
+    let source = source_node(BLOCKS.iter().map(|name| fixture(name)).collect(), 0);
+    let target = target_node(initial_validators(), block(BLOCKS[0]), vec![], 0);
+    let mut relayer = relayer(&source, &target, fast_retry(3));
+
+    let heights = relay_all(&mut relayer);
+    let expected: Vec<u64> = BLOCKS[1..]
+        .iter()
+        .map(|name| block(name).inner_lite.height)
+        .collect();
+    assert_eq!(heights, expected);
+
+    let client = RpcClient::new(target.url(), Duration::from_secs(5));
+    let height: u64 = client
+        .view_call(LIGHT_CLIENT, "current_height", json!({}))
+        .unwrap();
+    assert_eq!(height, 105738335);
+    let head: Option<String> = client
+        .view_call(LIGHT_CLIENT, "block_hashes", json!({ "height": height }))
+        .unwrap();
+    assert_eq!(head, Some(utils::to_base(relayer.state().head_hash())));
+
+    let transactions = submitted(&target);
+    assert_eq!(transactions.len(), expected.len());
+    for (i, transaction) in transactions.iter().enumerate() {
+        assert!(transaction.verify());
+        assert_eq!(transaction.transaction.nonce, i as u64 + 1);
+        assert_eq!(transaction.transaction.signer_id, RELAYER);
+        assert_eq!(transaction.transaction.receiver_id, LIGHT_CLIENT);
+    }
+}
+
+#[test]
+fn retries_unavailable_source() {
+    let source = source_node(BLOCKS[..2].iter().map(|name| fixture(name)).collect(), 2);
+    let target = target_node(initial_validators(), block(BLOCKS[0]), vec![], 0);
+    let mut relayer = relayer(&source, &target, fast_retry(3));
+
+    assert_eq!(relayer.step().unwrap(), Some(105276759));
+    assert_eq!(source.requests_for("next_light_client_block").len(), 3);
+    assert_eq!(submitted(&target).len(), 1);
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let source = source_node(BLOCKS[..2].iter().map(|name| fixture(name)).collect(), 5);
+    let target = target_node(initial_validators(), block(BLOCKS[0]), vec![], 0);
+    let mut relayer = relayer(&source, &target, fast_retry(3));
+
+    let err = relayer.step().unwrap_err();
+    assert!(matches!(err, RelayerError::Transport(_)), "{}", err);
+    assert_eq!(source.requests_for("next_light_client_block").len(), 3);
+    assert_eq!(relayer.state().current_height(), 105233559);
+    assert!(submitted(&target).is_empty());
+}
+
+#[test]
+fn lost_response_is_not_resubmitted() {
+    let source = source_node(BLOCKS[..3].iter().map(|name| fixture(name)).collect(), 0);
+    let target = target_node(initial_validators(), block(BLOCKS[0]), vec![], 1);
+    let mut relayer = relayer(&source, &target, fast_retry(3));
+
+    assert_eq!(relay_all(&mut relayer), [105276759, 105319959]);
+    assert_eq!(submitted(&target).len(), 2);
+}
+
+#[test]
+fn skips_blocks_the_light_client_already_has() {
+    let source = source_node(BLOCKS[..4].iter().map(|name| fixture(name)).collect(), 0);
+    let already_added = vec![block(BLOCKS[1]), block(BLOCKS[2])];
+    let target = target_node(initial_validators(), block(BLOCKS[0]), already_added, 0);
+    let mut relayer = relayer(&source, &target, fast_retry(3));
+
+    assert_eq!(relay_all(&mut relayer), [105276759, 105319959, 105363159]);
+    let transactions = submitted(&target);
+    assert_eq!(transactions.len(), 1);
+    let args = match &transactions[0].transaction.actions[0] {
+        Action::FunctionCall(call) => serde_json::from_slice::<Value>(&call.args).unwrap(),
+        action => panic!("unexpected action {:?}", action),
+    };
+    let submitted: Block = serde_json::from_value(args["block"].clone()).unwrap();
+    assert_eq!(submitted, block(BLOCKS[3]));
+}
+
+#[test]
+fn invalid_block_is_not_submitted() {
+    let mut forged = fixture(BLOCKS[1]);
+    let approvals = forged["approvals_after_next"].as_array_mut().unwrap();
+    approvals.rotate_left(1);
+    let source = source_node(vec![fixture(BLOCKS[0]), forged], 0);
+    let target = target_node(initial_validators(), block(BLOCKS[0]), vec![], 0);
+    let mut relayer = relayer(&source, &target, fast_retry(3));
+
+    let err = relayer.step().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            RelayerError::InvalidBlock {
+                height: 105276759,
+                error: BlockValidationError::SignatureStake
+            }
+        ),
+        "{}",
+        err
+    );
+    assert!(target.requests_for("broadcast_tx_commit").is_empty());
+    assert_eq!(relayer.state().current_height(), 105233559);
+}
+
+#[test]
+fn replica_rejects_blocks_like_the_contract() {
+    let next = block(BLOCKS[1]);
+    let mut cases: Vec<(Block, BlockValidationError)> = vec![
+        (block(BLOCKS[0]), BlockValidationError::Height),
+        (block(BLOCKS[2]), BlockValidationError::EpochId),
+    ];
+    let mut forged = next.clone();
+    forged.approvals_after_next.truncate(1);
+    cases.push((forged, BlockValidationError::ApprovalsTooShort));
+    let mut forged = next.clone();
+    forged
+        .approvals_after_next
+        .iter_mut()
+        .for_each(|a| *a = None);
+    cases.push((forged, BlockValidationError::TooFewApprovals));
+    let mut forged = next.clone();
+    forged.next_bps = None;
+    cases.push((forged, BlockValidationError::NextBpsMissing));
+    let mut forged = next.clone();
+    forged.next_bps.as_mut().unwrap().pop();
+    cases.push((forged, BlockValidationError::NextBpHash));
+    let mut forged = next;
+    forged.approvals_after_next.rotate_left(1);
+    cases.push((forged, BlockValidationError::SignatureStake));
+
+    let state = LightClientState::new(&initial_validators(), &block(BLOCKS[0])).unwrap();
+    for (forged, expected) in cases {
+        assert_eq!(state.validate(&forged), Err(expected.clone()));
+        let mut light_client = init_light_client(initial_validators(), block(BLOCKS[0]));
+        assert_eq!(
+            add_block(&mut light_client, forged),
+            Some(expected.to_string())
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use utils::merkle::MerkleError;
+use utils::Hash;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConversionError {
+    #[error("rpc returned an error: {0}")]
+    Rpc(String),
+    #[error("unexpected rpc response: {0}")]
+    Json(#[from] near_sdk::serde_json::Error),
+    #[error("unknown validator stake version '{0}'")]
+    UnknownValidatorVersion(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    #[error("outcome merkle proof is not valid: computed {computed:?}, block has {expected:?}")]
+    OutcomeRoot { expected: Hash, computed: Hash },
+    #[error("block proof is not valid: computed {computed:?}, expected {expected:?}")]
+    BlockMerkleRoot { expected: Hash, computed: Hash },
+    #[error("next_bps are required for the first block of an epoch")]
+    NextBpsMissing,
+    #[error("hash of block producers does not match next_bp_hash")]
+    NextBpHash,
+    #[error("block hash does not match: computed {computed:?}, expected {expected:?}")]
+    BlockHash { expected: Hash, computed: Hash },
+    #[error("merkle path is not valid: {0}")]
+    MerklePath(#[from] MerkleError),
+}
@@ -0,0 +1,250 @@
+//! Conversions from NEAR JSON-RPC responses into the structures used by the bridge contracts.
+//! Outcome proofs also convert back, for services standing in for a NEAR node in tests.
+//!
+//! This crate is meant for relayers and other off-chain services, it is not built for wasm.
+//! Validation uses the same hashing code as the prover and light client contracts.
+
+pub mod errors;
+pub mod views;
+
+use crate::errors::{ConversionError, ValidationError};
+use crate::views::{
+    BlockHeaderInnerLiteView, ExecutionOutcomeView, ExecutionOutcomeWithIdView,
+    LightClientBlockLiteView, LightClientBlockView, LightClientExecutionProofView,
+    ValidatorStakeView,
+};
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json::{self, Value};
+use types::{
+    Block, BlockHeaderInnerLite, BlockHeaderLight, ExecutionOutcome, ExecutionOutcomeWithId,
+    ExecutionOutcomeWithIdAndProof, FullOutcomeProof, Validator,
+};
+use utils::{Hash, Hashable};
+
+/// Parses the `result` of a JSON-RPC response, a bare result is accepted as well.
+pub fn from_rpc_response<T: DeserializeOwned>(response: Value) -> Result<T, ConversionError> {
+    let result = match response {
+        Value::Object(mut map) if map.contains_key("jsonrpc") => {
+            if let Some(error) = map.remove("error") {
+                return Err(ConversionError::Rpc(error.to_string()));
+            }
+            map.remove("result").unwrap_or(Value::Null)
+        }
+        result => result,
+    };
+    Ok(serde_json::from_value(result)?)
+}
+
+/// Converts a `next_light_client_block` response into a `Block`.
+pub fn light_client_block_from_rpc(response: Value) -> Result<Block, ConversionError> {
+    from_rpc_response::<LightClientBlockView>(response)?.try_into()
+}
+
+/// Converts an `EXPERIMENTAL_light_client_proof` response into a `FullOutcomeProof`.
+pub fn outcome_proof_from_rpc(response: Value) -> Result<FullOutcomeProof, ConversionError> {
+    Ok(from_rpc_response::<LightClientExecutionProofView>(response)?.into())
+}
+
+/// Checks the outcome proof the same way the prover does. The block proof is only
+/// checked when the merkle root of the block used for proving is known.
+pub fn validate_outcome_proof(
+    proof: &FullOutcomeProof,
+    block_merkle_root: Option<Hash>,
+) -> Result<(), ValidationError> {
+    let computed = proof.outcome_root()?;
+    let expected = proof.block_header_lite.inner_lite.outcome_root;
+    if computed != expected {
+        return Err(ValidationError::OutcomeRoot { expected, computed });
+    }
+
+    if let Some(expected) = block_merkle_root {
+        let computed = proof.block_merkle_root()?;
+        if computed != expected {
+            return Err(ValidationError::BlockMerkleRoot { expected, computed });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the block hashes to `expected_hash`. Approvals need the epoch's block
+/// producers and are checked by the light client itself.
+pub fn validate_block(block: &Block, expected_hash: Hash) -> Result<(), ValidationError> {
+    let computed = block.hash();
+    if computed != expected_hash {
+        return Err(ValidationError::BlockHash {
+            expected: expected_hash,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Checks `next_bps` against the committed `next_bp_hash`. The light client requires this
+/// for the first block it accepts from the next epoch, other blocks may omit `next_bps`.
+pub fn validate_next_bps(block: &Block) -> Result<(), ValidationError> {
+    let next_bps = block
+        .next_bps
+        .as_ref()
+        .ok_or(ValidationError::NextBpsMissing)?;
+    if types::block_producers_hash(next_bps) != block.inner_lite.next_bp_hash {
+        return Err(ValidationError::NextBpHash);
+    }
+    Ok(())
+}
+
+impl TryFrom<LightClientBlockView> for Block {
+    type Error = ConversionError;
+
+    fn try_from(view: LightClientBlockView) -> Result<Self, Self::Error> {
+        let next_bps = view
+            .next_bps
+            .map(|next_bps| {
+                next_bps
+                    .into_iter()
+                    .map(Validator::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        Ok(Block {
+            prev_block_hash: view.prev_block_hash,
+            next_block_inner_hash: view.next_block_inner_hash,
+            inner_lite: view.inner_lite.into(),
+            inner_rest_hash: view.inner_rest_hash,
+            next_bps,
+            approvals_after_next: view.approvals_after_next,
+        })
+    }
+}
+
+impl TryFrom<ValidatorStakeView> for Validator {
+    type Error = ConversionError;
+
+    fn try_from(view: ValidatorStakeView) -> Result<Self, Self::Error> {
+        match view.validator_stake_struct_version.as_deref() {
+            None | Some("V1") if view.is_chunk_only.is_none() => Ok(Validator::new_v1(
+                view.account_id,
+                view.public_key,
+                view.stake,
+            )),
+            None | Some("V2") => Ok(Validator::new_v2(
+                view.account_id,
+                view.public_key,
+                view.stake,
+                view.is_chunk_only.unwrap_or_default(),
+            )),
+            Some(version) => Err(ConversionError::UnknownValidatorVersion(
+                version.to_string(),
+            )),
+        }
+    }
+}
+
+impl From<BlockHeaderInnerLiteView> for BlockHeaderInnerLite {
+    fn from(view: BlockHeaderInnerLiteView) -> Self {
+        BlockHeaderInnerLite {
+            height: view.height,
+            epoch_id: view.epoch_id,
+            next_epoch_id: view.next_epoch_id,
+            prev_state_root: view.prev_state_root,
+            outcome_root: view.outcome_root,
+            timestamp: view.timestamp_nanosec,
+            next_bp_hash: view.next_bp_hash,
+            block_merkle_root: view.block_merkle_root,
+        }
+    }
+}
+
+impl From<LightClientExecutionProofView> for FullOutcomeProof {
+    fn from(view: LightClientExecutionProofView) -> Self {
+        FullOutcomeProof {
+            outcome_proof: view.outcome_proof.into(),
+            outcome_root_proof: view.outcome_root_proof,
+            block_header_lite: view.block_header_lite.into(),
+            block_proof: view.block_proof,
+        }
+    }
+}
+
+impl From<ExecutionOutcomeWithIdView> for ExecutionOutcomeWithIdAndProof {
+    fn from(view: ExecutionOutcomeWithIdView) -> Self {
+        ExecutionOutcomeWithIdAndProof {
+            proof: view.proof,
+            block_hash: view.block_hash,
+            outcome_with_id: ExecutionOutcomeWithId {
+                id: view.id,
+                outcome: view.outcome.into(),
+            },
+        }
+    }
+}
+
+impl From<ExecutionOutcomeView> for ExecutionOutcome {
+    fn from(view: ExecutionOutcomeView) -> Self {
+        ExecutionOutcome {
+            logs: view.logs.into_iter().map(String::into_bytes).collect(),
+            receipt_ids: view.receipt_ids,
+            gas_burnt: view.gas_burnt,
+            tokens_burnt: view.tokens_burnt,
+            executor_id: view.executor_id,
+            status: view.status,
+        }
+    }
+}
+
+impl From<LightClientBlockLiteView> for BlockHeaderLight {
+    fn from(view: LightClientBlockLiteView) -> Self {
+        BlockHeaderLight {
+            prev_block_hash: view.prev_block_hash,
+            inner_rest_hash: view.inner_rest_hash,
+            inner_lite: view.inner_lite.into(),
+        }
+    }
+}
+
+impl From<BlockHeaderInnerLite> for BlockHeaderInnerLiteView {
+    fn from(inner_lite: BlockHeaderInnerLite) -> Self {
+        BlockHeaderInnerLiteView {
+            height: inner_lite.height,
+            epoch_id: inner_lite.epoch_id,
+            next_epoch_id: inner_lite.next_epoch_id,
+            prev_state_root: inner_lite.prev_state_root,
+            outcome_root: inner_lite.outcome_root,
+            timestamp_nanosec: inner_lite.timestamp,
+            next_bp_hash: inner_lite.next_bp_hash,
+            block_merkle_root: inner_lite.block_merkle_root,
+        }
+    }
+}
+
+impl From<FullOutcomeProof> for LightClientExecutionProofView {
+    fn from(proof: FullOutcomeProof) -> Self {
+        let outcome = proof.outcome_proof.outcome_with_id.outcome;
+        LightClientExecutionProofView {
+            outcome_proof: ExecutionOutcomeWithIdView {
+                proof: proof.outcome_proof.proof,
+                block_hash: proof.outcome_proof.block_hash,
+                id: proof.outcome_proof.outcome_with_id.id,
+                outcome: ExecutionOutcomeView {
+                    logs: outcome
+                        .logs
+                        .into_iter()
+                        .map(|log| String::from_utf8_lossy(&log).into_owned())
+                        .collect(),
+                    receipt_ids: outcome.receipt_ids,
+                    gas_burnt: outcome.gas_burnt,
+                    tokens_burnt: outcome.tokens_burnt,
+                    executor_id: outcome.executor_id,
+                    status: outcome.status,
+                },
+            },
+            outcome_root_proof: proof.outcome_root_proof,
+            block_header_lite: LightClientBlockLiteView {
+                prev_block_hash: proof.block_header_lite.prev_block_hash,
+                inner_rest_hash: proof.block_header_lite.inner_rest_hash,
+                inner_lite: proof.block_header_lite.inner_lite.into(),
+            },
+            block_proof: proof.block_proof,
+        }
+    }
+}
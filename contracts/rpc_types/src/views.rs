@@ -0,0 +1,149 @@
+//! Structures as returned by the NEAR JSON-RPC, only the fields the bridge needs are kept.
+
+use near_sdk::serde::{Deserialize, Serialize};
+use types::{ExecutionStatus, MerklePath, PublicKey, Signature};
+use utils::{
+    base_hash_format, base_hash_format_many, u128_dec_format_compatible, u64_dec_format_compatible,
+    Hash,
+};
+
+/// Result of `next_light_client_block`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LightClientBlockView {
+    #[serde(with = "base_hash_format")]
+    pub prev_block_hash: Hash,
+    #[serde(with = "base_hash_format")]
+    pub next_block_inner_hash: Hash,
+    pub inner_lite: BlockHeaderInnerLiteView,
+    #[serde(with = "base_hash_format")]
+    pub inner_rest_hash: Hash,
+    #[serde(default)]
+    pub next_bps: Option<Vec<ValidatorStakeView>>,
+    pub approvals_after_next: Vec<Option<Signature>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BlockHeaderInnerLiteView {
+    #[serde(with = "u64_dec_format_compatible")]
+    pub height: u64,
+    #[serde(with = "base_hash_format")]
+    pub epoch_id: Hash,
+    #[serde(with = "base_hash_format")]
+    pub next_epoch_id: Hash,
+    #[serde(with = "base_hash_format")]
+    pub prev_state_root: Hash,
+    #[serde(with = "base_hash_format")]
+    pub outcome_root: Hash,
+    // `timestamp` is also returned, but as a JSON number it loses precision in most clients
+    #[serde(with = "u64_dec_format_compatible")]
+    pub timestamp_nanosec: u64,
+    #[serde(with = "base_hash_format")]
+    pub next_bp_hash: Hash,
+    #[serde(with = "base_hash_format")]
+    pub block_merkle_root: Hash,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ValidatorStakeView {
+    /// Missing in captures made before nearcore versioned validator stakes, those are V1.
+    #[serde(default)]
+    pub validator_stake_struct_version: Option<String>,
+    pub account_id: String,
+    pub public_key: PublicKey,
+    #[serde(with = "u128_dec_format_compatible")]
+    pub stake: u128,
+    #[serde(default)]
+    pub is_chunk_only: Option<bool>,
+}
+
+/// Result of `EXPERIMENTAL_light_client_proof`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LightClientExecutionProofView {
+    pub outcome_proof: ExecutionOutcomeWithIdView,
+    pub outcome_root_proof: MerklePath,
+    pub block_header_lite: LightClientBlockLiteView,
+    pub block_proof: MerklePath,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecutionOutcomeWithIdView {
+    pub proof: MerklePath,
+    #[serde(with = "base_hash_format")]
+    pub block_hash: Hash,
+    #[serde(with = "base_hash_format")]
+    pub id: Hash,
+    pub outcome: ExecutionOutcomeView,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecutionOutcomeView {
+    pub logs: Vec<String>,
+    #[serde(with = "base_hash_format_many")]
+    pub receipt_ids: Vec<Hash>,
+    #[serde(with = "u64_dec_format_compatible")]
+    pub gas_burnt: u64,
+    #[serde(with = "u128_dec_format_compatible")]
+    pub tokens_burnt: u128,
+    pub executor_id: String,
+    pub status: ExecutionStatus,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LightClientBlockLiteView {
+    #[serde(with = "base_hash_format")]
+    pub prev_block_hash: Hash,
+    #[serde(with = "base_hash_format")]
+    pub inner_rest_hash: Hash,
+    pub inner_lite: BlockHeaderInnerLiteView,
+}
+
+/// Result of `block`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BlockView {
+    pub header: BlockHeaderView,
+    pub chunks: Vec<ChunkHeaderView>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BlockHeaderView {
+    #[serde(with = "u64_dec_format_compatible")]
+    pub height: u64,
+    #[serde(with = "base_hash_format")]
+    pub hash: Hash,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChunkHeaderView {
+    #[serde(with = "base_hash_format")]
+    pub chunk_hash: Hash,
+    pub shard_id: u64,
+    /// Chunks missing in a block are repeated from an earlier block, with its height.
+    #[serde(with = "u64_dec_format_compatible")]
+    pub height_included: u64,
+}
+
+/// Result of `chunk`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChunkView {
+    pub receipts: Vec<ReceiptView>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReceiptView {
+    #[serde(with = "base_hash_format")]
+    pub receipt_id: Hash,
+    pub predecessor_id: String,
+    pub receiver_id: String,
+}
@@ -0,0 +1,232 @@
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json::{self, json, Value};
+use rpc_types::errors::{ConversionError, ValidationError};
+use rpc_types::views::{BlockView, ChunkView, LightClientExecutionProofView};
+use rpc_types::{
+    from_rpc_response, light_client_block_from_rpc, outcome_proof_from_rpc, validate_block,
+    validate_next_bps, validate_outcome_proof,
+};
+use types::{Block, FullOutcomeProof};
+use utils::hashes::decode_hex;
+use utils::{Hash, Hashable};
+
+fn fixture(path: &str) -> Value {
+    let content = std::fs::read_to_string(format!("../{}", path)).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+fn fixture_as<T: DeserializeOwned>(path: &str) -> T {
+    serde_json::from_value(fixture(path)).unwrap()
+}
+
+fn hash(hex: &str) -> Hash {
+    decode_hex(hex).try_into().unwrap()
+}
+
+const BLOCKS: &[&str] = &[
+    "light_client/tests/block_9605.json",
+    "light_client/tests/block_9610.json",
+    "light_client/tests/block_93439858.json",
+    "light_client/tests/block_105190359.json",
+    "light_client/tests/block_115780580.json",
+    "light_client/tests/block_115799227.json",
+    "light_client/tests/network_block1.json",
+    "light_client/tests/local_block_1.json",
+];
+
+const PROOFS: &[(&str, &str)] = &[
+    (
+        "prover/tests/proof2.json",
+        "22f00dd154366d758cd3e4fe81c1caed8e0db6227fe4b2b52a8e5a468aa0a723",
+    ),
+    (
+        "prover/tests/proof3.json",
+        "0d0776820a9a81481a559c36fd5d69c33718fb7d7fd3be7564a446e043e2cb35",
+    ),
+    (
+        "prover/tests/proof7.json",
+        "8298c9cd1048df03e9ccefac4b022636a30a2f7e6a8c33cc4104901b92e08dfd",
+    ),
+    (
+        "prover/tests/proof10.json",
+        "685eba49d8de4d6020db910f2f982305b546f85b151ba9b78c281785d0731475",
+    ),
+];
+
+#[test]
+fn blocks_match_contract_format() {
+    for path in BLOCKS {
+        let block = light_client_block_from_rpc(fixture(path)).unwrap();
+        assert_eq!(block, fixture_as::<Block>(path), "{}", path);
+    }
+}
+
+#[test]
+fn block_hashes() {
+    let block = light_client_block_from_rpc(fixture(BLOCKS[0])).unwrap();
+    let expected = hash("c4770276d5e782d847ea3ce0674894a572df3ea75b960ff57d66395df0eb2a34");
+    assert_eq!(block.hash(), expected);
+    validate_block(&block, expected).unwrap();
+
+    let block = light_client_block_from_rpc(fixture(BLOCKS[1])).unwrap();
+    let expected = hash("f28629da269e59f2494c6bf283e9e67dadaa1c1f753607650d21e5e5b916a0dc");
+    validate_block(&block, expected).unwrap();
+}
+
+#[test]
+fn block_with_wrong_hash() {
+    let block = light_client_block_from_rpc(fixture(BLOCKS[0])).unwrap();
+    let wrong = hash("f28629da269e59f2494c6bf283e9e67dadaa1c1f753607650d21e5e5b916a0dc");
+    assert!(matches!(
+        validate_block(&block, wrong),
+        Err(ValidationError::BlockHash { .. })
+    ));
+}
+
+#[test]
+fn epoch_switching_blocks_next_bps() {
+    // block_9605 predates versioned validator stakes, its next_bp_hash is over untagged stakes
+    for path in [
+        "light_client/tests/block_93439858.json",
+        "light_client/tests/block_105190359.json",
+        "light_client/tests/block_105233559.json",
+        "light_client/tests/block_115780580.json",
+    ] {
+        let block = light_client_block_from_rpc(fixture(path)).unwrap();
+        validate_next_bps(&block).unwrap();
+    }
+}
+
+#[test]
+fn block_with_tampered_next_bps() {
+    let mut block = light_client_block_from_rpc(fixture(BLOCKS[3])).unwrap();
+    let next_bps = block.next_bps.as_mut().expect("fixture has next_bps");
+    next_bps.pop();
+    assert_eq!(validate_next_bps(&block), Err(ValidationError::NextBpHash));
+
+    block.next_bps = None;
+    assert_eq!(
+        validate_next_bps(&block),
+        Err(ValidationError::NextBpsMissing)
+    );
+}
+
+#[test]
+fn jsonrpc_envelope() {
+    let response = json!({
+        "jsonrpc": "2.0",
+        "id": "dontcare",
+        "result": fixture(BLOCKS[0]),
+    });
+    let block = light_client_block_from_rpc(response).unwrap();
+    assert_eq!(block, fixture_as::<Block>(BLOCKS[0]));
+}
+
+#[test]
+fn jsonrpc_error() {
+    let response = json!({
+        "jsonrpc": "2.0",
+        "id": "dontcare",
+        "error": {"name": "HANDLER_ERROR", "cause": {"name": "UNKNOWN_BLOCK"}},
+    });
+    assert!(matches!(
+        light_client_block_from_rpc(response),
+        Err(ConversionError::Rpc(_))
+    ));
+}
+
+#[test]
+fn proofs_match_contract_format() {
+    for (path, root) in PROOFS {
+        let proof = outcome_proof_from_rpc(fixture(path)).unwrap();
+        assert_eq!(proof, fixture_as::<FullOutcomeProof>(path), "{}", path);
+        validate_outcome_proof(&proof, Some(hash(root))).unwrap();
+    }
+}
+
+#[test]
+fn proofs_convert_back_to_rpc_format() {
+    for (path, _) in PROOFS {
+        let proof = outcome_proof_from_rpc(fixture(path)).unwrap();
+        let view = LightClientExecutionProofView::from(proof.clone());
+        let response = serde_json::to_value(&view).unwrap();
+        assert_eq!(outcome_proof_from_rpc(response).unwrap(), proof, "{}", path);
+    }
+}
+
+#[test]
+fn blocks_and_chunks() {
+    let block_hash = utils::hashes::sha256(b"block");
+    let chunk_hash = utils::hashes::sha256(b"chunk");
+    let receipt_id = utils::hashes::sha256(b"receipt");
+    let block: BlockView = from_rpc_response(json!({
+        "author": "node0",
+        "header": {
+            "height": 1052,
+            "hash": utils::to_base(block_hash),
+        },
+        "chunks": [{
+            "chunk_hash": utils::to_base(chunk_hash),
+            "shard_id": 0,
+            "height_included": 1052,
+            "height_created": 1052,
+        }],
+    }))
+    .unwrap();
+    assert_eq!(block.header.height, 1052);
+    assert_eq!(block.header.hash, block_hash);
+    assert_eq!(block.chunks[0].chunk_hash, chunk_hash);
+    assert_eq!(block.chunks[0].height_included, 1052);
+
+    let chunk: ChunkView = from_rpc_response(json!({
+        "receipts": [{
+            "predecessor_id": "alice.near",
+            "receiver_id": "ft_connector.near",
+            "receipt_id": utils::to_base(receipt_id),
+            "receipt": { "Action": { "actions": [] } },
+        }],
+        "transactions": [],
+    }))
+    .unwrap();
+    assert_eq!(chunk.receipts[0].receiver_id, "ft_connector.near");
+    assert_eq!(chunk.receipts[0].receipt_id, receipt_id);
+}
+
+#[test]
+fn connector_proofs() {
+    for path in [
+        "ft_connector/tests/destination_test_assets/lock_proof.json",
+        "ft_connector/tests/destination_test_assets/lock_proof2.json",
+        "ft_connector/tests/source_test_assets/burn_proof.json",
+        "ft_connector/tests/source_test_assets/deploy_proof.json",
+    ] {
+        let proof = outcome_proof_from_rpc(fixture(path)).unwrap();
+        assert_eq!(proof, fixture_as::<FullOutcomeProof>(path), "{}", path);
+        validate_outcome_proof(&proof, None).unwrap();
+    }
+}
+
+#[test]
+fn proof_with_wrong_block_merkle_root() {
+    let (path, _) = PROOFS[0];
+    let proof = outcome_proof_from_rpc(fixture(path)).unwrap();
+    let wrong = hash("22f00dd154366d758cd3e4fe81c1caed8e0db6227fe4b2b52a8e5a468aa0a724");
+    assert!(matches!(
+        validate_outcome_proof(&proof, Some(wrong)),
+        Err(ValidationError::BlockMerkleRoot { .. })
+    ));
+}
+
+#[test]
+fn incorrect_proofs() {
+    for path in [
+        "prover/tests/incorrect_proof1.json",
+        "prover/tests/incorrect_proof2.json",
+    ] {
+        let proof = outcome_proof_from_rpc(fixture(path)).unwrap();
+        assert!(matches!(
+            validate_outcome_proof(&proof, None),
+            Err(ValidationError::OutcomeRoot { .. })
+        ));
+    }
+}
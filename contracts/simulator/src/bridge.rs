@@ -0,0 +1,461 @@
+//! The bridge deployed on a simulated NEAR chain and a simulated Calimero shard, with a
+//! relayer moving headers and connector events between them.
+
+use crate::contracts::{
+    bridge_token_code, bridge_token_deployer_code, connector_permissions_code, ft_connector_code,
+    light_client_code, prover_code, xsc_connector_code,
+};
+use crate::runtime::{CallResult, Runtime};
+use near_sdk::serde_json::{json, Value};
+use near_sdk::{AccountId, Balance, ONE_NEAR};
+use test_utils::chain::ChainGenerator;
+use types::{ConnectorType, EncodedOutcomeProof, ExecutionStatus};
+use utils::Hash;
+
+/// Balance of every account created by the simulator.
+pub const ACCOUNT_BALANCE: Balance = 1_000 * ONE_NEAR;
+
+/// Deposit the relayer attaches to connector calls, it covers recording the proof.
+pub const RELAY_DEPOSIT: Balance = ONE_NEAR / 10;
+
+/// Deposit covering the bridge token account and its storage.
+pub const DEPLOY_TOKEN_DEPOSIT: Balance = 6 * ONE_NEAR;
+
+/// Connector method the relayer calls for each event on the other side.
+const ROUTES: &[(&str, &str)] = &[
+    ("CALIMERO_EVENT_DEPLOY_FT", "register_on_other"),
+    ("CALIMERO_EVENT_LOCK_FT", "mint"),
+    ("CALIMERO_EVENT_BURN_FT", "unlock"),
+    ("CALIMERO_EVENT_MINTED_FT", "acknowledge"),
+    ("CALIMERO_EVENT_MINT_FAILED_FT", "refund"),
+    ("CALIMERO_EVENT_CANCEL_FT", "cancel_mint"),
+    ("CALIMERO_EVENT_CROSS_CALL", "cross_call_execute"),
+    (
+        "CALIMERO_EVENT_CROSS_RESPONSE",
+        "cross_call_receive_response",
+    ),
+];
+
+/// Accounts of the bridge contracts on one side.
+#[derive(Debug, Clone)]
+pub struct BridgeAccounts {
+    /// Chain id the connectors on this side are configured with.
+    pub chain_id: String,
+    /// Light client of the other side.
+    pub light_client: AccountId,
+    pub prover: AccountId,
+    pub permissions: AccountId,
+    pub ft_connector: AccountId,
+    /// Only registered in the permissions, the NFT connector is not deployed.
+    pub nft_connector: AccountId,
+    pub xsc_connector: AccountId,
+    /// Only deployed on the Calimero side, where bridged tokens are created.
+    pub bridge_token_deployer: AccountId,
+    /// Signs all calls made by the relayer on this side.
+    pub relayer: AccountId,
+}
+
+impl BridgeAccounts {
+    pub fn for_chain(suffix: &str) -> Self {
+        let account = |name: &str| -> AccountId { format!("{}.{}", name, suffix).parse().unwrap() };
+        Self {
+            chain_id: suffix.to_string(),
+            light_client: account("light_client"),
+            prover: account("prover"),
+            permissions: account("permissions"),
+            ft_connector: account("ft_connector"),
+            nft_connector: account("nft_connector"),
+            xsc_connector: account("xsc_connector"),
+            bridge_token_deployer: account("ft_deployer"),
+            relayer: account("relayer"),
+        }
+    }
+}
+
+/// Call made by the relayer, kept so tests can inspect or replay it.
+#[derive(Debug, Clone)]
+pub struct RelayedCall {
+    pub receiver_id: AccountId,
+    pub method: String,
+    pub args: Value,
+    pub result: CallResult,
+}
+
+pub struct BridgeSimulator {
+    pub near: Runtime,
+    pub calimero: Runtime,
+    pub near_accounts: BridgeAccounts,
+    pub calimero_accounts: BridgeAccounts,
+    /// Number of included outcomes already scanned for events, per side.
+    near_cursor: usize,
+    calimero_cursor: usize,
+}
+
+impl Default for BridgeSimulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BridgeSimulator {
+    /// Deploys and wires up the bridge on both sides. Permissions allow every account to use
+    /// the FT and cross shard connectors.
+    pub fn new() -> Self {
+        let mut simulator = Self {
+            near: Runtime::new(ChainGenerator::new("near", 100, 20)),
+            calimero: Runtime::new(ChainGenerator::new("calimero", 1, 10)),
+            near_accounts: BridgeAccounts::for_chain("near"),
+            calimero_accounts: BridgeAccounts::for_chain("calimero"),
+            near_cursor: 0,
+            calimero_cursor: 0,
+        };
+        // light clients are initialized with the genesis block, which needs its successor
+        simulator.near.produce_block();
+        simulator.calimero.produce_block();
+
+        let near_chain = simulator.near.chain();
+        let calimero_chain = simulator.calimero.chain();
+        let near_genesis = (near_chain.validators(0), near_chain.light_client_block(100));
+        let calimero_genesis = (
+            calimero_chain.validators(0),
+            calimero_chain.light_client_block(1),
+        );
+
+        deploy_bridge(
+            &mut simulator.near,
+            &simulator.near_accounts,
+            &simulator.calimero_accounts,
+            calimero_genesis,
+        );
+        deploy_bridge(
+            &mut simulator.calimero,
+            &simulator.calimero_accounts,
+            &simulator.near_accounts,
+            near_genesis,
+        );
+
+        let accounts = simulator.calimero_accounts.clone();
+        let calimero = &mut simulator.calimero;
+        calimero.create_account(&accounts.bridge_token_deployer, ACCOUNT_BALANCE);
+        calimero.deploy(&accounts.bridge_token_deployer, bridge_token_deployer_code);
+        calimero.register_code(
+            bridge_token_deployer::BRIDGE_TOKEN_BINARY,
+            bridge_token_code,
+        );
+        calimero
+            .call(
+                &accounts.bridge_token_deployer,
+                &accounts.bridge_token_deployer,
+                "new",
+                json!({
+                    "bridge_account": accounts.ft_connector,
+                    "source_master_account": "near",
+                }),
+                0,
+            )
+            .unwrap();
+        calimero
+            .call(
+                &accounts.ft_connector,
+                &accounts.ft_connector,
+                "set_deployer",
+                json!({ "deployer_account": accounts.bridge_token_deployer }),
+                0,
+            )
+            .unwrap();
+
+        simulator.near.produce_block();
+        simulator.calimero.produce_block();
+        // nothing that happened during the setup has to be relayed
+        simulator.near_cursor = simulator.near.included_outcomes().len();
+        simulator.calimero_cursor = simulator.calimero.included_outcomes().len();
+        simulator
+    }
+
+    /// Deploys the bridged version of the NEAR token `source` on Calimero, relays the mapping
+    /// back to NEAR and returns the account of the bridged token.
+    pub fn deploy_bridge_token(&mut self, source: &AccountId) -> AccountId {
+        let connector = self.calimero_accounts.ft_connector.clone();
+        let (token,): (AccountId,) = self
+            .calimero
+            .call(
+                &connector,
+                &connector,
+                "deploy_bridge_token",
+                json!({ "source_address": source }),
+                DEPLOY_TOKEN_DEPOSIT,
+            )
+            .unwrap()
+            .json();
+        for call in self.relay() {
+            call.result.unwrap();
+        }
+        token
+    }
+
+    /// Relays headers and events in both directions until neither side emits new events.
+    /// Returns the connector calls made, a failed call does not stop the relayer.
+    pub fn relay(&mut self) -> Vec<RelayedCall> {
+        let mut relayed = vec![];
+        loop {
+            let mut calls = relay_events(
+                &mut self.near,
+                &mut self.calimero,
+                &self.near_accounts,
+                &self.calimero_accounts,
+                &mut self.near_cursor,
+            );
+            calls.extend(relay_events(
+                &mut self.calimero,
+                &mut self.near,
+                &self.calimero_accounts,
+                &self.near_accounts,
+                &mut self.calimero_cursor,
+            ));
+            if calls.is_empty() {
+                return relayed;
+            }
+            relayed.extend(calls);
+        }
+    }
+
+    /// Moves both light clients far enough for everything included so far to be proven,
+    /// leaving the events to an external relayer.
+    pub fn relay_headers(&mut self) {
+        follow(&mut self.near, &mut self.calimero, &self.calimero_accounts);
+        follow(&mut self.calimero, &mut self.near, &self.near_accounts);
+    }
+}
+
+/// Moves the light client on `dst` past the last outcome `src` included.
+fn follow(src: &mut Runtime, dst: &mut Runtime, dst_accounts: &BridgeAccounts) {
+    if src.has_pending_outcomes() {
+        src.produce_block();
+    }
+    let last_included = src
+        .included_outcomes()
+        .last()
+        .map_or(0, |(height, _)| *height);
+    // same as for events, the head needs a successor signing it
+    while src.chain().head_height() < last_included + 2 {
+        src.produce_block();
+    }
+    submit_headers(
+        src.chain(),
+        dst,
+        dst_accounts,
+        src.chain().head_height() - 1,
+    );
+}
+
+/// Deploys the contracts of one side, its light client follows the other side starting
+/// from `genesis`.
+fn deploy_bridge(
+    runtime: &mut Runtime,
+    accounts: &BridgeAccounts,
+    other: &BridgeAccounts,
+    genesis: (Vec<types::Validator>, types::Block),
+) {
+    runtime.create_account(&accounts.relayer, ACCOUNT_BALANCE);
+    for (account_id, code) in [
+        (
+            &accounts.light_client,
+            light_client_code as crate::ContractCode,
+        ),
+        (&accounts.prover, prover_code),
+        (&accounts.permissions, connector_permissions_code),
+        (&accounts.ft_connector, ft_connector_code),
+        (&accounts.xsc_connector, xsc_connector_code),
+    ] {
+        runtime.create_account(account_id, ACCOUNT_BALANCE);
+        runtime.deploy(account_id, code);
+    }
+
+    let mut setup = |signer: &AccountId, receiver: &AccountId, method: &str, args: Value| {
+        let deposit = if method == "add_allow_regex_rule" {
+            ONE_NEAR
+        } else {
+            0
+        };
+        runtime
+            .call(signer, receiver, method, args, deposit)
+            .unwrap();
+    };
+    let (validators, block) = genesis;
+    setup(
+        &accounts.light_client,
+        &accounts.light_client,
+        "new",
+        json!({ "max_blocks": null }),
+    );
+    setup(
+        &accounts.light_client,
+        &accounts.light_client,
+        "init_with_validators",
+        json!({ "initial_validators": validators }),
+    );
+    setup(
+        &accounts.light_client,
+        &accounts.light_client,
+        "init_with_block",
+        json!({ "block": block }),
+    );
+    setup(
+        &accounts.prover,
+        &accounts.prover,
+        "new",
+        json!({ "light_client_account_id": accounts.light_client }),
+    );
+    setup(
+        &accounts.permissions,
+        &accounts.permissions,
+        "new",
+        json!({
+            "ft_connector_account": accounts.ft_connector,
+            "nft_connector_account": accounts.nft_connector,
+            "xsc_connector_account": accounts.xsc_connector,
+        }),
+    );
+    for (connector, locker, connector_type) in [
+        (
+            &accounts.ft_connector,
+            &other.ft_connector,
+            ConnectorType::FT,
+        ),
+        (
+            &accounts.xsc_connector,
+            &other.xsc_connector,
+            ConnectorType::XSC,
+        ),
+    ] {
+        setup(
+            connector,
+            connector,
+            "new",
+            json!({
+                "prover_account": accounts.prover,
+                "connector_permissions_account": accounts.permissions,
+                "proof_validity_ns": null,
+            }),
+        );
+        setup(
+            connector,
+            connector,
+            "set_locker",
+            json!({ "locker_account": locker }),
+        );
+        setup(
+            connector,
+            connector,
+            "set_chain_domain",
+            json!({ "chain_id": accounts.chain_id, "peer_chain_id": other.chain_id }),
+        );
+        setup(
+            connector,
+            &accounts.permissions,
+            "add_allow_regex_rule",
+            json!({ "regex_rule": ".*", "connector_type": connector_type }),
+        );
+    }
+}
+
+/// Relays the events `src` included since `cursor` to `dst`, submitting the headers the
+/// light client of `dst` needs to verify them first.
+fn relay_events(
+    src: &mut Runtime,
+    dst: &mut Runtime,
+    src_accounts: &BridgeAccounts,
+    dst_accounts: &BridgeAccounts,
+    cursor: &mut usize,
+) -> Vec<RelayedCall> {
+    if src.has_pending_outcomes() {
+        src.produce_block();
+    }
+    let events: Vec<(u64, Hash, AccountId, &str)> = src.included_outcomes()[*cursor..]
+        .iter()
+        .filter(|(_, outcome)| {
+            matches!(
+                outcome.outcome.status,
+                ExecutionStatus::SuccessValue(_) | ExecutionStatus::SuccessReceiptId(_)
+            )
+        })
+        .filter_map(|(height, outcome)| {
+            let receiver_id = if outcome.outcome.executor_id == src_accounts.ft_connector.as_str() {
+                dst_accounts.ft_connector.clone()
+            } else if outcome.outcome.executor_id == src_accounts.xsc_connector.as_str() {
+                dst_accounts.xsc_connector.clone()
+            } else {
+                return None;
+            };
+            let log = outcome.outcome.logs.first()?;
+            let (_, method) = ROUTES
+                .iter()
+                .find(|(event, _)| log.starts_with(format!("{}:", event).as_bytes()))?;
+            Some((*height, outcome.id, receiver_id, *method))
+        })
+        .collect();
+    *cursor = src.included_outcomes().len();
+
+    let last_event_height = match events.iter().map(|(height, ..)| *height).max() {
+        Some(height) => height,
+        None => return vec![],
+    };
+    // the proof is checked against a block after the event, whose successor is needed to sign it
+    while src.chain().head_height() < last_event_height + 2 {
+        src.produce_block();
+    }
+    let height = submit_headers(
+        src.chain(),
+        dst,
+        dst_accounts,
+        src.chain().head_height() - 1,
+    );
+
+    events
+        .into_iter()
+        .map(|(_, outcome_id, receiver_id, method)| {
+            let proof = src.chain().outcome_proof(&outcome_id, height);
+            let args = json!({ "proof": EncodedOutcomeProof::borsh(&proof), "height": height });
+            let result = dst.call(
+                &dst_accounts.relayer,
+                &receiver_id,
+                method,
+                args.clone(),
+                RELAY_DEPOSIT,
+            );
+            RelayedCall {
+                receiver_id,
+                method: method.to_string(),
+                args,
+                result,
+            }
+        })
+        .collect()
+}
+
+/// Moves the light client on `dst` to `target`, submitting the first block of every epoch
+/// in between so it can follow the validator changes. Returns the height of its head.
+fn submit_headers(
+    chain: &ChainGenerator,
+    dst: &mut Runtime,
+    dst_accounts: &BridgeAccounts,
+    target: u64,
+) -> u64 {
+    let current: u64 = dst.view(&dst_accounts.light_client, "current_height", json!({}));
+    if current >= target {
+        return current;
+    }
+    let epoch_starts =
+        (chain.epoch_of(current) + 1..chain.epoch_of(target)).map(|epoch| chain.epoch_start(epoch));
+    for height in epoch_starts.chain([target]) {
+        dst.call(
+            &dst_accounts.relayer,
+            &dst_accounts.light_client,
+            "add_light_client_block",
+            json!({ "block": chain.light_client_block(height) }),
+            0,
+        )
+        .unwrap();
+    }
+    target
+}
@@ -0,0 +1,449 @@
+//! Native code of the bridge contracts.
+//!
+//! [`contract_code!`](crate::contract_code) builds a [`ContractCode`](crate::runtime::ContractCode)
+//! that does what the wasm entrypoints generated by `near_bindgen` do: it deserializes the
+//! arguments, reads the contract state, calls the method, writes the state back and checks
+//! `#[private]` and `#[payable]`. Methods are listed with the argument types as they are sent,
+//! methods taking references call the contract through an explicit closure.
+//!
+//! Failures raised here use `panic!`. Contracts failing through `env::panic_str` go through
+//! the `extern "C"` host functions of the mocked blockchain, compilers that do not unwind out
+//! of those abort the process instead of failing the receipt.
+
+use admin_controlled::{AdminControlled, Mask};
+use bridge_token::BridgeToken;
+use bridge_token_deployer::BridgeTokenDeployer;
+use connector_base::claims::Claim;
+use connector_base::events::ChainDomain;
+use connector_base::fees::FeeSchedule;
+use connector_base::limits::{GlobalLimit, Limit};
+use connector_base::queue::QueuedTransfer;
+use connector_base::transfers::{Transfer, TransferStatus};
+use connector_base::{
+    DeployerAware, FeeAware, LimitAware, OtherNetworkAware, OtherNetworkTokenAware, TokenMint,
+    TokenUnlock, TransferQueue,
+};
+use connector_permissions::ConnectorPermissions;
+use ft_connector::FungibleTokenConnector;
+use light_client::LightClient;
+use mock_light_client::{BlockMerkleRootsCall, MockLightClient};
+use mock_prover::{MockProver, ProveOutcomeCall};
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_contract_standards::fungible_token::resolver::FungibleTokenResolver;
+use near_contract_standards::storage_management::{StorageBalance, StorageManagement};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json::{self, Value};
+use near_sdk::{env, AccountId, Balance, Gas};
+use prover::Prover;
+use types::{Block, ConnectorType, EncodedOutcomeProof, MerklePath, Validator};
+use utils::Hash;
+use xsc_connector::CrossShardConnector;
+
+/// Builds a `fn(method, args) -> Option<Vec<u8>>` running the listed methods of a contract.
+///
+/// ```ignore
+/// contract_code! {
+///     pub fn prover_code(Prover) {
+///         #[init] fn new(light_client_account_id: String);
+///         fn prove_outcome(full_outcome_proof: EncodedOutcomeProof, block_height: u64);
+///         #[private] borsh fn merkle_root_callback(hash: Hash, proof: MerklePath) -> bool;
+///         fn can_bridge(account_id: AccountId) -> bool => |c| c.can_bridge(&account_id);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! contract_code {
+    ($(#[$doc:meta])* $vis:vis fn $code:ident($contract:ty) { $($methods:tt)* }) => {
+        $(#[$doc])*
+        $vis fn $code(method: &str, input: &[u8]) -> Option<Vec<u8>> {
+            $crate::contract_code!(@methods $contract, method, input; $($methods)*);
+            panic!("MethodNotFound: {}", method)
+        }
+    };
+
+    (@methods $contract:ty, $method:ident, $input:ident;) => {};
+    (@methods $contract:ty, $method:ident, $input:ident;
+        #[init] fn $name:ident($($arg:ident: $ty:ty),* $(,)?); $($rest:tt)*
+    ) => {
+        if $method == stringify!($name) {
+            $crate::contracts::check_modifiers($method, &[]);
+            if $crate::near_sdk::env::state_exists() {
+                panic!("The contract has already been initialized");
+            }
+            #[allow(unused_mut, unused_variables)]
+            let mut args = $crate::contracts::JsonArgs::new($input);
+            let ($($arg,)*) = ($(args.take::<$ty>(stringify!($arg)),)*);
+            $crate::near_sdk::env::state_write(&<$contract>::$name($($arg),*));
+            return None;
+        }
+        $crate::contract_code!(@methods $contract, $method, $input; $($rest)*);
+    };
+    (@methods $contract:ty, $method:ident, $input:ident;
+        $(#[$attr:ident])* borsh fn $name:ident($($arg:ident: $ty:ty),* $(,)?)
+            $(-> $ret:ty)? $(=> |$c:ident| $body:expr)?; $($rest:tt)*
+    ) => {
+        if $method == stringify!($name) {
+            $crate::contracts::check_modifiers($method, &[$(stringify!($attr)),*]);
+            let ($($arg,)*): ($($ty,)*) =
+                $crate::near_sdk::borsh::BorshDeserialize::try_from_slice($input)
+                    .unwrap_or_else(|_| {
+                        panic!("Failed to deserialize input from Borsh.")
+                    });
+            return $crate::contract_code!(
+                @call $contract, ($($arg),*) $name $(-> $ret)? $(=> |$c| $body)?
+            );
+        }
+        $crate::contract_code!(@methods $contract, $method, $input; $($rest)*);
+    };
+    (@methods $contract:ty, $method:ident, $input:ident;
+        $(#[$attr:ident])* fn $name:ident($($arg:ident: $ty:ty),* $(,)?)
+            $(-> $ret:ty)? $(=> |$c:ident| $body:expr)?; $($rest:tt)*
+    ) => {
+        if $method == stringify!($name) {
+            $crate::contracts::check_modifiers($method, &[$(stringify!($attr)),*]);
+            #[allow(unused_mut, unused_variables)]
+            let mut args = $crate::contracts::JsonArgs::new($input);
+            let ($($arg,)*) = ($(args.take::<$ty>(stringify!($arg)),)*);
+            return $crate::contract_code!(
+                @call $contract, ($($arg),*) $name $(-> $ret)? $(=> |$c| $body)?
+            );
+        }
+        $crate::contract_code!(@methods $contract, $method, $input; $($rest)*);
+    };
+
+    (@call $contract:ty, ($($arg:ident),*) $name:ident $(-> $ret:ty)? $(=> |$c:ident| $body:expr)?) => {{
+        #[allow(unused_mut)]
+        let mut contract: $contract = $crate::contracts::read_state();
+        let result = $crate::contract_code!(@invoke contract, $name($($arg),*) $(|$c| $body)?);
+        $crate::near_sdk::env::state_write(&contract);
+        $crate::contract_code!(@return result $(-> $ret)?)
+    }};
+
+    (@invoke $contract:ident, $name:ident($($arg:ident),*)) => {
+        $contract.$name($($arg),*)
+    };
+    (@invoke $contract:ident, $name:ident($($arg:ident),*) |$c:ident| $body:expr) => {{
+        let $c = &mut $contract;
+        $body
+    }};
+
+    // promises returned as values are scheduled when they are dropped
+    (@return $result:ident) => {{
+        #[allow(clippy::drop_non_drop, clippy::let_unit_value)]
+        let _ = $result;
+        None
+    }};
+    (@return $result:ident -> $ret:ty) => {{
+        let result: $ret = $result;
+        Some($crate::near_sdk::serde_json::to_vec(&result).unwrap())
+    }};
+}
+
+#[doc(hidden)]
+pub fn check_modifiers(method: &str, attrs: &[&str]) {
+    if attrs.contains(&"private") && env::predecessor_account_id() != env::current_account_id() {
+        panic!("Method {} is private", method);
+    }
+    if !attrs.contains(&"payable") && env::attached_deposit() != 0 {
+        panic!("Method {} doesn't accept deposit", method);
+    }
+}
+
+#[doc(hidden)]
+pub fn read_state<T: near_sdk::borsh::BorshDeserialize>() -> T {
+    env::state_read().expect("The contract is not initialized")
+}
+
+/// JSON arguments, given either as an object or positionally as an array.
+#[doc(hidden)]
+pub struct JsonArgs {
+    args: Value,
+    position: usize,
+}
+
+impl JsonArgs {
+    pub fn new(input: &[u8]) -> Self {
+        let args = if input.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(input).expect("Failed to deserialize input from JSON.")
+        };
+        Self { args, position: 0 }
+    }
+
+    pub fn take<T: DeserializeOwned>(&mut self, name: &str) -> T {
+        let value = match &mut self.args {
+            Value::Object(args) => args.remove(name),
+            Value::Array(args) => args.get_mut(self.position).map(Value::take),
+            _ => None,
+        };
+        self.position += 1;
+        serde_json::from_value(value.unwrap_or(Value::Null))
+            .unwrap_or_else(|err| panic!("Failed to deserialize argument {}: {}", name, err))
+    }
+}
+
+contract_code! {
+    pub fn light_client_code(LightClient) {
+        #[init] fn new(max_blocks: Option<usize>);
+        #[private] fn init_with_validators(initial_validators: Vec<Validator>);
+        #[private] fn init_with_block(block: Block);
+        fn add_light_client_block(block: Block);
+        fn current_height() -> u64;
+        fn block_hashes(height: u64) -> Option<Hash>;
+        fn block_merkle_roots(height: u64) -> Option<Hash>;
+    }
+}
+
+contract_code! {
+    pub fn prover_code(Prover) {
+        #[init] fn new(light_client_account_id: String);
+        fn prove_outcome(full_outcome_proof: EncodedOutcomeProof, block_height: u64);
+        #[private] borsh fn merkle_root_callback(
+            block_header_lite_hash: Hash,
+            block_proof: MerklePath,
+        ) -> bool;
+    }
+}
+
+contract_code! {
+    pub fn mock_light_client_code(MockLightClient) {
+        #[init] fn new();
+        fn add_merkle_root(height: u64, hash: Hash) => |c| c.add_merkle_root(&height, &hash);
+        fn set_height_behavior(height: u64, behavior: mock_light_client::Behavior);
+        fn set_default_behavior(behavior: mock_light_client::Behavior);
+        fn set_record_calls(record_calls: bool);
+        fn clear_calls();
+        fn get_calls() -> Vec<BlockMerkleRootsCall>;
+        fn block_merkle_roots(height: u64) -> Option<Hash>;
+    }
+}
+
+contract_code! {
+    pub fn mock_prover_code(MockProver) {
+        #[init] fn new();
+        fn add_approved_hash(hash: Hash) => |c| c.add_approved_hash(&hash);
+        fn set_proof_behavior(outcome_id: Hash, behavior: mock_prover::Behavior);
+        fn set_height_behavior(height: u64, behavior: mock_prover::Behavior);
+        fn set_default_behavior(behavior: Option<mock_prover::Behavior>);
+        fn clear_calls();
+        fn get_calls() -> Vec<ProveOutcomeCall>;
+        fn prove_outcome(full_outcome_proof: EncodedOutcomeProof, block_height: u64);
+        #[private] fn check_hash(behavior: Option<mock_prover::Behavior>) -> bool;
+    }
+}
+
+contract_code! {
+    pub fn connector_permissions_code(ConnectorPermissions) {
+        #[init] fn new(
+            ft_connector_account: AccountId,
+            nft_connector_account: AccountId,
+            xsc_connector_account: AccountId,
+        );
+        #[payable] fn add_allow_regex_rule(regex_rule: String, connector_type: ConnectorType)
+            -> bool => |c| c.add_allow_regex_rule(&regex_rule, connector_type);
+        fn remove_allowed_regex_rule(regex_rule: String, connector_type: ConnectorType)
+            -> bool => |c| c.remove_allowed_regex_rule(&regex_rule, connector_type);
+        fn can_bridge(account_id: AccountId, connector_type: ConnectorType)
+            -> bool => |c| c.can_bridge(&account_id, connector_type);
+        fn can_make_cross_shard_call_for_contract(account_id: AccountId, contract_id: AccountId)
+            -> bool;
+    }
+}
+
+contract_code! {
+    pub fn ft_connector_code(FungibleTokenConnector) {
+        #[init] fn new(
+            prover_account: AccountId,
+            connector_permissions_account: AccountId,
+            proof_validity_ns: Option<u64>,
+        );
+        fn get_paused() -> Mask;
+        #[private] fn set_paused(paused: Mask);
+        #[private] fn set_locker(locker_account: AccountId);
+        #[private] fn set_chain_domain(chain_id: String, peer_chain_id: String);
+        fn view_chain_domain() -> Option<ChainDomain>;
+        fn prune_used_events(limit: u64) -> u64;
+        #[private] fn set_deployer(deployer_account: AccountId);
+        #[private] #[payable] fn deploy_bridge_token(source_address: String);
+        #[private] fn complete_deployment(source_address: AccountId) -> (AccountId,);
+        fn view_mapping(source_account: AccountId) -> Option<AccountId>;
+        #[private] #[payable] fn map_contracts(
+            source_contract: AccountId,
+            destination_contract: AccountId,
+            proof: EncodedOutcomeProof,
+        );
+        #[payable] fn register_on_other(proof: EncodedOutcomeProof, height: u64);
+        fn ft_on_transfer(sender_id: AccountId, amount: U128, msg: String);
+        #[private] fn lock(
+            sender_id: AccountId,
+            ft_contract_id: AccountId,
+            amount: U128,
+            receiver_id: AccountId,
+            memo: Option<String>,
+        ) -> U128;
+        fn locked_balance(token: AccountId) -> U128;
+        fn all_locked_tokens(from_index: Option<u64>, limit: Option<u64>) -> Vec<(AccountId, U128)>;
+        #[payable] fn mint(proof: EncodedOutcomeProof, height: u64);
+        #[private] #[payable] fn finish_mint(
+            caller_id: AccountId,
+            token_contract_account: String,
+            params: Vec<String>,
+            proof: EncodedOutcomeProof,
+        );
+        #[private] fn resolve_mint(params: Vec<String>) -> bool;
+        #[payable] fn cancel_mint(proof: EncodedOutcomeProof, height: u64);
+        #[private] #[payable] fn finish_cancel_mint(
+            caller_id: AccountId,
+            params: Vec<String>,
+            proof: EncodedOutcomeProof,
+        );
+        fn burn(burner_id: AccountId, transferable: U128);
+        #[payable] fn unlock(proof: EncodedOutcomeProof, height: u64);
+        #[payable] fn refund(proof: EncodedOutcomeProof, height: u64);
+        #[private] #[payable] fn finish_unlock(
+            caller_id: AccountId,
+            token_contract_account: AccountId,
+            token_receiver_account: AccountId,
+            transferable: U128,
+            transfer_nonce: Option<u64>,
+            proof: EncodedOutcomeProof,
+        );
+        #[private] fn resolve_unlock(
+            caller_id: AccountId,
+            remaining_deposit: U128,
+            receiver_id: AccountId,
+            claim: Claim<U128>,
+        ) -> bool;
+        #[payable] fn claim(transfer_id: String, receiver_id: Option<AccountId>);
+        #[private] fn resolve_claim(owner_id: AccountId, claim: Claim<U128>) -> bool;
+        fn view_claimable(account_id: AccountId) -> Vec<Claim<U128>>;
+        #[payable] fn acknowledge(proof: EncodedOutcomeProof, height: u64);
+        #[private] #[payable] fn finish_acknowledge(
+            caller_id: AccountId,
+            nonce: u64,
+            proof: EncodedOutcomeProof,
+        );
+        fn view_transfers(
+            account_id: AccountId,
+            status: Option<TransferStatus>,
+            from_index: Option<u64>,
+            limit: Option<u64>,
+        ) -> Vec<Transfer<U128>>;
+        #[private] fn set_cancel_delay(delay_ns: u64);
+        #[payable] fn cancel(nonce: U64);
+        #[private] fn set_fee_schedule(token: AccountId, schedule: Option<FeeSchedule>);
+        fn view_fee_schedule(token: AccountId) -> Option<FeeSchedule>;
+        fn view_accrued_fees(token: AccountId) -> U128;
+        #[private] fn withdraw_fees(token: AccountId, amount: U128, receiver_id: AccountId);
+        #[private] fn resolve_withdraw_fees(token: AccountId, amount: U128) -> bool;
+        #[payable] fn deposit_fees();
+        #[payable] fn withdraw_fee_deposit(amount: U128);
+        fn view_fee_deposit(account_id: AccountId) -> U128;
+        #[private] fn set_transfer_limit(token: AccountId, limit: Option<Limit>);
+        #[private] fn set_global_limit(limit: Option<GlobalLimit>);
+        fn view_transfer_limit(token: AccountId) -> Option<Limit>;
+        fn view_global_limit() -> Option<GlobalLimit>;
+        fn view_volume(token: AccountId) -> U128;
+        #[private] fn set_guardian(guardian_account: Option<AccountId>);
+        #[private] fn set_queue_delay(delay_ns: u64);
+        #[private] fn set_queue_threshold(token: AccountId, threshold: Option<U128>);
+        fn view_queue_threshold(token: AccountId) -> Option<U128>;
+        fn view_queue(from_index: Option<u64>, limit: Option<u64>) -> Vec<QueuedTransfer<U128>>;
+        #[payable] fn cancel_queued(id: U64);
+        #[payable] fn execute_queued(id: U64);
+    }
+}
+
+contract_code! {
+    pub fn xsc_connector_code(CrossShardConnector) {
+        #[init] fn new(
+            prover_account: AccountId,
+            connector_permissions_account: AccountId,
+            proof_validity_ns: Option<u64>,
+        );
+        #[private] fn set_locker(locker_account: AccountId);
+        #[private] fn set_chain_domain(chain_id: String, peer_chain_id: String);
+        fn view_chain_domain() -> Option<ChainDomain>;
+        fn prune_used_events(limit: u64) -> u64;
+        fn cross_call(
+            destination_contract_id: String,
+            destination_contract_method: String,
+            destination_contract_args: String,
+            destination_gas: Gas,
+            destination_deposit: Balance,
+            source_callback_method: String,
+        );
+        #[private] fn cross_call_resolve(
+            destination_contract_id: String,
+            destination_contract_method: String,
+            destination_contract_args: String,
+            destination_gas: Gas,
+            destination_deposit: Balance,
+            source_callback_method: String,
+            cross_call_initiator_account_id: String,
+        ) -> bool;
+        #[payable] fn cross_call_execute(proof: EncodedOutcomeProof, height: u64);
+        #[private] #[payable] fn finish_cross_call_execute(
+            caller_id: AccountId,
+            destination_contract: AccountId,
+            destination_contract_method: String,
+            destination_contract_args: Vec<u8>,
+            destination_gas: Gas,
+            destination_deposit: Balance,
+            source_contract: AccountId,
+            source_contract_method: String,
+            proof: EncodedOutcomeProof,
+        );
+        #[private] fn calimero_response(
+            source_contract: AccountId,
+            source_contract_method: String,
+            destination_contract: AccountId,
+        );
+        #[payable] fn cross_call_receive_response(proof: EncodedOutcomeProof, height: u64);
+        #[private] #[payable] fn finish_cross_response(
+            caller_id: AccountId,
+            source_contract: AccountId,
+            source_contract_method: String,
+            destination_predecessor: AccountId,
+            response: String,
+            proof: EncodedOutcomeProof,
+        );
+    }
+}
+
+contract_code! {
+    pub fn bridge_token_deployer_code(BridgeTokenDeployer) {
+        #[init] fn new(bridge_account: AccountId, source_master_account: AccountId);
+        #[payable] fn deploy_bridge_token(source_address: String);
+        #[private] fn complete_deployment(bridge_token_address: AccountId) -> (AccountId,);
+    }
+}
+
+contract_code! {
+    pub fn bridge_token_code(BridgeToken) {
+        #[init] fn new(controller: AccountId);
+        #[payable] fn mint(account_id: AccountId, amount: U128);
+        #[payable] fn withdraw(amount: U128);
+        #[payable] fn ft_transfer(receiver_id: AccountId, amount: U128, memo: Option<String>);
+        #[payable] fn ft_transfer_call(
+            receiver_id: AccountId,
+            amount: U128,
+            memo: Option<String>,
+            msg: String,
+        );
+        fn ft_total_supply() -> U128;
+        fn ft_balance_of(account_id: AccountId) -> U128;
+        #[private] fn ft_resolve_transfer(
+            sender_id: AccountId,
+            receiver_id: AccountId,
+            amount: U128,
+        ) -> U128;
+        #[payable] fn storage_deposit(
+            account_id: Option<AccountId>,
+            registration_only: Option<bool>,
+        ) -> StorageBalance;
+        fn storage_balance_of(account_id: AccountId) -> Option<StorageBalance>;
+    }
+}
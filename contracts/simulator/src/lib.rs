@@ -0,0 +1,18 @@
+//! In-process simulation of the bridge between NEAR and a Calimero shard.
+//!
+//! Both chains are [`Runtime`]s running the native code of the contracts on top of a
+//! [`ChainGenerator`](test_utils::chain::ChainGenerator), so their blocks and outcome proofs
+//! are checked by the real light client and prover. [`BridgeSimulator`] deploys the whole
+//! bridge on both sides and relays headers and connector events between them, which lets
+//! tests run complete round trips deterministically and without any node.
+//!
+//! The bridge token deployer embeds the bridge token binary, so `BRIDGE_TOKEN` has to be set
+//! as when building the deployer.
+
+pub mod bridge;
+pub mod contracts;
+pub mod runtime;
+
+pub use bridge::{BridgeAccounts, BridgeSimulator, RelayedCall};
+pub use near_sdk;
+pub use runtime::{CallResult, ContractCode, Runtime};
@@ -0,0 +1,591 @@
+//! One simulated chain: accounts running native contract code, receipt execution and blocks.
+//!
+//! Receipts a contract creates are executed right after it, as a chain in creation order: each
+//! one receives the result of the previous one as its promise result, and a call that created
+//! receipts without returning a value resolves to the result of the last one. This is how all
+//! connectors use promises (`promise_create` followed by `promise_then`), it is not a general
+//! model of the NEAR runtime. Gas is not metered and access keys are not modelled.
+
+use near_sdk::mock::{with_mocked_blockchain, VmAction};
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json::{self, Value};
+use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+use near_sdk::{
+    env, AccountId, Balance, Gas, MockedBlockchain, PromiseResult, RuntimeFeesConfig, VMConfig,
+};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use test_utils::chain::ChainGenerator;
+use types::errors::{ActionError, ActionErrorKind, FunctionCallError, TxExecutionError};
+use types::{ExecutionOutcome, ExecutionOutcomeWithId, ExecutionStatus};
+use utils::hashes::sha256;
+use utils::Hash;
+
+/// Native stand-in for a contract binary, see [`contract_code!`](crate::contract_code).
+/// Returns the serialized value the method returned, `None` if it returned nothing.
+pub type ContractCode = fn(method: &str, args: &[u8]) -> Option<Vec<u8>>;
+
+/// Gas attached to top level calls and to receipts created without static gas.
+pub const DEFAULT_GAS: Gas = Gas(300_000_000_000_000);
+
+#[derive(Clone, Default)]
+struct Account {
+    balance: Balance,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    storage_usage: u64,
+    code: Option<ContractCode>,
+}
+
+struct Receipt {
+    id: Hash,
+    signer_id: AccountId,
+    predecessor_id: AccountId,
+    receiver_id: AccountId,
+    actions: Vec<VmAction>,
+}
+
+struct Applied {
+    value: Option<Vec<u8>>,
+    logs: Vec<String>,
+    receipts: Vec<near_sdk::mock::Receipt>,
+}
+
+struct Failed {
+    message: String,
+    logs: Vec<String>,
+}
+
+/// Everything a call caused on its chain.
+#[derive(Debug, Clone)]
+pub struct CallResult {
+    /// Outcomes of the call receipt and of all receipts it caused, in execution order.
+    pub outcomes: Vec<ExecutionOutcomeWithId>,
+    /// Value the call resolved to, or the panic message of the receipt it resolved to.
+    pub result: Result<Vec<u8>, String>,
+}
+
+impl CallResult {
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Panics with the failure message if the call failed.
+    pub fn unwrap(self) -> Self {
+        if let Err(message) = &self.result {
+            panic!("Call failed: {}", message);
+        }
+        self
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        let value = self.result.as_ref().expect("Call failed");
+        serde_json::from_slice(value).expect("Result is not valid JSON")
+    }
+
+    pub fn logs(&self) -> Vec<String> {
+        self.outcomes
+            .iter()
+            .flat_map(|outcome| &outcome.outcome.logs)
+            .map(|log| String::from_utf8_lossy(log).into_owned())
+            .collect()
+    }
+
+    /// Panic messages of all receipts that failed, including callbacks that were recovered from.
+    pub fn failures(&self) -> Vec<String> {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| match &outcome.outcome.status {
+                ExecutionStatus::Failure(TxExecutionError::ActionError(ActionError {
+                    kind: ActionErrorKind::FunctionCallError(FunctionCallError::ExecutionError(message)),
+                    ..
+                })) => Some(message.clone()),
+                ExecutionStatus::Failure(error) => Some(format!("{:?}", error)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+pub struct Runtime {
+    chain: ChainGenerator,
+    accounts: BTreeMap<AccountId, Account>,
+    codes: HashMap<Hash, ContractCode>,
+    /// Outcomes waiting for the next block.
+    pending: Vec<ExecutionOutcomeWithId>,
+    /// Outcomes included in blocks, with the height of their block.
+    included: Vec<(u64, ExecutionOutcomeWithId)>,
+    receipt_nonce: u64,
+}
+
+impl Runtime {
+    /// Starts the chain with its genesis block.
+    pub fn new(chain: ChainGenerator) -> Self {
+        let mut runtime = Self {
+            chain,
+            accounts: BTreeMap::new(),
+            codes: HashMap::new(),
+            pending: vec![],
+            included: vec![],
+            receipt_nonce: 0,
+        };
+        runtime.produce_block();
+        runtime
+    }
+
+    pub fn chain(&self) -> &ChainGenerator {
+        &self.chain
+    }
+
+    /// Height of the block the next call will be included in.
+    pub fn block_height(&self) -> u64 {
+        self.chain.head_height() + 1
+    }
+
+    /// Puts all pending outcomes into a new block.
+    pub fn produce_block(&mut self) -> u64 {
+        let outcomes = std::mem::take(&mut self.pending);
+        let height = self.chain.produce_block(outcomes.clone());
+        self.included
+            .extend(outcomes.into_iter().map(|outcome| (height, outcome)));
+        height
+    }
+
+    pub fn has_pending_outcomes(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn included_outcomes(&self) -> &[(u64, ExecutionOutcomeWithId)] {
+        &self.included
+    }
+
+    pub fn create_account(&mut self, account_id: &AccountId, balance: Balance) {
+        let previous = self.accounts.insert(
+            account_id.clone(),
+            Account {
+                balance,
+                ..Default::default()
+            },
+        );
+        assert!(previous.is_none(), "Account {} already exists", account_id);
+    }
+
+    pub fn deploy(&mut self, account_id: &AccountId, code: ContractCode) {
+        self.accounts
+            .get_mut(account_id)
+            .unwrap_or_else(|| panic!("Account {} does not exist", account_id))
+            .code = Some(code);
+    }
+
+    /// Makes `DeployContract` actions carrying `binary` deploy `code`.
+    pub fn register_code(&mut self, binary: &[u8], code: ContractCode) {
+        self.codes.insert(sha256(binary), code);
+    }
+
+    pub fn balance(&self, account_id: &AccountId) -> Option<Balance> {
+        self.accounts.get(account_id).map(|account| account.balance)
+    }
+
+    pub fn call(
+        &mut self,
+        signer_id: &AccountId,
+        receiver_id: &AccountId,
+        method: &str,
+        args: Value,
+        deposit: Balance,
+    ) -> CallResult {
+        self.call_raw(
+            signer_id,
+            receiver_id,
+            method,
+            serde_json::to_vec(&args).unwrap(),
+            deposit,
+        )
+    }
+
+    pub fn call_raw(
+        &mut self,
+        signer_id: &AccountId,
+        receiver_id: &AccountId,
+        method: &str,
+        args: Vec<u8>,
+        deposit: Balance,
+    ) -> CallResult {
+        let signer = self
+            .accounts
+            .get_mut(signer_id)
+            .unwrap_or_else(|| panic!("Signer {} does not exist", signer_id));
+        assert!(
+            signer.balance >= deposit,
+            "Signer {} can not cover the deposit",
+            signer_id
+        );
+        signer.balance -= deposit;
+
+        let receipt = Receipt {
+            id: self.next_receipt_id(),
+            signer_id: signer_id.clone(),
+            predecessor_id: signer_id.clone(),
+            receiver_id: receiver_id.clone(),
+            actions: vec![VmAction::FunctionCall {
+                function_name: method.to_string(),
+                args,
+                gas: DEFAULT_GAS,
+                deposit,
+            }],
+        };
+        let mut outcomes = vec![];
+        let result = self.execute(receipt, vec![], &mut outcomes);
+        self.pending.extend(outcomes.iter().cloned());
+        CallResult { outcomes, result }
+    }
+
+    /// Runs a method without keeping any of its changes or receipts.
+    pub fn view<T: DeserializeOwned>(
+        &mut self,
+        account_id: &AccountId,
+        method: &str,
+        args: Value,
+    ) -> T {
+        let snapshot = self.accounts.get(account_id).cloned();
+        let result = self.run(
+            account_id,
+            account_id,
+            account_id,
+            method,
+            &serde_json::to_vec(&args).unwrap(),
+            0,
+            DEFAULT_GAS,
+            vec![],
+        );
+        if let Some(account) = snapshot {
+            self.accounts.insert(account_id.clone(), account);
+        }
+        match result {
+            Ok(Applied {
+                value: Some(value), ..
+            }) => serde_json::from_slice(&value).expect("Result is not valid JSON"),
+            Ok(_) => panic!("View {} returned nothing", method),
+            Err(failed) => panic!("View {} failed: {}", method, failed.message),
+        }
+    }
+
+    fn next_receipt_id(&mut self) -> Hash {
+        self.receipt_nonce += 1;
+        sha256(format!("{}-receipt{}", self.chain.name(), self.receipt_nonce).as_bytes())
+    }
+
+    fn execute(
+        &mut self,
+        receipt: Receipt,
+        promise_results: Vec<PromiseResult>,
+        outcomes: &mut Vec<ExecutionOutcomeWithId>,
+    ) -> Result<Vec<u8>, String> {
+        let snapshot = self.accounts.get(&receipt.receiver_id).cloned();
+        let applied = self
+            .apply(&receipt, promise_results)
+            .and_then(|applied| self.charge_receipts(&receipt.receiver_id, applied));
+
+        let applied = match applied {
+            Ok(applied) => applied,
+            Err(failed) => {
+                match snapshot {
+                    Some(account) => self.accounts.insert(receipt.receiver_id.clone(), account),
+                    None => self.accounts.remove(&receipt.receiver_id),
+                };
+                self.credit(&receipt.predecessor_id, attached_deposit(&receipt.actions));
+                outcomes.push(self.outcome(
+                    &receipt,
+                    failed.logs,
+                    vec![],
+                    failure(&failed.message),
+                ));
+                return Err(failed.message);
+            }
+        };
+
+        let children: Vec<Receipt> = applied
+            .receipts
+            .into_iter()
+            .map(|created| Receipt {
+                id: self.next_receipt_id(),
+                signer_id: receipt.signer_id.clone(),
+                predecessor_id: receipt.receiver_id.clone(),
+                receiver_id: created.receiver_id.as_str().parse().unwrap(),
+                actions: created.actions,
+            })
+            .collect();
+        let status = match (&applied.value, children.last()) {
+            (None, Some(last)) => ExecutionStatus::SuccessReceiptId(last.id),
+            (value, _) => ExecutionStatus::SuccessValue(value.clone().unwrap_or_default()),
+        };
+        let receipt_ids = children.iter().map(|child| child.id).collect();
+        outcomes.push(self.outcome(&receipt, applied.logs, receipt_ids, status));
+
+        let mut last = None;
+        for child in children {
+            let promise_results = match last.take() {
+                Some(Ok(value)) => vec![PromiseResult::Successful(value)],
+                Some(Err(_)) => vec![PromiseResult::Failed],
+                None => vec![],
+            };
+            last = Some(self.execute(child, promise_results, outcomes));
+        }
+        match applied.value {
+            Some(value) => Ok(value),
+            None => last.unwrap_or(Ok(vec![])),
+        }
+    }
+
+    fn apply(
+        &mut self,
+        receipt: &Receipt,
+        promise_results: Vec<PromiseResult>,
+    ) -> Result<Applied, Failed> {
+        let mut applied = Applied {
+            value: None,
+            logs: vec![],
+            receipts: vec![],
+        };
+        for action in &receipt.actions {
+            let exists = self.accounts.contains_key(&receipt.receiver_id);
+            match action {
+                VmAction::CreateAccount if exists => {
+                    return Err(failed(format!(
+                        "Account {} already exists",
+                        receipt.receiver_id
+                    )))
+                }
+                VmAction::CreateAccount => {
+                    self.accounts
+                        .insert(receipt.receiver_id.clone(), Account::default());
+                }
+                // transfers to unknown accounts create them, like implicit accounts do
+                VmAction::Transfer { deposit } => self.credit(&receipt.receiver_id, *deposit),
+                _ if !exists => {
+                    return Err(failed(format!(
+                        "Account {} does not exist",
+                        receipt.receiver_id
+                    )))
+                }
+                VmAction::DeployContract { code } => {
+                    let code = *self
+                        .codes
+                        .get(&sha256(code))
+                        .ok_or_else(|| failed("Contract binary is not registered".to_string()))?;
+                    self.deploy(&receipt.receiver_id, code);
+                }
+                VmAction::FunctionCall {
+                    function_name,
+                    args,
+                    gas,
+                    deposit,
+                } => {
+                    self.credit(&receipt.receiver_id, *deposit);
+                    let gas = if gas.0 == 0 { DEFAULT_GAS } else { *gas };
+                    let mut call = self.run(
+                        &receipt.signer_id,
+                        &receipt.predecessor_id,
+                        &receipt.receiver_id,
+                        function_name,
+                        args,
+                        *deposit,
+                        gas,
+                        clone_promise_results(&promise_results),
+                    )?;
+                    applied.logs.append(&mut call.logs);
+                    applied.receipts.append(&mut call.receipts);
+                    applied.value = call.value;
+                }
+                VmAction::AddKeyWithFullAccess { .. } | VmAction::AddKeyWithFunctionCall { .. } => {
+                }
+                action => return Err(failed(format!("Action {:?} is not supported", action))),
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Takes the deposits of the receipts created by `account_id` from its balance.
+    fn charge_receipts(
+        &mut self,
+        account_id: &AccountId,
+        applied: Applied,
+    ) -> Result<Applied, Failed> {
+        let total: Balance = applied
+            .receipts
+            .iter()
+            .map(|receipt| attached_deposit(&receipt.actions))
+            .sum();
+        let account = self.accounts.get_mut(account_id).unwrap();
+        if account.balance < total {
+            return Err(Failed {
+                message: format!("Account {} can not cover the attached deposits", account_id),
+                logs: applied.logs,
+            });
+        }
+        account.balance -= total;
+        Ok(applied)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &mut self,
+        signer_id: &AccountId,
+        predecessor_id: &AccountId,
+        receiver_id: &AccountId,
+        method: &str,
+        args: &[u8],
+        deposit: Balance,
+        gas: Gas,
+        promise_results: Vec<PromiseResult>,
+    ) -> Result<Applied, Failed> {
+        let height = self.block_height();
+        let timestamp = self.chain.block_timestamp(height);
+        let account = self.accounts.get_mut(receiver_id).unwrap();
+        let code = account
+            .code
+            .ok_or_else(|| failed(format!("Account {} has no contract", receiver_id)))?;
+
+        let mut context = VMContextBuilder::new()
+            .current_account_id(receiver_id.clone())
+            .signer_account_id(signer_id.clone())
+            .predecessor_account_id(predecessor_id.clone())
+            .block_index(height)
+            .block_timestamp(timestamp)
+            .account_balance(account.balance)
+            .storage_usage(account.storage_usage)
+            .attached_deposit(deposit)
+            .prepaid_gas(gas)
+            .build();
+        context.input = args.to_vec();
+        env::set_blockchain_interface(MockedBlockchain::new(
+            context,
+            VMConfig::free(),
+            RuntimeFeesConfig::free(),
+            promise_results,
+            std::mem::take(&mut account.storage),
+            Default::default(),
+            None,
+        ));
+
+        let result = quietly(|| panic::catch_unwind(AssertUnwindSafe(|| code(method, args))));
+        let logs = get_logs();
+        account.storage = with_mocked_blockchain(|blockchain| blockchain.take_storage());
+        match result {
+            Ok(value) => {
+                account.storage_usage = env::storage_usage();
+                Ok(Applied {
+                    value,
+                    logs,
+                    receipts: get_created_receipts(),
+                })
+            }
+            Err(payload) => Err(Failed {
+                message: panic_message(payload),
+                logs,
+            }),
+        }
+    }
+
+    fn credit(&mut self, account_id: &AccountId, amount: Balance) {
+        self.accounts.entry(account_id.clone()).or_default().balance += amount;
+    }
+
+    fn outcome(
+        &self,
+        receipt: &Receipt,
+        logs: Vec<String>,
+        receipt_ids: Vec<Hash>,
+        status: ExecutionStatus,
+    ) -> ExecutionOutcomeWithId {
+        ExecutionOutcomeWithId {
+            id: receipt.id,
+            outcome: ExecutionOutcome {
+                logs: logs.into_iter().map(String::into_bytes).collect(),
+                receipt_ids,
+                gas_burnt: 0,
+                tokens_burnt: 0,
+                executor_id: receipt.receiver_id.to_string(),
+                status,
+            },
+        }
+    }
+}
+
+fn attached_deposit(actions: &[VmAction]) -> Balance {
+    actions
+        .iter()
+        .map(|action| match action {
+            VmAction::FunctionCall { deposit, .. } | VmAction::Transfer { deposit } => *deposit,
+            _ => 0,
+        })
+        .sum()
+}
+
+// `PromiseResult` is not `Clone`, every function call of a receipt sees the same results
+fn clone_promise_results(results: &[PromiseResult]) -> Vec<PromiseResult> {
+    results
+        .iter()
+        .map(|result| match result {
+            PromiseResult::NotReady => PromiseResult::NotReady,
+            PromiseResult::Successful(value) => PromiseResult::Successful(value.clone()),
+            PromiseResult::Failed => PromiseResult::Failed,
+        })
+        .collect()
+}
+
+fn failed(message: String) -> Failed {
+    Failed {
+        message,
+        logs: vec![],
+    }
+}
+
+fn failure(message: &str) -> ExecutionStatus {
+    ExecutionStatus::Failure(TxExecutionError::ActionError(ActionError {
+        index: Some(0),
+        kind: ActionErrorKind::FunctionCallError(FunctionCallError::ExecutionError(
+            message.to_string(),
+        )),
+    }))
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` without printing the panics contracts are expected to raise.
+fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                default_hook(info)
+            }
+        }));
+    });
+    QUIET.with(|quiet| quiet.set(true));
+    let result = f();
+    QUIET.with(|quiet| quiet.set(false));
+    result
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast::<&str>()
+            .map(|message| message.to_string())
+            .unwrap_or_else(|_| "Contract panicked".to_string()),
+    };
+    // `env::panic_str` surfaces as an unwrapped host error holding the contract message
+    match message.split_once("GuestPanic { panic_msg: \"") {
+        Some((_, rest)) => rest
+            .rsplit_once("\" }")
+            .map(|(message, _)| message.to_string())
+            .unwrap_or(message),
+        None => message,
+    }
+}
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+BRIDGE_TOKEN=../ft_bridge_token.wasm cargo test -- --nocapture
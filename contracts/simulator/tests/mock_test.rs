@@ -0,0 +1,336 @@
+use near_sdk::serde_json::json;
+use near_sdk::{AccountId, ONE_NEAR};
+use simulator::bridge::ACCOUNT_BALANCE;
+use simulator::contracts::{
+    ft_connector_code, mock_light_client_code, mock_prover_code, prover_code,
+};
+use simulator::{ContractCode, Runtime};
+use test_utils::chain::{outcome, ChainGenerator};
+use types::{BridgeError, EncodedOutcomeProof, ExecutionOutcomeWithId, FullOutcomeProof};
+use utils::Hash;
+
+fn account(account_id: &str) -> AccountId {
+    account_id.parse().unwrap()
+}
+
+/// Source chain with three outcomes in block 2, proofs are built against block 4.
+fn source_chain() -> (ChainGenerator, Vec<ExecutionOutcomeWithId>) {
+    let mut chain = ChainGenerator::new("near", 1, 10);
+    chain.produce_blocks(1);
+    let outcomes: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|seed| {
+            outcome(
+                seed,
+                "ft_connector.near",
+                &["CALIMERO_EVENT_LOCK_FT:wrap.near:alice.near:100:alice.near::0:near:calimero"],
+            )
+        })
+        .collect();
+    chain.produce_block(outcomes.clone());
+    chain.produce_blocks(3);
+    (chain, outcomes)
+}
+
+fn runtime_with(contracts: &[(&str, ContractCode)]) -> Runtime {
+    let mut runtime = Runtime::new(ChainGenerator::new("calimero", 1, 10));
+    runtime.create_account(&account("user.calimero"), ACCOUNT_BALANCE);
+    for (account_id, code) in contracts {
+        runtime.create_account(&account(account_id), ACCOUNT_BALANCE);
+        runtime.deploy(&account(account_id), *code);
+    }
+    runtime
+}
+
+fn prove(
+    runtime: &mut Runtime,
+    prover: &str,
+    proof: FullOutcomeProof,
+    height: u64,
+) -> Result<bool, String> {
+    let result = runtime.call(
+        &account("user.calimero"),
+        &account(prover),
+        "prove_outcome",
+        json!({ "full_outcome_proof": EncodedOutcomeProof::borsh(&proof), "block_height": height }),
+        0,
+    );
+    result.result.as_ref().map_err(Clone::clone)?;
+    Ok(result.json())
+}
+
+#[test]
+fn mock_prover_behaviors() {
+    let (chain, outcomes) = source_chain();
+    let proof = |i: usize| chain.outcome_proof(&outcomes[i].id, 4);
+    let prover = account("prover.calimero");
+    let mut runtime = runtime_with(&[("prover.calimero", mock_prover_code)]);
+    runtime.call(&prover, &prover, "new", json!({}), 0).unwrap();
+
+    let user = account("user.calimero");
+    let configure = |runtime: &mut Runtime, method: &str, args| {
+        runtime.call(&user, &prover, method, args, 0).unwrap();
+    };
+    configure(
+        &mut runtime,
+        "set_proof_behavior",
+        json!({ "outcome_id": outcomes[0].id, "behavior": "False" }),
+    );
+    configure(
+        &mut runtime,
+        "set_height_behavior",
+        json!({ "height": 7, "behavior": "Panic" }),
+    );
+    configure(
+        &mut runtime,
+        "set_proof_behavior",
+        json!({ "outcome_id": outcomes[2].id, "behavior": "HeightPruned" }),
+    );
+
+    // the behavior of the proof takes precedence over the one of the height
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof(0), 7),
+        Ok(false)
+    );
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof(1), 7),
+        Err("MockProver: configured to panic".to_string())
+    );
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof(1), 4),
+        Err("Not approved hash".to_string())
+    );
+    configure(
+        &mut runtime,
+        "add_approved_hash",
+        json!({ "hash": chain.block_hash(2) }),
+    );
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof(1), 4),
+        Ok(true)
+    );
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof(2), 4),
+        Err(BridgeError::BlockProofInvalid.to_string())
+    );
+
+    let calls: Vec<mock_prover::ProveOutcomeCall> = runtime.view(&prover, "get_calls", json!({}));
+    let behaviors: Vec<_> = calls.iter().map(|call| call.behavior).collect();
+    use mock_prover::Behavior::*;
+    assert_eq!(
+        behaviors,
+        [
+            Some(False),
+            Some(Panic),
+            None,
+            Some(Success),
+            Some(HeightPruned)
+        ]
+    );
+    assert_eq!(calls[1].outcome_id, outcomes[1].id);
+    assert_eq!(calls[1].block_hash, chain.block_hash(2));
+    assert_eq!(calls[1].block_height, 7);
+    assert!(calls.iter().all(|call| call.predecessor_id == user));
+}
+
+#[test]
+fn prover_with_mock_light_client() {
+    let (chain, outcomes) = source_chain();
+    let light_client = account("light_client.calimero");
+    let prover = account("prover.calimero");
+    let mut runtime = runtime_with(&[
+        ("light_client.calimero", mock_light_client_code),
+        ("prover.calimero", prover_code),
+    ]);
+    runtime
+        .call(&light_client, &light_client, "new", json!({}), 0)
+        .unwrap();
+    runtime
+        .call(
+            &prover,
+            &prover,
+            "new",
+            json!({ "light_client_account_id": light_client }),
+            0,
+        )
+        .unwrap();
+    runtime
+        .call(
+            &light_client,
+            &light_client,
+            "add_merkle_root",
+            json!({ "height": 4, "hash": chain.block_merkle_root(4) }),
+            0,
+        )
+        .unwrap();
+
+    // a view that records nothing unless asked to
+    let root: Option<Hash> =
+        runtime.view(&light_client, "block_merkle_roots", json!({ "height": 4 }));
+    assert_eq!(root, Some(chain.block_merkle_root(4)));
+    let proof = chain.outcome_proof(&outcomes[0].id, 4);
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof.clone(), 4),
+        Ok(true)
+    );
+    let calls: Vec<mock_light_client::BlockMerkleRootsCall> =
+        runtime.view(&light_client, "get_calls", json!({}));
+    assert!(calls.is_empty());
+
+    runtime
+        .call(
+            &light_client,
+            &light_client,
+            "set_record_calls",
+            json!({ "record_calls": true }),
+            0,
+        )
+        .unwrap();
+    assert_eq!(
+        prove(&mut runtime, "prover.calimero", proof.clone(), 4),
+        Ok(true)
+    );
+    for behavior in ["HeightPruned", "False"] {
+        runtime
+            .call(
+                &light_client,
+                &light_client,
+                "set_height_behavior",
+                json!({ "height": 4, "behavior": behavior }),
+                0,
+            )
+            .unwrap();
+        assert_eq!(
+            prove(&mut runtime, "prover.calimero", proof.clone(), 4),
+            Err(BridgeError::BlockProofInvalid.to_string())
+        );
+    }
+
+    let calls: Vec<mock_light_client::BlockMerkleRootsCall> =
+        runtime.view(&light_client, "get_calls", json!({}));
+    use mock_light_client::Behavior::*;
+    assert_eq!(
+        calls.iter().map(|call| call.behavior).collect::<Vec<_>>(),
+        [Success, HeightPruned, False]
+    );
+    assert!(calls
+        .iter()
+        .all(|call| call.predecessor_id == prover && call.height == 4));
+
+    runtime
+        .call(&light_client, &light_client, "clear_calls", json!({}), 0)
+        .unwrap();
+    let calls: Vec<mock_light_client::BlockMerkleRootsCall> =
+        runtime.view(&light_client, "get_calls", json!({}));
+    assert!(calls.is_empty());
+}
+
+/// FT connector using the mock prover, with `ft_connector.near` on `near` as its locker.
+fn connector_with_mock_prover(chain_id: &str) -> Runtime {
+    let prover = account("prover.calimero");
+    let connector = account("ft_connector.calimero");
+    let mut runtime = runtime_with(&[
+        ("prover.calimero", mock_prover_code),
+        ("ft_connector.calimero", ft_connector_code),
+    ]);
+    runtime.call(&prover, &prover, "new", json!({}), 0).unwrap();
+    runtime
+        .call(
+            &connector,
+            &connector,
+            "new",
+            json!({
+                "prover_account": prover,
+                "connector_permissions_account": "permissions.calimero",
+                "proof_validity_ns": null,
+            }),
+            0,
+        )
+        .unwrap();
+    runtime
+        .call(
+            &connector,
+            &connector,
+            "set_locker",
+            json!({ "locker_account": "ft_connector.near" }),
+            0,
+        )
+        .unwrap();
+    runtime
+        .call(
+            &connector,
+            &connector,
+            "set_chain_domain",
+            json!({ "chain_id": chain_id, "peer_chain_id": "near" }),
+            0,
+        )
+        .unwrap();
+    runtime
+}
+
+#[test]
+fn connector_rejects_proof_the_prover_denies() {
+    let (chain, outcomes) = source_chain();
+    let prover = account("prover.calimero");
+    let connector = account("ft_connector.calimero");
+    let mut runtime = connector_with_mock_prover("calimero");
+    runtime
+        .call(
+            &prover,
+            &prover,
+            "set_default_behavior",
+            json!({ "behavior": "False" }),
+            0,
+        )
+        .unwrap();
+
+    let proof = chain.outcome_proof(&outcomes[0].id, 4);
+    let mint = runtime.call(
+        &account("user.calimero"),
+        &connector,
+        "mint",
+        json!({ "proof": EncodedOutcomeProof::borsh(&proof), "height": 4 }),
+        ONE_NEAR,
+    );
+    assert!(!mint.is_success());
+    assert!(mint
+        .failures()
+        .contains(&BridgeError::ProofNotVerified.to_string()));
+    // the deposit of the failed callback is refunded to the connector
+    assert_eq!(
+        runtime.balance(&connector),
+        Some(ACCOUNT_BALANCE + ONE_NEAR)
+    );
+
+    let calls: Vec<mock_prover::ProveOutcomeCall> = runtime.view(&prover, "get_calls", json!({}));
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].predecessor_id, connector);
+    assert_eq!(calls[0].outcome_id, outcomes[0].id);
+    assert_eq!(calls[0].behavior, Some(mock_prover::Behavior::False));
+}
+
+#[test]
+fn connector_rejects_event_for_another_chain() {
+    let (chain, outcomes) = source_chain();
+    let prover = account("prover.calimero");
+    let connector = account("ft_connector.calimero");
+    // same locker, but the event was sent to the `calimero` shard
+    let mut runtime = connector_with_mock_prover("calimero-2");
+
+    let proof = chain.outcome_proof(&outcomes[0].id, 4);
+    let mint = runtime.call(
+        &account("user.calimero"),
+        &connector,
+        "mint",
+        json!({ "proof": EncodedOutcomeProof::borsh(&proof), "height": 4 }),
+        ONE_NEAR,
+    );
+    assert!(!mint.is_success());
+    assert!(mint
+        .failures()
+        .iter()
+        .any(|failure| failure.starts_with(&BridgeError::WrongDomain.to_string())));
+
+    let calls: Vec<mock_prover::ProveOutcomeCall> = runtime.view(&prover, "get_calls", json!({}));
+    assert!(calls.is_empty());
+}
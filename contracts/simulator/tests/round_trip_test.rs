@@ -0,0 +1,1309 @@
+use connector_base::claims::Claim;
+use connector_base::transfers::{Transfer, TransferStatus};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::{json, Value};
+use near_sdk::{env, AccountId, Gas, ONE_NEAR, ONE_YOCTO};
+use simulator::bridge::{ACCOUNT_BALANCE, RELAY_DEPOSIT};
+use simulator::contract_code;
+use simulator::contracts::bridge_token_code;
+use simulator::BridgeSimulator;
+use types::BridgeError;
+
+fn account(account_id: &str) -> AccountId {
+    account_id.parse().unwrap()
+}
+
+fn balance_of(
+    sim: &mut BridgeSimulator,
+    on_near: bool,
+    token: &AccountId,
+    owner: &AccountId,
+) -> u128 {
+    let runtime = if on_near {
+        &mut sim.near
+    } else {
+        &mut sim.calimero
+    };
+    let balance: U128 = runtime.view(token, "ft_balance_of", json!({ "account_id": owner }));
+    balance.0
+}
+
+/// Deploys `wrap.near` holding 1000 tokens of alice and bridges it to Calimero, alice deposits
+/// for the storage of her locks.
+fn setup_token(sim: &mut BridgeSimulator) -> (AccountId, AccountId) {
+    let wrap = account("wrap.near");
+    let alice = account("alice.near");
+    sim.near.create_account(&wrap, ACCOUNT_BALANCE);
+    sim.near.deploy(&wrap, bridge_token_code);
+    sim.near.create_account(&alice, ACCOUNT_BALANCE);
+    sim.calimero.create_account(&alice, ACCOUNT_BALANCE);
+
+    sim.near
+        .call(&wrap, &wrap, "new", json!({ "controller": wrap }), 0)
+        .unwrap();
+    sim.near
+        .call(
+            &wrap,
+            &wrap,
+            "mint",
+            json!({ "account_id": alice, "amount": "1000" }),
+            ONE_NEAR,
+        )
+        .unwrap();
+    let connector = sim.near_accounts.ft_connector.clone();
+    sim.near
+        .call(
+            &alice,
+            &wrap,
+            "storage_deposit",
+            json!({ "account_id": connector }),
+            ONE_NEAR,
+        )
+        .unwrap();
+    sim.near
+        .call(&alice, &connector, "deposit_fees", json!({}), ONE_NEAR)
+        .unwrap();
+
+    let token = sim.deploy_bridge_token(&wrap);
+    (wrap, token)
+}
+
+fn lock(sim: &mut BridgeSimulator, wrap: &AccountId, amount: u128) {
+    let alice = account("alice.near");
+    let connector = sim.near_accounts.ft_connector.clone();
+    sim.near
+        .call(
+            &alice,
+            wrap,
+            "ft_transfer_call",
+            json!({ "receiver_id": connector, "amount": amount.to_string(), "msg": "" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+}
+
+#[test]
+fn ft_lock_mint_withdraw_unlock() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    assert_eq!(token, account("wrap.ft_deployer.calimero"));
+    let mapping: Option<AccountId> = sim.near.view(
+        &near_connector,
+        "view_mapping",
+        json!({ "source_account": token }),
+    );
+    assert_eq!(mapping, Some(wrap.clone()));
+
+    lock(&mut sim, &wrap, 400);
+    let transfers: Vec<Transfer<U128>> = sim.near.view(
+        &near_connector,
+        "view_transfers",
+        json!({ "account_id": alice, "status": "Pending" }),
+    );
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].transferable, U128(400));
+    let relayed = sim.relay();
+    let methods: Vec<&str> = relayed.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(methods, ["mint", "acknowledge"]);
+    for call in relayed {
+        call.result.unwrap();
+    }
+    let transfers: Vec<Transfer<U128>> = sim.near.view(
+        &near_connector,
+        "view_transfers",
+        json!({ "account_id": alice }),
+    );
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].status, TransferStatus::Completed);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 600);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 400);
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 400);
+
+    // the light client has to follow several epochs of Calimero before the unlock
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+    sim.calimero
+        .call(
+            &alice,
+            &token,
+            "withdraw",
+            json!({ "amount": "150" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let relayed = sim.relay();
+    assert_eq!(relayed.len(), 1);
+    assert_eq!(relayed[0].method, "unlock");
+    relayed[0].result.clone().unwrap();
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 250);
+    let supply: U128 = sim.calimero.view(&token, "ft_total_supply", json!({}));
+    assert_eq!(supply.0, 250);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 750);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 250);
+    let locked: U128 = sim
+        .near
+        .view(&near_connector, "locked_balance", json!({ "token": wrap }));
+    assert_eq!(locked, supply);
+    let all_locked: Vec<(AccountId, U128)> = sim.near.view(
+        &near_connector,
+        "all_locked_tokens",
+        json!({ "from_index": null, "limit": null }),
+    );
+    assert_eq!(all_locked, vec![(wrap, U128(250))]);
+}
+
+#[test]
+fn lock_mints_to_receiver_of_msg() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let bob = account("bob.calimero");
+    sim.calimero.create_account(&bob, ACCOUNT_BALANCE);
+    let connector = sim.near_accounts.ft_connector.clone();
+    let transfer_call =
+        |msg: &str| json!({ "receiver_id": connector, "amount": "400", "msg": msg });
+
+    let invalid = json!({ "receiver_id": "Bob:calimero" }).to_string();
+    let invalid = sim.near.call(
+        &alice,
+        &wrap,
+        "ft_transfer_call",
+        transfer_call(&invalid),
+        ONE_YOCTO,
+    );
+    assert!(invalid
+        .failures()
+        .contains(&BridgeError::InvalidTransferMessage.to_string()));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+
+    let msg = json!({ "receiver_id": bob, "memo": "rent: october" }).to_string();
+    sim.near
+        .call(
+            &alice,
+            &wrap,
+            "ft_transfer_call",
+            transfer_call(&msg),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let relayed = sim.relay();
+    let methods: Vec<&str> = relayed.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(methods, ["mint", "acknowledge"]);
+    for call in relayed {
+        call.result.unwrap();
+    }
+    assert_eq!(balance_of(&mut sim, false, &token, &bob), 400);
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 0);
+}
+
+#[test]
+fn lock_storage_is_paid_from_fee_deposit() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, _) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let connector = sim.near_accounts.ft_connector.clone();
+    let deposit = |sim: &mut BridgeSimulator| -> U128 {
+        sim.near.view(
+            &connector,
+            "view_fee_deposit",
+            json!({ "account_id": alice }),
+        )
+    };
+    let initial_deposit = deposit(&mut sim);
+    lock(&mut sim, &wrap, 100);
+    let remaining_deposit = deposit(&mut sim);
+    assert!(remaining_deposit.0 < initial_deposit.0);
+
+    sim.near
+        .call(
+            &alice,
+            &connector,
+            "withdraw_fee_deposit",
+            json!({ "amount": remaining_deposit }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let uncovered = sim.near.call(
+        &alice,
+        &wrap,
+        "ft_transfer_call",
+        json!({ "receiver_id": connector, "amount": "100", "msg": "" }),
+        ONE_YOCTO,
+    );
+    assert!(uncovered
+        .failures()
+        .contains(&BridgeError::LockStorageNotCovered.to_string()));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 900);
+}
+
+#[test]
+fn relayed_event_cannot_be_replayed() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    lock(&mut sim, &wrap, 100);
+    let mint = sim.relay().remove(0);
+    mint.result.unwrap();
+
+    let relayer = sim.calimero_accounts.relayer.clone();
+    let replay = sim.calimero.call(
+        &relayer,
+        &mint.receiver_id,
+        &mint.method,
+        mint.args.clone(),
+        RELAY_DEPOSIT,
+    );
+    assert!(!replay.is_success());
+    assert!(replay
+        .failures()
+        .contains(&BridgeError::EventReused.to_string()));
+    assert_eq!(
+        balance_of(&mut sim, false, &token, &account("alice.near")),
+        100
+    );
+}
+
+/// Bridge token of which minting always fails.
+fn frozen_token_code(method: &str, input: &[u8]) -> Option<Vec<u8>> {
+    if method == "mint" {
+        panic!("Minting is frozen")
+    }
+    bridge_token_code(method, input)
+}
+
+#[test]
+fn failed_mint_is_refunded() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    sim.calimero.deploy(&token, frozen_token_code);
+    // the refund is proven on NEAR right after the failed mint
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+
+    lock(&mut sim, &wrap, 300);
+    let relayed = sim.relay();
+    let methods: Vec<&str> = relayed.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(methods, ["mint", "refund"]);
+    assert!(relayed[0]
+        .result
+        .failures()
+        .contains(&"Minting is frozen".to_string()));
+    for call in relayed {
+        call.result.unwrap();
+    }
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 0);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 0);
+    let transfers: Vec<Transfer<U128>> = sim.near.view(
+        &near_connector,
+        "view_transfers",
+        json!({ "account_id": alice, "status": "Refunded" }),
+    );
+    assert_eq!(transfers.len(), 1);
+}
+
+#[test]
+fn unminted_lock_can_be_cancelled() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_paused",
+            json!({ "paused": ft_connector::PAUSE_MINT }),
+            0,
+        )
+        .unwrap();
+    sim.near
+        .call(
+            &near_connector,
+            &near_connector,
+            "set_cancel_delay",
+            json!({ "delay_ns": 10 * 1_000_000_000u64 }),
+            0,
+        )
+        .unwrap();
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+
+    lock(&mut sim, &wrap, 300);
+    let mint = sim.relay().remove(0);
+    assert!(mint
+        .result
+        .failures()
+        .contains(&BridgeError::Paused.to_string()));
+    let cancel = json!({ "nonce": "0" });
+    let early = sim
+        .near
+        .call(&alice, &near_connector, "cancel", cancel.clone(), ONE_YOCTO);
+    assert!(early
+        .failures()
+        .contains(&BridgeError::CancelTooEarly.to_string()));
+    for _ in 0..10 {
+        sim.near.produce_block();
+    }
+    let bob = account("bob.near");
+    sim.near.create_account(&bob, ACCOUNT_BALANCE);
+    let stolen = sim
+        .near
+        .call(&bob, &near_connector, "cancel", cancel.clone(), ONE_YOCTO);
+    assert!(stolen
+        .failures()
+        .contains(&BridgeError::Unauthorized.to_string()));
+
+    sim.near
+        .call(&alice, &near_connector, "cancel", cancel, ONE_YOCTO)
+        .unwrap();
+    let relayed = sim.relay();
+    let methods: Vec<&str> = relayed.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(methods, ["cancel_mint", "refund"]);
+    // only the first log of an outcome is proven, the refund is proven with the recorded cancel
+    let cancelled = relayed[0]
+        .result
+        .outcomes
+        .iter()
+        .find(|outcome| outcome.outcome.logs.len() > 1)
+        .unwrap();
+    let first_log = String::from_utf8_lossy(&cancelled.outcome.logs[0]).into_owned();
+    assert!(first_log.starts_with("CALIMERO_EVENT_MINT_FAILED_FT:"));
+    for call in relayed {
+        call.result.unwrap();
+    }
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 0);
+    let transfers: Vec<Transfer<U128>> = sim.near.view(
+        &near_connector,
+        "view_transfers",
+        json!({ "account_id": alice, "status": "Refunded" }),
+    );
+    assert_eq!(transfers.len(), 1);
+
+    // the lock can not be minted once cancelled
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_paused",
+            json!({ "paused": 0 }),
+            0,
+        )
+        .unwrap();
+    let relayer = sim.calimero_accounts.relayer.clone();
+    let replay = sim.calimero.call(
+        &relayer,
+        &mint.receiver_id,
+        &mint.method,
+        mint.args.clone(),
+        RELAY_DEPOSIT,
+    );
+    assert!(replay
+        .failures()
+        .contains(&BridgeError::TransferCancelled.to_string()));
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 0);
+}
+
+#[test]
+fn bridge_fees_are_accrued_and_withdrawn() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let bob = account("bob.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    let schedule = json!({ "flat": "10", "bps": 100, "min": "0", "max": "50" });
+    sim.near
+        .call(
+            &near_connector,
+            &near_connector,
+            "set_fee_schedule",
+            json!({ "token": wrap, "schedule": schedule }),
+            0,
+        )
+        .unwrap();
+    let view: Value = sim.near.view(
+        &near_connector,
+        "view_fee_schedule",
+        json!({ "token": wrap }),
+    );
+    assert_eq!(view, schedule);
+
+    let uncovered = sim.near.call(
+        &alice,
+        &wrap,
+        "ft_transfer_call",
+        json!({ "receiver_id": near_connector, "amount": "10", "msg": "" }),
+        ONE_YOCTO,
+    );
+    assert!(uncovered
+        .failures()
+        .contains(&BridgeError::FeeNotCovered.to_string()));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+
+    // 10 flat and 1% of 400
+    lock(&mut sim, &wrap, 400);
+    for call in sim.relay() {
+        call.result.unwrap();
+    }
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 386);
+    let accrued: U128 = sim.near.view(
+        &near_connector,
+        "view_accrued_fees",
+        json!({ "token": wrap }),
+    );
+    assert_eq!(accrued, U128(14));
+
+    // 10 flat and 1% of 186 rounded down
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+    sim.calimero
+        .call(
+            &alice,
+            &token,
+            "withdraw",
+            json!({ "amount": "186" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    sim.relay().remove(0).result.unwrap();
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 775);
+
+    sim.near.create_account(&bob, ACCOUNT_BALANCE);
+    sim.near
+        .call(
+            &bob,
+            &wrap,
+            "storage_deposit",
+            json!({ "account_id": bob }),
+            ONE_NEAR,
+        )
+        .unwrap();
+    let excessive = sim.near.call(
+        &near_connector,
+        &near_connector,
+        "withdraw_fees",
+        json!({ "token": wrap, "amount": "26", "receiver_id": bob }),
+        0,
+    );
+    assert!(excessive
+        .failures()
+        .contains(&BridgeError::InsufficientFees.to_string()));
+    sim.near
+        .call(
+            &near_connector,
+            &near_connector,
+            "withdraw_fees",
+            json!({ "token": wrap, "amount": "25", "receiver_id": bob }),
+            0,
+        )
+        .unwrap();
+    assert_eq!(balance_of(&mut sim, true, &wrap, &bob), 25);
+    let accrued: U128 = sim.near.view(
+        &near_connector,
+        "view_accrued_fees",
+        json!({ "token": wrap }),
+    );
+    assert_eq!(accrued, U128(0));
+    // what is left in the connector backs exactly what is left on Calimero
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 200);
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 200);
+}
+
+#[test]
+fn exceeded_mint_volume_pauses_minting() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    let limit = |max_volume: &str| {
+        json!({
+            "token": token,
+            "limit": { "max_transfer": null, "max_volume": max_volume, "window_ns": "3600000000000" },
+        })
+    };
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_transfer_limit",
+            limit("500"),
+            0,
+        )
+        .unwrap();
+
+    lock(&mut sim, &wrap, 400);
+    sim.relay().remove(0).result.unwrap();
+    lock(&mut sim, &wrap, 200);
+    let mint = sim.relay().remove(0);
+    let alert = format!("CALIMERO_EVENT_LIMIT_EXCEEDED_FT:{}:200:volume:", token);
+    assert!(mint.result.logs().iter().any(|log| log.starts_with(&alert)));
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 400);
+    let paused: u64 = sim
+        .calimero
+        .view(&calimero_connector, "get_paused", json!({}));
+    assert_eq!(paused, ft_connector::PAUSE_MINT);
+    let volume: U128 = sim.calimero.view(
+        &calimero_connector,
+        "view_volume",
+        json!({ "token": token }),
+    );
+    assert_eq!(volume, U128(400));
+
+    // the lock is minted once the limit is raised and minting unpaused
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_transfer_limit",
+            limit("1000"),
+            0,
+        )
+        .unwrap();
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_paused",
+            json!({ "paused": 0 }),
+            0,
+        )
+        .unwrap();
+    let relayer = sim.calimero_accounts.relayer.clone();
+    sim.calimero
+        .call(
+            &relayer,
+            &mint.receiver_id,
+            &mint.method,
+            mint.args.clone(),
+            RELAY_DEPOSIT,
+        )
+        .unwrap();
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 600);
+}
+
+#[test]
+fn replayed_mint_does_not_count_towards_limits() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_transfer_limit",
+            json!({
+                "token": token,
+                "limit": { "max_transfer": null, "max_volume": "500", "window_ns": "3600000000000" },
+            }),
+            0,
+        )
+        .unwrap();
+
+    lock(&mut sim, &wrap, 400);
+    let mint = sim.relay().remove(0);
+    mint.result.unwrap();
+
+    let relayer = sim.calimero_accounts.relayer.clone();
+    let replay = sim.calimero.call(
+        &relayer,
+        &mint.receiver_id,
+        &mint.method,
+        mint.args.clone(),
+        RELAY_DEPOSIT,
+    );
+    assert!(replay
+        .failures()
+        .contains(&BridgeError::EventReused.to_string()));
+    let paused: u64 = sim
+        .calimero
+        .view(&calimero_connector, "get_paused", json!({}));
+    assert_eq!(paused, 0);
+    let volume: U128 = sim.calimero.view(
+        &calimero_connector,
+        "view_volume",
+        json!({ "token": token }),
+    );
+    assert_eq!(volume, U128(400));
+}
+
+#[test]
+fn mint_above_max_transfer_waits_for_guardian() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_transfer_limit",
+            json!({
+                "token": token,
+                "limit": { "max_transfer": "300", "max_volume": null, "window_ns": "3600000000000" },
+            }),
+            0,
+        )
+        .unwrap();
+
+    lock(&mut sim, &wrap, 400);
+    let mint = sim.relay().remove(0);
+    assert!(mint.result.logs().contains(&"TransferQueued:0".to_string()));
+    let paused: u64 = sim
+        .calimero
+        .view(&calimero_connector, "get_paused", json!({}));
+    assert_eq!(paused, 0);
+
+    let execute = json!({ "id": "0" });
+    let unauthorized = sim.calimero.call(
+        &alice,
+        &calimero_connector,
+        "execute_queued",
+        execute.clone(),
+        0,
+    );
+    assert!(unauthorized
+        .failures()
+        .contains(&BridgeError::Unauthorized.to_string()));
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 0);
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "execute_queued",
+            execute,
+            0,
+        )
+        .unwrap();
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 400);
+    // the approved mint counts towards the window of the token
+    let volume: U128 = sim.calimero.view(
+        &calimero_connector,
+        "view_volume",
+        json!({ "token": token }),
+    );
+    assert_eq!(volume, U128(400));
+}
+
+#[test]
+fn large_mint_is_queued_until_delay_passed() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let bob = account("bob.near");
+    sim.calimero.create_account(&bob, ACCOUNT_BALANCE);
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    for (method, args) in [
+        ("set_guardian", json!({ "guardian_account": bob })),
+        (
+            "set_queue_delay",
+            json!({ "delay_ns": 10 * 1_000_000_000u64 }),
+        ),
+        (
+            "set_queue_threshold",
+            json!({ "token": token, "threshold": "300" }),
+        ),
+    ] {
+        sim.calimero
+            .call(&calimero_connector, &calimero_connector, method, args, 0)
+            .unwrap();
+    }
+
+    lock(&mut sim, &wrap, 400);
+    let mint = sim.relay().remove(0);
+    assert!(mint.result.logs().contains(&"TransferQueued:0".to_string()));
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 0);
+    let queued: Vec<Value> = sim.calimero.view(
+        &calimero_connector,
+        "view_queue",
+        json!({ "from_index": null, "limit": null }),
+    );
+    assert_eq!(queued.len(), 1);
+
+    let execute = json!({ "id": "0" });
+    let early = sim.calimero.call(
+        &bob,
+        &calimero_connector,
+        "execute_queued",
+        execute.clone(),
+        0,
+    );
+    assert!(early
+        .failures()
+        .contains(&BridgeError::QueuedTransferNotReady.to_string()));
+    for _ in 0..10 {
+        sim.calimero.produce_block();
+    }
+    sim.calimero
+        .call(&bob, &calimero_connector, "execute_queued", execute, 0)
+        .unwrap();
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 400);
+
+    // the guardian drops a queued transfer, which can not be executed anymore
+    lock(&mut sim, &wrap, 500);
+    sim.relay().remove(0).result.unwrap();
+    sim.calimero
+        .call(
+            &bob,
+            &calimero_connector,
+            "cancel_queued",
+            json!({ "id": "1" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    for _ in 0..10 {
+        sim.calimero.produce_block();
+    }
+    let dropped = sim.calimero.call(
+        &bob,
+        &calimero_connector,
+        "execute_queued",
+        json!({ "id": "1" }),
+        0,
+    );
+    assert!(dropped
+        .failures()
+        .contains(&BridgeError::QueuedTransferNotFound.to_string()));
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 400);
+}
+
+/// Makes `guardian` the guardian of the ft connector on NEAR, or on Calimero, queueing
+/// transfers of `token` above 300 for 10 seconds.
+fn queue_transfers(
+    sim: &mut BridgeSimulator,
+    on_near: bool,
+    guardian: &AccountId,
+    token: &AccountId,
+) {
+    let (runtime, connector) = if on_near {
+        (&mut sim.near, sim.near_accounts.ft_connector.clone())
+    } else {
+        (
+            &mut sim.calimero,
+            sim.calimero_accounts.ft_connector.clone(),
+        )
+    };
+    runtime.create_account(guardian, ACCOUNT_BALANCE);
+    for (method, args) in [
+        ("set_guardian", json!({ "guardian_account": guardian })),
+        (
+            "set_queue_delay",
+            json!({ "delay_ns": 10 * 1_000_000_000u64 }),
+        ),
+        (
+            "set_queue_threshold",
+            json!({ "token": token, "threshold": "300" }),
+        ),
+    ] {
+        runtime
+            .call(&connector, &connector, method, args, 0)
+            .unwrap();
+    }
+}
+
+#[test]
+fn queued_mint_counts_towards_limits_once_executed() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let bob = account("bob.near");
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    queue_transfers(&mut sim, false, &bob, &token);
+    sim.calimero
+        .call(
+            &calimero_connector,
+            &calimero_connector,
+            "set_transfer_limit",
+            json!({
+                "token": token,
+                "limit": { "max_transfer": null, "max_volume": "600", "window_ns": "3600000000000" },
+            }),
+            0,
+        )
+        .unwrap();
+    let volume = |sim: &mut BridgeSimulator| -> U128 {
+        sim.calimero.view(
+            &calimero_connector,
+            "view_volume",
+            json!({ "token": token }),
+        )
+    };
+
+    lock(&mut sim, &wrap, 400);
+    lock(&mut sim, &wrap, 400);
+    for call in sim.relay() {
+        call.result.unwrap();
+    }
+    assert_eq!(volume(&mut sim), U128(0));
+    for _ in 0..10 {
+        sim.calimero.produce_block();
+    }
+    sim.calimero
+        .call(
+            &alice,
+            &calimero_connector,
+            "execute_queued",
+            json!({ "id": "0" }),
+            0,
+        )
+        .unwrap();
+    assert_eq!(volume(&mut sim), U128(400));
+
+    // exceeding the volume pauses minting and keeps the transfer queued
+    sim.calimero
+        .call(
+            &alice,
+            &calimero_connector,
+            "execute_queued",
+            json!({ "id": "1" }),
+            0,
+        )
+        .unwrap();
+    let paused: u64 = sim
+        .calimero
+        .view(&calimero_connector, "get_paused", json!({}));
+    assert_eq!(paused, ft_connector::PAUSE_MINT);
+    let queued: Vec<Value> = sim.calimero.view(
+        &calimero_connector,
+        "view_queue",
+        json!({ "from_index": null, "limit": null }),
+    );
+    assert_eq!(queued.len(), 1);
+    assert_eq!(volume(&mut sim), U128(400));
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 400);
+}
+
+#[test]
+fn cancelled_queued_mint_is_refunded() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let bob = account("bob.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    let calimero_connector = sim.calimero_accounts.ft_connector.clone();
+    queue_transfers(&mut sim, false, &bob, &token);
+    // the refund is proven on NEAR right after the cancellation
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+
+    lock(&mut sim, &wrap, 400);
+    let mint = sim.relay().remove(0);
+    assert!(mint.result.logs().contains(&"TransferQueued:0".to_string()));
+    let cancelled = sim
+        .calimero
+        .call(
+            &bob,
+            &calimero_connector,
+            "cancel_queued",
+            json!({ "id": "0" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let event = format!(
+        "CALIMERO_EVENT_QUEUE_CANCELLED_FT:0:mint:{}:{}:400:",
+        token, alice
+    );
+    assert!(cancelled.logs().iter().any(|log| log.starts_with(&event)));
+
+    let relayed = sim.relay();
+    let methods: Vec<&str> = relayed.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(methods, ["refund"]);
+    relayed[0].result.clone().unwrap();
+    assert_eq!(balance_of(&mut sim, false, &token, &alice), 0);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+    let locked: U128 = sim
+        .near
+        .view(&near_connector, "locked_balance", json!({ "token": wrap }));
+    assert_eq!(locked, U128(0));
+    let transfers: Vec<Transfer<U128>> = sim.near.view(
+        &near_connector,
+        "view_transfers",
+        json!({ "account_id": alice, "status": "Refunded" }),
+    );
+    assert_eq!(transfers.len(), 1);
+}
+
+/// Bridges 400 tokens of alice to Calimero and withdraws them again, the unlock being queued on
+/// NEAR with bob as the guardian.
+fn queued_unlock(sim: &mut BridgeSimulator) -> AccountId {
+    let (wrap, token) = setup_token(sim);
+    let alice = account("alice.near");
+    queue_transfers(sim, true, &account("bob.near"), &wrap);
+    lock(sim, &wrap, 400);
+    sim.relay();
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+
+    sim.calimero
+        .call(
+            &alice,
+            &token,
+            "withdraw",
+            json!({ "amount": "400" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let unlock = sim.relay().remove(0);
+    assert_eq!(unlock.method, "unlock");
+    assert!(unlock
+        .result
+        .logs()
+        .contains(&"TransferQueued:0".to_string()));
+    wrap
+}
+
+#[test]
+fn queued_unlock_releases_locked_once_executed() {
+    let mut sim = BridgeSimulator::new();
+    let wrap = queued_unlock(&mut sim);
+    let alice = account("alice.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    let locked = |sim: &mut BridgeSimulator| -> U128 {
+        sim.near
+            .view(&near_connector, "locked_balance", json!({ "token": wrap }))
+    };
+    assert_eq!(locked(&mut sim), U128(400));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 600);
+
+    for _ in 0..10 {
+        sim.near.produce_block();
+    }
+    // the deposit has to cover keeping the Token claimable if the transfer fails
+    let unpaid = sim.near.call(
+        &alice,
+        &near_connector,
+        "execute_queued",
+        json!({ "id": "0" }),
+        0,
+    );
+    assert!(unpaid
+        .failures()
+        .contains(&BridgeError::DepositTooLow.to_string()));
+    assert_eq!(locked(&mut sim), U128(400));
+    sim.near
+        .call(
+            &alice,
+            &near_connector,
+            "execute_queued",
+            json!({ "id": "0" }),
+            RELAY_DEPOSIT,
+        )
+        .unwrap();
+    assert_eq!(locked(&mut sim), U128(0));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 0);
+}
+
+#[test]
+fn cancelled_queued_unlock_stays_locked() {
+    let mut sim = BridgeSimulator::new();
+    let wrap = queued_unlock(&mut sim);
+    let alice = account("alice.near");
+    let bob = account("bob.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    let cancelled = sim
+        .near
+        .call(
+            &bob,
+            &near_connector,
+            "cancel_queued",
+            json!({ "id": "0" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let event = format!(
+        "CALIMERO_EVENT_QUEUE_CANCELLED_FT:0:unlock:{}:{}:400:",
+        wrap, alice
+    );
+    assert!(cancelled.logs().iter().any(|log| log.starts_with(&event)));
+
+    // the Token is still held, so the locked balance still accounts for it
+    let locked: U128 = sim
+        .near
+        .view(&near_connector, "locked_balance", json!({ "token": wrap }));
+    assert_eq!(locked, U128(400));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 400);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 600);
+    for _ in 0..10 {
+        sim.near.produce_block();
+    }
+    let dropped = sim.near.call(
+        &alice,
+        &near_connector,
+        "execute_queued",
+        json!({ "id": "0" }),
+        0,
+    );
+    assert!(dropped
+        .failures()
+        .contains(&BridgeError::QueuedTransferNotFound.to_string()));
+}
+
+/// Bridge token of which transfers always fail.
+fn stuck_token_code(method: &str, input: &[u8]) -> Option<Vec<u8>> {
+    if method == "ft_transfer" {
+        panic!("Transfers are stuck")
+    }
+    bridge_token_code(method, input)
+}
+
+#[test]
+fn failed_unlock_is_claimable() {
+    let mut sim = BridgeSimulator::new();
+    let (wrap, token) = setup_token(&mut sim);
+    let alice = account("alice.near");
+    let near_connector = sim.near_accounts.ft_connector.clone();
+    lock(&mut sim, &wrap, 200);
+    sim.relay();
+    for _ in 0..35 {
+        sim.calimero.produce_block();
+    }
+
+    sim.near.deploy(&wrap, stuck_token_code);
+    sim.calimero
+        .call(
+            &alice,
+            &token,
+            "withdraw",
+            json!({ "amount": "200" }),
+            ONE_YOCTO,
+        )
+        .unwrap();
+    let unlock = sim.relay().remove(0);
+    assert_eq!(unlock.method, "unlock");
+    assert!(unlock
+        .result
+        .failures()
+        .contains(&"Transfers are stuck".to_string()));
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 800);
+    let claims: Vec<Claim<U128>> = sim.near.view(
+        &near_connector,
+        "view_claimable",
+        json!({ "account_id": alice }),
+    );
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims[0].token, wrap);
+    assert_eq!(claims[0].transferable, U128(200));
+
+    sim.near.deploy(&wrap, bridge_token_code);
+    let claim = json!({ "transfer_id": claims[0].transfer_id });
+    let bob = account("bob.near");
+    sim.near.create_account(&bob, ACCOUNT_BALANCE);
+    let stolen = sim
+        .near
+        .call(&bob, &near_connector, "claim", claim.clone(), ONE_YOCTO);
+    assert!(stolen
+        .failures()
+        .contains(&BridgeError::ClaimNotFound.to_string()));
+    sim.near
+        .call(&alice, &near_connector, "claim", claim, ONE_YOCTO)
+        .unwrap();
+    assert_eq!(balance_of(&mut sim, true, &wrap, &alice), 1000);
+    assert_eq!(balance_of(&mut sim, true, &wrap, &near_connector), 0);
+    let claims: Vec<Claim<U128>> = sim.near.view(
+        &near_connector,
+        "view_claimable",
+        json!({ "account_id": alice }),
+    );
+    assert!(claims.is_empty());
+}
+
+#[test]
+fn simulation_is_deterministic() {
+    let run = || {
+        let mut sim = BridgeSimulator::new();
+        let (wrap, token) = setup_token(&mut sim);
+        lock(&mut sim, &wrap, 10);
+        sim.relay();
+        sim.calimero
+            .call(
+                &account("alice.near"),
+                &token,
+                "withdraw",
+                json!({ "amount": "10" }),
+                ONE_YOCTO,
+            )
+            .unwrap();
+        sim.relay();
+        let near_head = sim.near.chain().head_height();
+        let calimero_head = sim.calimero.chain().head_height();
+        (
+            sim.near.chain().block_hash(near_head),
+            sim.calimero.chain().block_hash(calimero_head),
+        )
+    };
+    assert_eq!(run(), run());
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct Caller {
+    xsc_connector: AccountId,
+    responses: Vec<Option<String>>,
+    calimero_predecessor_id: Option<AccountId>,
+}
+
+impl Caller {
+    fn new(xsc_connector: AccountId) -> Self {
+        Self {
+            xsc_connector,
+            responses: vec![],
+            calimero_predecessor_id: None,
+        }
+    }
+
+    fn call_calimero(&mut self, contract: String, method: String, args: String) {
+        env::promise_create(
+            self.xsc_connector.clone(),
+            "cross_call",
+            &near_sdk::serde_json::to_vec(&json!({
+                "destination_contract_id": contract,
+                "destination_contract_method": method,
+                "destination_contract_args": args,
+                "destination_gas": Gas(20_000_000_000_000),
+                "destination_deposit": 0,
+                "source_callback_method": "on_response",
+            }))
+            .unwrap(),
+            0,
+            Gas(100_000_000_000_000),
+        );
+    }
+
+    fn on_response(&mut self, response: Option<Vec<u8>>, calimero_predecessor_id: AccountId) {
+        assert_eq!(env::predecessor_account_id(), self.xsc_connector);
+        self.responses
+            .push(response.map(|response| String::from_utf8(response).unwrap()));
+        self.calimero_predecessor_id = Some(calimero_predecessor_id);
+    }
+
+    fn responses(&self) -> Vec<Option<String>> {
+        self.responses.clone()
+    }
+
+    fn calimero_predecessor_id(&self) -> Option<AccountId> {
+        self.calimero_predecessor_id.clone()
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct Greeter {}
+
+impl Greeter {
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn greet(&self, name: String) -> String {
+        format!("Hello, {}!", name)
+    }
+
+    fn fail(&self) {
+        panic!("Greeter failed")
+    }
+}
+
+contract_code! {
+    fn caller_code(Caller) {
+        #[init] fn new(xsc_connector: AccountId);
+        fn call_calimero(contract: String, method: String, args: String);
+        fn on_response(response: Option<Vec<u8>>, calimero_predecessor_id: AccountId);
+        fn responses() -> Vec<Option<String>>;
+        fn calimero_predecessor_id() -> Option<AccountId>;
+    }
+}
+
+contract_code! {
+    fn greeter_code(Greeter) {
+        #[init] fn new();
+        fn greet(name: String) -> String;
+        fn fail();
+    }
+}
+
+fn setup_cross_call() -> (BridgeSimulator, AccountId, AccountId) {
+    let mut sim = BridgeSimulator::new();
+    let caller = account("caller.near");
+    let greeter = account("greeter.calimero");
+    sim.near.create_account(&caller, ACCOUNT_BALANCE);
+    sim.near.deploy(&caller, caller_code);
+    let xsc_connector = sim.near_accounts.xsc_connector.clone();
+    sim.near
+        .call(
+            &caller,
+            &caller,
+            "new",
+            json!({ "xsc_connector": xsc_connector }),
+            0,
+        )
+        .unwrap();
+    sim.calimero.create_account(&greeter, ACCOUNT_BALANCE);
+    sim.calimero.deploy(&greeter, greeter_code);
+    sim.calimero
+        .call(&greeter, &greeter, "new", json!({}), 0)
+        .unwrap();
+    (sim, caller, greeter)
+}
+
+#[test]
+fn cross_call_round_trip() {
+    let (mut sim, caller, greeter) = setup_cross_call();
+    let args = json!({ "name": "alice" }).to_string();
+    sim.near
+        .call(
+            &caller,
+            &caller,
+            "call_calimero",
+            json!({ "contract": greeter, "method": "greet", "args": args }),
+            0,
+        )
+        .unwrap();
+
+    let relayed = sim.relay();
+    let methods: Vec<&str> = relayed.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(
+        methods,
+        ["cross_call_execute", "cross_call_receive_response"]
+    );
+    for call in relayed {
+        call.result.unwrap();
+    }
+    let responses: Vec<Option<String>> = sim.near.view(&caller, "responses", json!({}));
+    assert_eq!(responses, [Some("\"Hello, alice!\"".to_string())]);
+    let predecessor: Option<AccountId> =
+        sim.near.view(&caller, "calimero_predecessor_id", json!({}));
+    assert_eq!(predecessor, Some(greeter));
+}
+
+#[test]
+fn failed_cross_call_returns_no_response() {
+    let (mut sim, caller, greeter) = setup_cross_call();
+    sim.near
+        .call(
+            &caller,
+            &caller,
+            "call_calimero",
+            json!({ "contract": greeter, "method": "fail", "args": "{}" }),
+            0,
+        )
+        .unwrap();
+
+    let relayed = sim.relay();
+    assert_eq!(relayed.len(), 2);
+    assert!(relayed[0]
+        .result
+        .failures()
+        .contains(&"Greeter failed".to_string()));
+    let responses: Vec<Option<String>> = sim.near.view(&caller, "responses", json!({}));
+    assert_eq!(responses, [None]);
+}
@@ -0,0 +1,312 @@
+//! Synthetic NEAR-like chain producing signed light client blocks and outcome proofs.
+//!
+//! Blocks follow the hashing and signing rules checked by the light client and the prover,
+//! so they can be used instead of captured RPC responses.
+
+use near_sdk::borsh::BorshSerialize;
+use std::collections::HashMap;
+use types::signature::{ED25519SecretKey, SecretKey};
+use types::{
+    Block, BlockHeaderInnerLite, BlockHeaderLight, ExecutionOutcome, ExecutionOutcomeWithId,
+    ExecutionOutcomeWithIdAndProof, ExecutionStatus, FullOutcomeProof, MerklePath, Validator,
+};
+use utils::hashes::{combine_hash2, sha256};
+use utils::merkle::{merklize_hashes, PartialMerkleTree};
+use utils::{swap_bytes8, Hash, Hashable};
+
+pub const DEFAULT_VALIDATOR_STAKE: u128 = 1_000_000_000_000_000_000_000_000_000_000;
+const GENESIS_TIMESTAMP: u64 = 1_600_000_000_000_000_000;
+const BLOCK_TIME: u64 = 1_000_000_000;
+
+/// Deterministic ed25519 key, the same seed always gives the same key.
+pub fn secret_key_from_seed(seed: &str) -> SecretKey {
+    let secret = ed25519_dalek::SecretKey::from_bytes(&sha256(seed.as_bytes())).unwrap();
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    let mut keypair = [0u8; ed25519_dalek::KEYPAIR_LENGTH];
+    keypair[..ed25519_dalek::SECRET_KEY_LENGTH].copy_from_slice(secret.as_bytes());
+    keypair[ed25519_dalek::SECRET_KEY_LENGTH..].copy_from_slice(public.as_bytes());
+    SecretKey::ED25519(ED25519SecretKey(keypair))
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidatorKey {
+    pub account_id: String,
+    pub secret_key: SecretKey,
+    pub stake: u128,
+}
+
+impl ValidatorKey {
+    pub fn new(account_id: &str, stake: u128) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            secret_key: secret_key_from_seed(account_id),
+            stake,
+        }
+    }
+
+    pub fn validator(&self) -> Validator {
+        Validator::new_v1(
+            self.account_id.clone(),
+            self.secret_key.public_key(),
+            self.stake,
+        )
+    }
+}
+
+/// Outcome with the given logs, the id is derived from `seed` so it is unique per seed.
+pub fn outcome(seed: &str, executor_id: &str, logs: &[&str]) -> ExecutionOutcomeWithId {
+    ExecutionOutcomeWithId {
+        id: sha256(seed.as_bytes()),
+        outcome: ExecutionOutcome {
+            logs: logs.iter().map(|log| log.as_bytes().to_vec()).collect(),
+            receipt_ids: vec![],
+            gas_burnt: 2_428_395_018_008,
+            tokens_burnt: 242_839_501_800_800_000_000,
+            executor_id: executor_id.to_string(),
+            status: ExecutionStatus::SuccessValue(vec![]),
+        },
+    }
+}
+
+struct ProducedBlock {
+    header: BlockHeaderLight,
+    inner_hash: Hash,
+    hash: Hash,
+    // outcomes of every shard
+    chunks: Vec<Vec<ExecutionOutcomeWithId>>,
+}
+
+pub struct ChainGenerator {
+    genesis_height: u64,
+    epoch_length: u64,
+    num_shards: usize,
+    validators_per_epoch: usize,
+    chain_name: String,
+    blocks: Vec<ProducedBlock>,
+    block_tree: PartialMerkleTree,
+    // outcome id -> (block index, shard, index in the chunk)
+    outcomes: HashMap<Hash, (usize, usize, usize)>,
+}
+
+impl ChainGenerator {
+    /// `chain_name` is mixed into every key and hash so two generated chains never collide.
+    pub fn new(chain_name: &str, genesis_height: u64, epoch_length: u64) -> Self {
+        assert!(epoch_length > 0, "Epoch length must be positive");
+        Self {
+            genesis_height,
+            epoch_length,
+            num_shards: 1,
+            validators_per_epoch: 4,
+            chain_name: chain_name.to_string(),
+            blocks: vec![],
+            block_tree: PartialMerkleTree::default(),
+            outcomes: HashMap::new(),
+        }
+    }
+
+    pub fn with_shards(mut self, num_shards: usize) -> Self {
+        assert!(self.blocks.is_empty(), "Shards must be set before producing blocks");
+        self.num_shards = num_shards;
+        self
+    }
+
+    pub fn with_validators(mut self, validators_per_epoch: usize) -> Self {
+        assert!(self.blocks.is_empty(), "Validators must be set before producing blocks");
+        self.validators_per_epoch = validators_per_epoch;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.chain_name
+    }
+
+    /// Every epoch has its own validator set, so each epoch change hands over to new keys.
+    pub fn validator_keys(&self, epoch: u64) -> Vec<ValidatorKey> {
+        (0..self.validators_per_epoch)
+            .map(|i| {
+                ValidatorKey::new(
+                    &format!("{}-epoch{}-validator{}", self.chain_name, epoch, i),
+                    DEFAULT_VALIDATOR_STAKE,
+                )
+            })
+            .collect()
+    }
+
+    pub fn validators(&self, epoch: u64) -> Vec<Validator> {
+        self.validator_keys(epoch)
+            .iter()
+            .map(ValidatorKey::validator)
+            .collect()
+    }
+
+    pub fn epoch_of(&self, height: u64) -> u64 {
+        (height - self.genesis_height) / self.epoch_length
+    }
+
+    /// Height of the first block of `epoch`.
+    pub fn epoch_start(&self, epoch: u64) -> u64 {
+        self.genesis_height + epoch * self.epoch_length
+    }
+
+    pub fn epoch_id(&self, epoch: u64) -> Hash {
+        sha256(format!("{}-epoch{}", self.chain_name, epoch).as_bytes())
+    }
+
+    pub fn genesis_height(&self) -> u64 {
+        self.genesis_height
+    }
+
+    /// Height of the last produced block.
+    pub fn head_height(&self) -> u64 {
+        assert!(!self.blocks.is_empty(), "No blocks produced");
+        self.genesis_height + self.blocks.len() as u64 - 1
+    }
+
+    pub fn block_hash(&self, height: u64) -> Hash {
+        self.block(height).hash
+    }
+
+    /// Timestamp of the block at `height`, also defined for blocks not produced yet.
+    pub fn block_timestamp(&self, height: u64) -> u64 {
+        GENESIS_TIMESTAMP + height * BLOCK_TIME
+    }
+
+    pub fn block_merkle_root(&self, height: u64) -> Hash {
+        self.block(height).header.inner_lite.block_merkle_root
+    }
+
+    /// Produces the next block, `outcomes` are put into the chunk of shard 0.
+    pub fn produce_block(&mut self, outcomes: Vec<ExecutionOutcomeWithId>) -> u64 {
+        let mut chunks = vec![vec![]; self.num_shards];
+        chunks[0] = outcomes;
+        self.produce_block_with_chunks(chunks)
+    }
+
+    pub fn produce_blocks(&mut self, count: u64) -> u64 {
+        for _ in 0..count {
+            self.produce_block(vec![]);
+        }
+        self.head_height()
+    }
+
+    pub fn produce_block_with_chunks(&mut self, chunks: Vec<Vec<ExecutionOutcomeWithId>>) -> u64 {
+        assert_eq!(chunks.len(), self.num_shards, "One chunk per shard is expected");
+        let height = self.genesis_height + self.blocks.len() as u64;
+        let epoch = self.epoch_of(height);
+        let index = self.blocks.len();
+
+        for (shard, chunk) in chunks.iter().enumerate() {
+            for (i, outcome) in chunk.iter().enumerate() {
+                let previous = self.outcomes.insert(outcome.id, (index, shard, i));
+                assert!(previous.is_none(), "Outcome id is used twice");
+            }
+        }
+
+        let inner_lite = BlockHeaderInnerLite {
+            height,
+            epoch_id: self.epoch_id(epoch),
+            next_epoch_id: self.epoch_id(epoch + 1),
+            prev_state_root: sha256(format!("{}-state{}", self.chain_name, height).as_bytes()),
+            outcome_root: Self::outcome_root_and_paths(&chunks).0,
+            timestamp: self.block_timestamp(height),
+            next_bp_hash: types::block_producers_hash(&self.validators(epoch + 1)),
+            block_merkle_root: self.block_tree.root(),
+        };
+        let header = BlockHeaderLight {
+            prev_block_hash: self.blocks.last().map(|block| block.hash).unwrap_or_default(),
+            inner_rest_hash: sha256(format!("{}-rest{}", self.chain_name, height).as_bytes()),
+            inner_lite,
+        };
+        let inner_lite_hash = sha256(&header.inner_lite.try_to_vec().unwrap());
+        let inner_hash = combine_hash2(inner_lite_hash, header.inner_rest_hash);
+        let hash = header.hash();
+
+        self.block_tree.insert(hash);
+        self.blocks.push(ProducedBlock {
+            header,
+            inner_hash,
+            hash,
+            chunks,
+        });
+        height
+    }
+
+    /// Light client block for `height`, signed by all validators of its epoch.
+    /// The next block has to be produced already, its inner hash is part of the approvals.
+    pub fn light_client_block(&self, height: u64) -> Block {
+        let block = self.block(height);
+        let next_block_inner_hash = self
+            .blocks
+            .get((height - self.genesis_height + 1) as usize)
+            .expect("Next block has to be produced first")
+            .inner_hash;
+
+        let message = [
+            &[0],
+            &combine_hash2(next_block_inner_hash, block.hash) as &[_],
+            &swap_bytes8(height + 2).to_be_bytes() as &[_],
+        ]
+        .concat();
+        let approvals_after_next = self
+            .validator_keys(self.epoch_of(height))
+            .iter()
+            .map(|key| Some(key.secret_key.sign(&message)))
+            .collect();
+
+        Block {
+            prev_block_hash: block.header.prev_block_hash,
+            next_block_inner_hash,
+            inner_lite: block.header.inner_lite.clone(),
+            inner_rest_hash: block.header.inner_rest_hash,
+            next_bps: Some(self.validators(self.epoch_of(height) + 1)),
+            approvals_after_next,
+        }
+    }
+
+    /// Proof of `outcome_id` against the `block_merkle_root` of the block at `head_height`,
+    /// which has to be above the block containing the outcome.
+    pub fn outcome_proof(&self, outcome_id: &Hash, head_height: u64) -> FullOutcomeProof {
+        let (index, shard, i) = *self.outcomes.get(outcome_id).expect("Unknown outcome");
+        let head_index = (head_height - self.genesis_height) as usize;
+        assert!(
+            index < head_index && head_index < self.blocks.len(),
+            "Head block must be produced after the outcome block"
+        );
+        let block = &self.blocks[index];
+
+        let (_, outcome_root_paths) = Self::outcome_root_and_paths(&block.chunks);
+        let chunk_leaves: Vec<Hash> = block.chunks[shard].iter().map(|o| o.hash()).collect();
+        let (_, chunk_paths) = merklize_hashes(&chunk_leaves);
+        let block_hashes: Vec<Hash> = self.blocks[..head_index].iter().map(|b| b.hash).collect();
+        let (_, block_paths) = merklize_hashes(&block_hashes);
+
+        FullOutcomeProof {
+            outcome_proof: ExecutionOutcomeWithIdAndProof {
+                proof: chunk_paths[i].clone(),
+                block_hash: block.hash,
+                outcome_with_id: block.chunks[shard][i].clone(),
+            },
+            outcome_root_proof: outcome_root_paths[shard].clone(),
+            block_header_lite: block.header.clone(),
+            block_proof: block_paths[index].clone(),
+        }
+    }
+
+    fn block(&self, height: u64) -> &ProducedBlock {
+        height
+            .checked_sub(self.genesis_height)
+            .and_then(|index| self.blocks.get(index as usize))
+            .expect("Block is not produced")
+    }
+
+    fn outcome_root_and_paths(chunks: &[Vec<ExecutionOutcomeWithId>]) -> (Hash, Vec<MerklePath>) {
+        let chunk_roots: Vec<Hash> = chunks
+            .iter()
+            .map(|chunk| {
+                let leaves: Vec<Hash> = chunk.iter().map(|outcome| outcome.hash()).collect();
+                sha256(&merklize_hashes(&leaves).0)
+            })
+            .collect();
+        merklize_hashes(&chunk_roots)
+    }
+}
@@ -1,3 +1,6 @@
+pub mod chain;
+pub mod rpc;
+
 use near_sdk::serde::de::DeserializeOwned;
 use std::error::Error;
 use std::fs::File;
@@ -10,3 +13,38 @@ pub fn file_as_json<T: DeserializeOwned>(filename: &str) -> Result<T, Box<dyn Er
 
     return Ok(value);
 }
+
+/// Appends the source and destination chain ids connectors tag events with to the first log
+/// of a recorded proof. The outcome no longer matches its merkle proof, so the result is only
+/// accepted by the mock prover.
+pub fn with_chain_ids(
+    mut proof: types::FullOutcomeProof,
+    source_chain: &str,
+    destination_chain: &str,
+) -> types::FullOutcomeProof {
+    let logs = &mut proof.outcome_proof.outcome_with_id.outcome.logs;
+    logs[0].extend(format!(":{}:{}", source_chain, destination_chain).into_bytes());
+    proof
+}
+
+/// Appends the sender of the lock event in the first log of a recorded proof as its receiver,
+/// with an empty memo, before `with_nonce`. Like it, the result is only accepted by the mock
+/// prover.
+pub fn with_receiver(mut proof: types::FullOutcomeProof) -> types::FullOutcomeProof {
+    let logs = &mut proof.outcome_proof.outcome_with_id.outcome.logs;
+    let sender = String::from_utf8_lossy(&logs[0])
+        .split(':')
+        .nth(2)
+        .unwrap_or_default()
+        .to_string();
+    logs[0].extend(format!(":{}:", sender).into_bytes());
+    proof
+}
+
+/// Appends the nonce source connectors tag lock events with to the first log of a recorded
+/// proof, before `with_chain_ids`. Like it, the result is only accepted by the mock prover.
+pub fn with_nonce(mut proof: types::FullOutcomeProof, nonce: u64) -> types::FullOutcomeProof {
+    let logs = &mut proof.outcome_proof.outcome_with_id.outcome.logs;
+    logs[0].extend(format!(":{}", nonce).into_bytes());
+    proof
+}
@@ -0,0 +1,200 @@
+//! Local JSON-RPC server standing in for a NEAR node in tests of off-chain services.
+//!
+//! Requests are answered on a background thread by a handler, which makes it possible to
+//! serve captured fixtures, fail requests on purpose or back the answers by contracts
+//! running natively in that thread.
+
+use near_sdk::serde_json::{self, json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Answer of the handler to one request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockResponse {
+    /// Sent as the `result` of the JSON-RPC response.
+    Result(Value),
+    /// Sent as the `error` of the JSON-RPC response.
+    Error(Value),
+    /// Sent as a bare HTTP status, as a proxy in front of an unavailable node does.
+    Status(u16),
+}
+
+/// Closure run on the server thread between two requests.
+type Command<S> = Box<dyn FnOnce(&mut S) + Send>;
+
+pub struct MockRpcServer<S = ()> {
+    url: String,
+    requests: Arc<Mutex<Vec<(String, Value)>>>,
+    stopped: Arc<AtomicBool>,
+    commands: Sender<Command<S>>,
+}
+
+impl MockRpcServer {
+    /// Starts the server on a free local port, `handler` gets the method and params of every
+    /// request in the order they arrive.
+    pub fn start<F>(mut handler: F) -> Self
+    where
+        F: FnMut(&str, &Value) -> MockResponse + Send + 'static,
+    {
+        Self::start_with_state(|| (), move |_, _, method, params| handler(method, params))
+    }
+}
+
+impl<S: 'static> MockRpcServer<S> {
+    /// Starts the server with a state created by `init` on the server thread, so it may hold
+    /// thread bound data such as mocked blockchains. `handler` also gets the path of the
+    /// request, which lets one server stand in for nodes of several chains.
+    pub fn start_with_state<I, F>(init: I, mut handler: F) -> Self
+    where
+        I: FnOnce() -> S + Send + 'static,
+        F: FnMut(&mut S, &str, &str, &Value) -> MockResponse + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind mock rpc server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let (commands, received_commands) = mpsc::channel::<Command<S>>();
+
+        let server_requests = requests.clone();
+        let server_stopped = stopped.clone();
+        thread::spawn(move || {
+            let mut state = init();
+            for stream in listener.incoming() {
+                if server_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                for command in received_commands.try_iter() {
+                    command(&mut state);
+                }
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                let (path, request) = match read_request(&mut stream) {
+                    Some(request) => request,
+                    None => continue,
+                };
+                let id = request["id"].clone();
+                let method = request["method"].as_str().unwrap_or_default().to_string();
+                let params = request["params"].clone();
+                server_requests
+                    .lock()
+                    .unwrap()
+                    .push((method.clone(), params.clone()));
+
+                let (status, body) = match handler(&mut state, &path, &method, &params) {
+                    MockResponse::Result(result) => {
+                        (200, json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+                    }
+                    MockResponse::Error(error) => {
+                        (200, json!({ "jsonrpc": "2.0", "id": id, "error": error }))
+                    }
+                    MockResponse::Status(status) => (status, Value::Null),
+                };
+                write_response(&mut stream, status, &body);
+            }
+        });
+
+        Self {
+            url,
+            requests,
+            stopped,
+            commands,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Method and params of every request received so far.
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Params of the requests received so far for `method`.
+    pub fn requests_for(&self, method: &str) -> Vec<Value> {
+        self.requests()
+            .into_iter()
+            .filter(|(request_method, _)| request_method == method)
+            .map(|(_, params)| params)
+            .collect()
+    }
+
+    /// Runs `f` with the state on the server thread and returns its result.
+    pub fn with_state<R, F>(&self, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut S) -> R + Send + 'static,
+    {
+        let (result, received_result) = mpsc::channel();
+        self.commands
+            .send(Box::new(move |state: &mut S| {
+                let _ = result.send(f(state));
+            }))
+            .expect("Mock rpc server stopped");
+        self.wake_up();
+        received_result.recv().expect("Mock rpc server stopped")
+    }
+
+    fn wake_up(&self) {
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+    }
+}
+
+impl<S> Drop for MockRpcServer<S> {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // wakes up the server thread so it sees the flag
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+    }
+}
+
+/// Path and body of the request.
+fn read_request(stream: &mut TcpStream) -> Option<(String, Value)> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    Some((path, serde_json::from_slice(&body).ok()?))
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &Value) {
+    let body = if body.is_null() {
+        String::new()
+    } else {
+        body.to_string()
+    };
+    let reason = if status == 200 { "OK" } else { "Error" };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    let _ = stream.flush();
+}
@@ -0,0 +1,72 @@
+use test_utils::chain::{outcome, ChainGenerator};
+use utils::Hashable;
+
+#[test]
+fn blocks_are_linked() {
+    let mut chain = ChainGenerator::new("near", 100, 5);
+    let head = chain.produce_blocks(12);
+    assert_eq!(head, 111);
+
+    for height in 101..head {
+        let block = chain.light_client_block(height);
+        assert_eq!(block.hash(), chain.block_hash(height));
+        assert_eq!(block.prev_block_hash, chain.block_hash(height - 1));
+        assert_eq!(
+            block.inner_lite.next_bp_hash,
+            types::block_producers_hash(block.next_bps.as_ref().unwrap())
+        );
+    }
+    assert_ne!(
+        chain.light_client_block(104).inner_lite.epoch_id,
+        chain.light_client_block(105).inner_lite.epoch_id
+    );
+}
+
+#[test]
+fn chains_do_not_collide() {
+    let mut near = ChainGenerator::new("near", 100, 5);
+    let mut calimero = ChainGenerator::new("calimero", 100, 5);
+    near.produce_blocks(2);
+    calimero.produce_blocks(2);
+    assert_ne!(near.block_hash(101), calimero.block_hash(101));
+    assert_ne!(near.validators(0), calimero.validators(0));
+}
+
+#[test]
+fn outcome_proofs_match_blocks() {
+    let mut chain = ChainGenerator::new("near", 100, 5).with_shards(3);
+    chain.produce_blocks(3);
+
+    let outcomes: Vec<_> = (0..5)
+        .map(|i| outcome(&format!("lock{}", i), "ft_connector.near", &["lock"]))
+        .collect();
+    let block_height = chain.produce_block_with_chunks(vec![
+        outcomes[..2].to_vec(),
+        vec![],
+        outcomes[2..].to_vec(),
+    ]);
+    chain.produce_blocks(9);
+
+    for head in block_height + 1..=chain.head_height() {
+        for outcome in &outcomes {
+            let proof = chain.outcome_proof(&outcome.id, head);
+            assert_eq!(proof.outcome_proof.outcome_with_id, *outcome);
+            assert_eq!(proof.outcome_proof.block_hash, chain.block_hash(block_height));
+            assert_eq!(
+                proof.outcome_root().unwrap(),
+                proof.block_header_lite.inner_lite.outcome_root
+            );
+            assert_eq!(proof.block_merkle_root().unwrap(), chain.block_merkle_root(head));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Head block must be produced after the outcome block")]
+fn proof_needs_later_head() {
+    let mut chain = ChainGenerator::new("near", 100, 5);
+    let lock = outcome("lock", "ft_connector.near", &["lock"]);
+    let height = chain.produce_block(vec![lock.clone()]);
+    chain.produce_blocks(1);
+    chain.outcome_proof(&lock.id, height);
+}
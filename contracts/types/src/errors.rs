@@ -1,3 +1,8 @@
+use crate::signature::PublicKey;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use utils::u128_dec_format;
+
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ParseKeyTypeError {
     #[error("unknown key type '{unknown_key_type}'")]
@@ -49,3 +54,590 @@ impl From<ParseKeyTypeError> for ParseSignatureError {
         }
     }
 }
+
+/// How a relayer should treat a call that failed with a `BridgeError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The same call may succeed later, e.g. once the light client has caught up.
+    Retryable,
+    /// The call will never succeed with these arguments.
+    Permanent,
+    /// The caller did something wrong, e.g. attached too little deposit.
+    User,
+}
+
+macro_rules! bridge_errors {
+    ($($(#[$doc:meta])* $name:ident = $code:literal, $class:ident, $message:literal;)*) => {
+        /// Failures of the bridge contracts. Contracts panic with `E<code>: <message>`, codes
+        /// are stable so off-chain services can classify failures from the panic message.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum BridgeError {
+            $($(#[$doc])* $name,)*
+        }
+
+        impl BridgeError {
+            pub const ALL: &'static [BridgeError] = &[$(BridgeError::$name,)*];
+
+            pub const fn code(self) -> u16 {
+                match self {
+                    $(BridgeError::$name => $code,)*
+                }
+            }
+
+            pub const fn message(self) -> &'static str {
+                match self {
+                    $(BridgeError::$name => $message,)*
+                }
+            }
+
+            pub const fn class(self) -> ErrorClass {
+                match self {
+                    $(BridgeError::$name => ErrorClass::$class,)*
+                }
+            }
+        }
+    };
+}
+
+bridge_errors! {
+    // contract setup and access
+    AlreadyInitialized = 100, Permanent, "Already initialized";
+    NotInitialized = 101, Retryable, "Contract is not initialized";
+    WrongInitializationStage = 102, Permanent, "Wrong initialization stage";
+    Paused = 103, Retryable, "Method is paused";
+    Unauthorized = 104, User, "Caller is not allowed to call this method";
+    InsufficientDeposit = 105, User, "Not enough attached deposit to complete action";
+    UnexpectedPromiseResults = 106, Permanent, "One and only one result was expected";
+    DeployerAlreadySet = 107, Permanent, "Deployer account should not be set";
+    LockerNotSet = 108, Permanent, "Locker account should be set";
+    LockerAlreadySet = 109, Permanent, "Locker account should not be set";
+    MetadataAlreadySet = 110, Permanent, "Metadata was already set";
+    NotSupported = 111, User, "Method is not supported";
+    InvalidRegexRule = 112, User, "Regex rule is not valid";
+    ChainDomainNotSet = 113, Permanent, "Chain ids should be set";
+    ChainDomainAlreadySet = 114, Permanent, "Chain ids should not be set";
+    InvalidChainId = 115, User, "Chain id is not valid";
+    InvalidFeeSchedule = 116, User, "Fee schedule is not valid";
+    InvalidLimit = 117, User, "Limit is not valid";
+
+    // proofs
+    InvalidProofEncoding = 200, Permanent, "Proof is not valid borsh";
+    /// The proven outcome was not executed by the connector on the other network.
+    UntrustedExecutor = 201, Permanent, "Untrusted prover account";
+    /// The proven outcome did not log the expected event.
+    UntrustedEvent = 202, Permanent, "Untrusted proof";
+    ProverFailed = 203, Retryable, "Prover failed";
+    ProofNotVerified = 204, Permanent, "Failed to verify the proof";
+    ProofExpired = 205, Permanent, "Proof expired";
+    EventReused = 206, Permanent, "Event cannot be reused for depositing.";
+    DepositTooLow = 207, User, "Deposit too low";
+    OutcomeProofInvalid = 208, Permanent, "outcome merkle proof is not valid";
+    /// A proof against a more recent light client block may still be accepted.
+    BlockProofInvalid = 209, Retryable, "block proof is not valid";
+    MerklePathInvalid = 210, Permanent, "merkle path is not valid";
+    MerkleRootUnavailable = 211, Retryable, "Merkle root promise failed";
+    /// The proven event was emitted for a different pair of chains.
+    WrongDomain = 212, Permanent, "Proof is meant for another chain";
+
+    // light client blocks
+    InitBlockWithoutNextBps = 300, Permanent, "Initialization block must contain next_bps";
+    BlockHeight = 301, Permanent, "New block must have higher height";
+    EpochId = 302, Permanent, "Epoch id of the block is not valid";
+    ApprovalsTooShort = 303, Permanent, "Approval list is too short";
+    TooFewApprovals = 304, Permanent, "Too few approvals";
+    NextBpsMissing = 305, Permanent, "Next next_bps should not be None";
+    NextBpHash = 306, Permanent, "Hash of block producers does not match";
+    SignatureStake = 307, Permanent, "Signature stake too low";
+    NoSuchSignature = 308, Permanent, "No such signature";
+    TooManyBlockProducers = 309, Permanent,
+        "It is not expected having that many block producers for the provided block";
+
+    // tokens and cross shard calls
+    /// The token is mapped once the deployment proof reaches this connector.
+    TokenNotMapped = 400, Retryable, "Token is not yet mapped";
+    DeployFailed = 401, Permanent, "Deploy bridge token failed";
+    UntrustedBurn = 402, User, "Untrusted burn";
+    CrossCallPayable = 403, User, "Cross Shard Method called can not currently be payable";
+    CrossCallGas = 404, User, "Cross Shard Method called uses too much gas";
+    InvalidResponse = 405, Permanent, "Invalid cross shard call response";
+    ClaimNotFound = 406, User, "No claimable transfer with this id";
+    /// The transfer was already acknowledged or refunded.
+    TransferNotPending = 407, Permanent, "Transfer is not pending";
+    /// The sender cancelled the transfer before it was minted.
+    TransferCancelled = 408, Permanent, "Transfer was cancelled";
+    /// Minting the transfer was already attempted on the destination, successfully or not.
+    TransferAlreadyMinted = 409, Permanent, "Transfer was already minted";
+    CancelTooEarly = 410, Retryable, "Transfer can not be cancelled yet";
+    /// The amount locked or the fee deposit of the sender is smaller than the bridge fee.
+    FeeNotCovered = 411, User, "Bridge fee is not covered";
+    InsufficientFees = 412, User, "Not enough fees to withdraw";
+    TransferLimitExceeded = 413, User, "Transfer exceeds the limit of the token";
+    QueuedTransferNotFound = 414, User, "No queued transfer with this id";
+    QueuedTransferNotReady = 415, Retryable, "Queued transfer can not be executed yet";
+    /// The burn or refund proven releases more of the token than the connector locked.
+    UnlockExceedsLocked = 416, Permanent, "Unlock exceeds the locked balance";
+    /// The burn or refund proven is of an NFT the connector does not hold.
+    TokenNotLocked = 417, Permanent, "Token is not locked";
+    /// `msg` of a transfer call is a JSON object without a valid `receiver_id` or `memo`.
+    InvalidTransferMessage = 418, User, "Transfer message is not valid";
+    /// The fee deposit of the sender is smaller than the storage the lock takes.
+    LockStorageNotCovered = 419, User, "Storage of the lock is not covered";
+}
+
+impl BridgeError {
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL.iter().copied().find(|err| err.code() == code)
+    }
+
+    /// Error a contract panicked with, found in a failure message such as
+    /// `Smart contract panicked: E206: Event cannot be reused for depositing.`.
+    pub fn from_failure(failure: &str) -> Option<Self> {
+        failure.match_indices('E').find_map(|(start, _)| {
+            let code = failure.get(start + 1..start + 4)?;
+            if !code.bytes().all(|b| b.is_ascii_digit())
+                || failure.get(start + 4..start + 6) != Some(": ")
+            {
+                return None;
+            }
+            Self::from_code(code.parse().ok()?)
+        })
+    }
+}
+
+impl std::fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "E{}: {}", self.code(), self.message())
+    }
+}
+
+pub fn panic_err(err: BridgeError) -> ! {
+    near_sdk::env::panic_str(&err.to_string())
+}
+
+/// Panics with `err` followed by `detail`, which must not be needed to classify the failure.
+pub fn panic_err_with(err: BridgeError, detail: &str) -> ! {
+    near_sdk::env::panic_str(&format!("{}: {}", err, detail))
+}
+
+/// Like `near_sdk::require!`, panicking with a `BridgeError` and an optional formatted detail.
+#[macro_export]
+macro_rules! require_err {
+    ($cond:expr, $err:expr $(,)?) => {
+        if !$cond {
+            $crate::errors::panic_err($err)
+        }
+    };
+    ($cond:expr, $err:expr, $($detail:tt)+) => {
+        if !$cond {
+            $crate::errors::panic_err_with($err, &format!($($detail)+))
+        }
+    };
+}
+
+/// Error returned in `ExecutionStatus::Failure`, taken from nearcore primitives.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum TxExecutionError {
+    /// An error happened during Action execution
+    ActionError(ActionError),
+    /// An error happened during Transaction execution
+    InvalidTxError(InvalidTxError),
+}
+
+impl From<ActionError> for TxExecutionError {
+    fn from(error: ActionError) -> Self {
+        TxExecutionError::ActionError(error)
+    }
+}
+
+impl From<InvalidTxError> for TxExecutionError {
+    fn from(error: InvalidTxError) -> Self {
+        TxExecutionError::InvalidTxError(error)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum InvalidTxError {
+    InvalidAccessKeyError(InvalidAccessKeyError),
+    InvalidSignerId {
+        signer_id: String,
+    },
+    SignerDoesNotExist {
+        signer_id: String,
+    },
+    InvalidNonce {
+        tx_nonce: u64,
+        ak_nonce: u64,
+    },
+    NonceTooLarge {
+        tx_nonce: u64,
+        upper_bound: u64,
+    },
+    InvalidReceiverId {
+        receiver_id: String,
+    },
+    InvalidSignature,
+    NotEnoughBalance {
+        signer_id: String,
+        #[serde(with = "u128_dec_format")]
+        balance: u128,
+        #[serde(with = "u128_dec_format")]
+        cost: u128,
+    },
+    LackBalanceForState {
+        signer_id: String,
+        #[serde(with = "u128_dec_format")]
+        amount: u128,
+    },
+    CostOverflow,
+    InvalidChain,
+    Expired,
+    ActionsValidation(ActionsValidationError),
+    TransactionSizeExceeded {
+        size: u64,
+        limit: u64,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum InvalidAccessKeyError {
+    AccessKeyNotFound {
+        account_id: String,
+        public_key: PublicKey,
+    },
+    ReceiverMismatch {
+        tx_receiver: String,
+        ak_receiver: String,
+    },
+    MethodNameMismatch {
+        method_name: String,
+    },
+    RequiresFullAccess,
+    NotEnoughAllowance {
+        account_id: String,
+        public_key: PublicKey,
+        #[serde(with = "u128_dec_format")]
+        allowance: u128,
+        #[serde(with = "u128_dec_format")]
+        cost: u128,
+    },
+    DepositWithFunctionCall,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ActionsValidationError {
+    DeleteActionMustBeFinal,
+    TotalPrepaidGasExceeded {
+        total_prepaid_gas: u64,
+        limit: u64,
+    },
+    TotalNumberOfActionsExceeded {
+        total_number_of_actions: u64,
+        limit: u64,
+    },
+    AddKeyMethodNamesNumberOfBytesExceeded {
+        total_number_of_bytes: u64,
+        limit: u64,
+    },
+    AddKeyMethodNameLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    IntegerOverflow,
+    InvalidAccountId {
+        account_id: String,
+    },
+    ContractSizeExceeded {
+        size: u64,
+        limit: u64,
+    },
+    FunctionCallMethodNameLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    FunctionCallArgumentsLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    UnsuitableStakingKey {
+        public_key: PublicKey,
+    },
+    FunctionCallZeroAttachedGas,
+    DelegateActionMustBeOnlyOne,
+    UnsupportedProtocolFeature {
+        protocol_feature: String,
+        version: u32,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ReceiptValidationError {
+    InvalidPredecessorId {
+        account_id: String,
+    },
+    InvalidReceiverId {
+        account_id: String,
+    },
+    InvalidSignerId {
+        account_id: String,
+    },
+    InvalidDataReceiverId {
+        account_id: String,
+    },
+    ReturnedValueLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    NumberInputDataDependenciesExceeded {
+        number_of_input_data_dependencies: u64,
+        limit: u64,
+    },
+    ActionsValidation(ActionsValidationError),
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActionError {
+    /// Index of the failed action in the transaction, `None` if the error is not related to a
+    /// single action.
+    pub index: Option<u64>,
+    pub kind: ActionErrorKind,
+}
+
+impl From<ActionErrorKind> for ActionError {
+    fn from(kind: ActionErrorKind) -> Self {
+        ActionError { index: None, kind }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ActionErrorKind {
+    AccountAlreadyExists {
+        account_id: String,
+    },
+    AccountDoesNotExist {
+        account_id: String,
+    },
+    CreateAccountOnlyByRegistrar {
+        account_id: String,
+        registrar_account_id: String,
+        predecessor_id: String,
+    },
+    CreateAccountNotAllowed {
+        account_id: String,
+        predecessor_id: String,
+    },
+    ActorNoPermission {
+        account_id: String,
+        actor_id: String,
+    },
+    DeleteKeyDoesNotExist {
+        account_id: String,
+        public_key: PublicKey,
+    },
+    AddKeyAlreadyExists {
+        account_id: String,
+        public_key: PublicKey,
+    },
+    DeleteAccountStaking {
+        account_id: String,
+    },
+    LackBalanceForState {
+        account_id: String,
+        #[serde(with = "u128_dec_format")]
+        amount: u128,
+    },
+    TriesToUnstake {
+        account_id: String,
+    },
+    TriesToStake {
+        account_id: String,
+        #[serde(with = "u128_dec_format")]
+        stake: u128,
+        #[serde(with = "u128_dec_format")]
+        locked: u128,
+        #[serde(with = "u128_dec_format")]
+        balance: u128,
+    },
+    InsufficientStake {
+        account_id: String,
+        #[serde(with = "u128_dec_format")]
+        stake: u128,
+        #[serde(with = "u128_dec_format")]
+        minimum_stake: u128,
+    },
+    FunctionCallError(FunctionCallError),
+    NewReceiptValidationError(ReceiptValidationError),
+    OnlyImplicitAccountCreationAllowed {
+        account_id: String,
+    },
+    DeleteAccountWithLargeState {
+        account_id: String,
+    },
+    DelegateActionInvalidSignature,
+    DelegateActionSenderDoesNotMatchTxReceiver {
+        sender_id: String,
+        receiver_id: String,
+    },
+    DelegateActionExpired,
+    DelegateActionAccessKeyError(InvalidAccessKeyError),
+    DelegateActionInvalidNonce {
+        delegate_nonce: u64,
+        ak_nonce: u64,
+    },
+    DelegateActionNonceTooLarge {
+        delegate_nonce: u64,
+        upper_bound: u64,
+    },
+}
+
+/// Serializable form of a contract call failure, `FunctionCallErrorSer` in nearcore.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FunctionCallError {
+    CompilationError(CompilationError),
+    LinkError {
+        msg: String,
+    },
+    MethodResolveError(MethodResolveError),
+    WasmTrap(WasmTrap),
+    WasmUnknownError,
+    HostError(HostError),
+    // Unused, kept so that ExecutionError has the same borsh index as in nearcore
+    _EVMError,
+    ExecutionError(String),
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum CompilationError {
+    CodeDoesNotExist { account_id: String },
+    PrepareError(PrepareError),
+    WasmerCompileError { msg: String },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PrepareError {
+    Serialization,
+    Deserialization,
+    InternalMemoryDeclared,
+    GasInstrumentation,
+    StackHeightInstrumentation,
+    Instantiate,
+    Memory,
+    TooManyFunctions,
+    TooManyLocals,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum MethodResolveError {
+    MethodEmptyName,
+    MethodNotFound,
+    MethodInvalidSignature,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum WasmTrap {
+    Unreachable,
+    IncorrectCallIndirectSignature,
+    MemoryOutOfBounds,
+    CallIndirectOOB,
+    IllegalArithmetic,
+    MisalignedAtomicAccess,
+    IndirectCallToNull,
+    StackOverflow,
+    GenericTrap,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum HostError {
+    BadUTF16,
+    BadUTF8,
+    GasExceeded,
+    GasLimitExceeded,
+    BalanceExceeded,
+    EmptyMethodName,
+    GuestPanic {
+        panic_msg: String,
+    },
+    IntegerOverflow,
+    InvalidPromiseIndex {
+        promise_idx: u64,
+    },
+    CannotAppendActionToJointPromise,
+    CannotReturnJointPromise,
+    InvalidPromiseResultIndex {
+        result_idx: u64,
+    },
+    InvalidRegisterId {
+        register_id: u64,
+    },
+    IteratorWasInvalidated {
+        iterator_index: u64,
+    },
+    MemoryAccessViolation,
+    InvalidReceiptIndex {
+        receipt_index: u64,
+    },
+    InvalidIteratorIndex {
+        iterator_index: u64,
+    },
+    InvalidAccountId,
+    InvalidMethodName,
+    InvalidPublicKey,
+    ProhibitedInView {
+        method_name: String,
+    },
+    NumberOfLogsExceeded {
+        limit: u64,
+    },
+    KeyLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    ValueLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    TotalLogLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    NumberPromisesExceeded {
+        number_of_promises: u64,
+        limit: u64,
+    },
+    NumberInputDataDependenciesExceeded {
+        number_of_input_data_dependencies: u64,
+        limit: u64,
+    },
+    ReturnedValueLengthExceeded {
+        length: u64,
+        limit: u64,
+    },
+    ContractSizeExceeded {
+        size: u64,
+        limit: u64,
+    },
+    Deprecated {
+        method_name: String,
+    },
+    ECRecoverError {
+        msg: String,
+    },
+    AltBn128InvalidInput {
+        msg: String,
+    },
+    Ed25519VerifyInvalidInput {
+        msg: String,
+    },
+}
@@ -1,15 +1,19 @@
 pub mod errors;
 pub mod signature;
 
+use crate::errors::TxExecutionError;
+pub use crate::errors::{BridgeError, ErrorClass};
 pub use crate::signature::{PublicKey, Signature};
+pub use utils::merkle::{
+    MerkleError, MerklePath, MerklePathItem, MERKLE_PATH_LEFT, MERKLE_PATH_RIGHT,
+};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::env;
 use near_sdk::serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Debug;
 use utils::{
-    base64_format, base_hash_format, base_hash_format_many, hashes, logging, merkle_u8_format,
-    option_base64_format, string_bytes_format_many, swap_bytes16, swap_bytes4, swap_bytes8,
+    base64_format, base_hash_format, base_hash_format_many, hashes, logging, option_base64_format,
+    option_u128_dec_format, string_bytes_format_many, swap_bytes16, swap_bytes4, swap_bytes8,
     u128_dec_format, u64_dec_format, u64_dec_format_compatible, Hash, Hashable,
 };
 
@@ -19,9 +23,7 @@ macro_rules! impl_header_hash {
         impl Hashable for $struct {
             fn hash(&self) -> Hash {
                 let inner_lite_hash_bytes: Hash =
-                    env::sha256(&self.inner_lite.try_to_vec().expect("Failed to serialize"))
-                        .try_into()
-                        .unwrap();
+                    hashes::sha256(&self.inner_lite.try_to_vec().expect("Failed to serialize"));
                 let hash = hashes::combine_hash3(
                     inner_lite_hash_bytes,
                     self.inner_rest_hash,
@@ -60,6 +62,11 @@ pub struct Block {
 
 impl_header_hash!(Block);
 
+/// Hash committed to in `next_bp_hash` of the previous epoch's blocks.
+pub fn block_producers_hash(block_producers: &[Validator]) -> Hash {
+    hashes::sha256(&block_producers.try_to_vec().expect("Failed to serialize"))
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
 #[serde(untagged)]
@@ -184,38 +191,67 @@ pub struct FullOutcomeProof {
     pub block_proof: MerklePath,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
-#[serde(crate = "near_sdk::serde")]
-pub struct ExecutionOutcomeWithIdAndProof {
-    pub proof: MerklePath,
-    #[serde(with = "base_hash_format")]
-    pub block_hash: Hash,
-    #[serde(flatten)]
-    pub outcome_with_id: ExecutionOutcomeWithId,
+impl FullOutcomeProof {
+    /// Outcome root of the block containing the outcome, as computed by the prover.
+    pub fn outcome_root(&self) -> Result<Hash, MerkleError> {
+        let chunk_outcome_root = self
+            .outcome_proof
+            .proof
+            .compute_root(&self.outcome_proof.outcome_with_id.hash())?;
+        self.outcome_root_proof
+            .compute_root(&hashes::sha256(&chunk_outcome_root))
+    }
+
+    /// Block merkle root the `block_header_lite` is proven against.
+    pub fn block_merkle_root(&self) -> Result<Hash, MerkleError> {
+        self.block_proof.compute_root(&self.block_header_lite.hash())
+    }
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Proof as accepted by the prover and connector entrypoints.
+///
+/// Callers may keep passing `FullOutcomeProof` as a JSON object, or pass its Borsh
+/// serialization wrapped in a base64 string. The Borsh layout is the field order of
+/// `FullOutcomeProof` and its members and is considered stable, contracts use it to
+/// forward proofs between each other.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
-#[serde(transparent)]
-pub struct MerklePath {
-    #[serde(flatten)]
-    pub items: Vec<MerklePathItem>,
+#[serde(untagged)]
+pub enum EncodedOutcomeProof {
+    Borsh(#[serde(with = "base64_format")] Vec<u8>),
+    Json(FullOutcomeProof),
 }
 
-pub const MERKLE_PATH_LEFT: u8 = 0;
-pub const MERKLE_PATH_RIGHT: u8 = 1;
+impl EncodedOutcomeProof {
+    /// Borsh encodes the proof, used when forwarding it to other contracts.
+    pub fn borsh(proof: &FullOutcomeProof) -> Self {
+        Self::Borsh(proof.try_to_vec().expect("Failed to serialize"))
+    }
+
+    pub fn decode(self) -> std::io::Result<FullOutcomeProof> {
+        match self {
+            Self::Borsh(bytes) => FullOutcomeProof::try_from_slice(&bytes),
+            Self::Json(proof) => Ok(proof),
+        }
+    }
+}
+
+impl From<FullOutcomeProof> for EncodedOutcomeProof {
+    fn from(proof: FullOutcomeProof) -> Self {
+        Self::Json(proof)
+    }
+}
 
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
-pub struct MerklePathItem {
+pub struct ExecutionOutcomeWithIdAndProof {
+    pub proof: MerklePath,
     #[serde(with = "base_hash_format")]
-    pub hash: Hash,
-    #[serde(with = "merkle_u8_format")]
-    pub direction: u8, // 0 = left, 1 = right
+    pub block_hash: Hash,
+    #[serde(flatten)]
+    pub outcome_with_id: ExecutionOutcomeWithId,
 }
 
-impl_self_hash!(MerklePathItem);
-
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
 pub struct BlockHeaderLight {
@@ -247,7 +283,7 @@ impl Hashable for ExecutionOutcomeWithId {
             bytes.extend(&hash);
         }
 
-        env::sha256(&bytes).try_into().unwrap()
+        hashes::sha256(&bytes)
     }
 }
 
@@ -277,10 +313,14 @@ impl ExecutionOutcome {
         bytes.extend(&swap_bytes8(self.gas_burnt).to_be_bytes());
         bytes.extend(&swap_bytes16(self.tokens_burnt).to_be_bytes());
         bytes.extend(&self.executor_id.try_to_vec().expect("Failed to serialize"));
-        bytes.extend(&self.status.try_to_vec().expect("Failed to serialize"));
-        let mut res: Vec<Hash> = vec![env::sha256(&bytes).try_into().unwrap()];
+        bytes.extend(
+            &PartialExecutionStatus::from(&self.status)
+                .try_to_vec()
+                .expect("Failed to serialize"),
+        );
+        let mut res: Vec<Hash> = vec![hashes::sha256(&bytes)];
         for log in &self.logs {
-            res.push(env::sha256(log).try_into().unwrap());
+            res.push(hashes::sha256(log));
         }
 
         res
@@ -290,14 +330,36 @@ impl ExecutionOutcome {
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
 pub enum ExecutionStatus {
-    Unknown(),
-    Failed(),
+    Unknown,
+    Failure(TxExecutionError),
     #[serde(with = "base64_format")]
     SuccessValue(Vec<u8>),
     #[serde(with = "base_hash_format")]
     SuccessReceiptId(Hash),
 }
 
+/// ExecutionStatus as it is merkelized, the failure details are not part of the outcome hash.
+#[derive(BorshSerialize, PartialEq, Eq, Clone, Debug)]
+pub enum PartialExecutionStatus {
+    Unknown,
+    Failure,
+    SuccessValue(Vec<u8>),
+    SuccessReceiptId(Hash),
+}
+
+impl From<&ExecutionStatus> for PartialExecutionStatus {
+    fn from(status: &ExecutionStatus) -> Self {
+        match status {
+            ExecutionStatus::Unknown => PartialExecutionStatus::Unknown,
+            ExecutionStatus::Failure(_) => PartialExecutionStatus::Failure,
+            ExecutionStatus::SuccessValue(value) => {
+                PartialExecutionStatus::SuccessValue(value.clone())
+            }
+            ExecutionStatus::SuccessReceiptId(id) => PartialExecutionStatus::SuccessReceiptId(*id),
+        }
+    }
+}
+
 /// Taken from nearcore primitives-core
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 #[serde(crate = "near_sdk::serde")]
@@ -310,11 +372,11 @@ pub enum Action {
     DeployContract(DeployContractAction),
     FunctionCall(FunctionCallAction),
     Transfer(TransferAction),
-    // Calimero will not deal with key and stake Actions
-    //Stake(StakeAction),
-    //AddKey(AddKeyAction),
-    //DeleteKey(DeleteKeyAction),
-    //DeleteAccount(DeleteAccountAction),
+    Stake(StakeAction),
+    AddKey(AddKeyAction),
+    DeleteKey(DeleteKeyAction),
+    DeleteAccount(DeleteAccountAction),
+    Delegate(SignedDelegateAction),
 }
 
 impl Action {
@@ -415,6 +477,171 @@ impl From<TransferAction> for Action {
     }
 }
 
+/// An action which stakes signer_id tokens and sets up the validator public key
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeAction {
+    /// Amount of tokens to stake.
+    #[serde(with = "u128_dec_format")]
+    pub stake: u128,
+    /// Validator key which will be used to sign transactions on behalf of signer_id
+    pub public_key: PublicKey,
+}
+
+impl From<StakeAction> for Action {
+    fn from(stake_action: StakeAction) -> Self {
+        Self::Stake(stake_action)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AddKeyAction {
+    /// A public key which will be associated with an access_key
+    pub public_key: PublicKey,
+    /// An access key with the permission
+    pub access_key: AccessKey,
+}
+
+impl From<AddKeyAction> for Action {
+    fn from(add_key_action: AddKeyAction) -> Self {
+        Self::AddKey(add_key_action)
+    }
+}
+
+/// Access key, taken from nearcore primitives-core
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccessKey {
+    /// Nonce for this access key, used for tx nonce generation.
+    pub nonce: u64,
+    /// Defines permissions for this access key.
+    pub permission: AccessKeyPermission,
+}
+
+impl AccessKey {
+    pub fn full_access() -> Self {
+        Self {
+            nonce: 0,
+            permission: AccessKeyPermission::FullAccess,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AccessKeyPermission {
+    FunctionCall(FunctionCallPermission),
+    /// Grants full access to the account.
+    FullAccess,
+}
+
+/// Grants limited permission to make transactions with FunctionCallActions
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FunctionCallPermission {
+    /// Balance limit for gas and transaction fees, `None` means unlimited allowance.
+    #[serde(with = "option_u128_dec_format")]
+    pub allowance: Option<u128>,
+    /// The access key only allows transactions with the given receiver's account id.
+    pub receiver_id: String,
+    /// Allowed method names, empty list means any method can be called.
+    pub method_names: Vec<String>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeleteKeyAction {
+    /// A public key associated with the access_key to be deleted.
+    pub public_key: PublicKey,
+}
+
+impl From<DeleteKeyAction> for Action {
+    fn from(delete_key_action: DeleteKeyAction) -> Self {
+        Self::DeleteKey(delete_key_action)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeleteAccountAction {
+    pub beneficiary_id: String,
+}
+
+impl From<DeleteAccountAction> for Action {
+    fn from(delete_account_action: DeleteAccountAction) -> Self {
+        Self::DeleteAccount(delete_account_action)
+    }
+}
+
+/// Borsh index of `Action::Delegate`
+const ACTION_DELEGATE_NUMBER: u8 = 8;
+
+/// Meta transaction action (NEP-366), the relayer pays for `actions` sent on behalf of `sender_id`.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegateAction {
+    /// Signer of the delegated actions
+    pub sender_id: String,
+    /// Receiver of the delegated actions.
+    pub receiver_id: String,
+    /// List of actions to be executed, nested delegate actions are not allowed.
+    pub actions: Vec<NonDelegateAction>,
+    /// Nonce to ensure that the same delegate action is not sent twice by a relayer
+    pub nonce: u64,
+    /// The maximal height of the block in the blockchain below which the given DelegateAction is valid.
+    pub max_block_height: u64,
+    /// Public key used to sign this delegated action.
+    pub public_key: PublicKey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedDelegateAction {
+    pub delegate_action: DelegateAction,
+    pub signature: Signature,
+}
+
+impl From<SignedDelegateAction> for Action {
+    fn from(delegate_action: SignedDelegateAction) -> Self {
+        Self::Delegate(delegate_action)
+    }
+}
+
+/// Any action except `Action::Delegate`, the borsh deserializer rejects nested delegate actions.
+#[derive(BorshSerialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NonDelegateAction(Action);
+
+impl From<NonDelegateAction> for Action {
+    fn from(action: NonDelegateAction) -> Self {
+        action.0
+    }
+}
+
+impl TryFrom<Action> for NonDelegateAction {
+    type Error = Action;
+
+    fn try_from(action: Action) -> Result<Self, Action> {
+        match action {
+            Action::Delegate(_) => Err(action),
+            action => Ok(Self(action)),
+        }
+    }
+}
+
+impl BorshDeserialize for NonDelegateAction {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        if buf.first() == Some(&ACTION_DELEGATE_NUMBER) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "DelegateAction mustn't contain a nested one",
+            ));
+        }
+        <Action as BorshDeserialize>::deserialize(buf).map(Self)
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Transaction {
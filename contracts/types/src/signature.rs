@@ -518,6 +518,12 @@ impl FromStr for SecretKey {
                         received_length: length,
                     });
                 }
+                // `sign` relies on the secret and public halves forming a keypair
+                ed25519_dalek::Keypair::from_bytes(&array).map_err(|err| {
+                    Self::Err::InvalidData {
+                        error_message: err.to_string(),
+                    }
+                })?;
                 Ok(Self::ED25519(ED25519SecretKey(array)))
             }
             KeyType::SECP256K1 => {
@@ -615,7 +621,11 @@ impl Secp256K1Signature {
         &self,
         msg: [u8; 32],
     ) -> Result<Secp256K1PublicKey, crate::errors::ParseSignatureError> {
-        let rec_id = libsecp256k1::RecoveryId::parse(self.0[64]).unwrap();
+        let rec_id = libsecp256k1::RecoveryId::parse(self.0[64]).map_err(|err| {
+            crate::errors::ParseSignatureError::InvalidData {
+                error_message: err.to_string(),
+            }
+        })?;
         let signature = libsecp256k1::Signature::parse_standard(&self.0[0..64].try_into().unwrap())
             .map_err(|err| crate::errors::ParseSignatureError::InvalidData {
                 error_message: err.to_string(),
@@ -744,21 +754,34 @@ impl Signature {
                 }
             }
             (Signature::SECP256K1(signature), PublicKey::SECP256K1(public_key)) => {
-                let _rec_id = libsecp256k1::RecoveryId::parse(signature.0[64]).unwrap();
-                let sig =
-                    libsecp256k1::Signature::parse_standard(&signature.0[0..64].try_into().unwrap())
-                        .unwrap();
+                // only 32 byte message hashes can be signed with secp256k1
+                let message: &[u8; 32] = match data.try_into() {
+                    Ok(message) => message,
+                    Err(_) => return false,
+                };
+                if libsecp256k1::RecoveryId::parse(signature.0[64]).is_err() {
+                    return false;
+                }
+                let sig = match libsecp256k1::Signature::parse_standard(
+                    &signature.0[0..64].try_into().unwrap(),
+                ) {
+                    Ok(sig) => sig,
+                    Err(_) => return false,
+                };
                 let pdata: [u8; 65] = {
                     // code borrowed from https://github.com/openethereum/openethereum/blob/98b7c07171cd320f32877dfa5aa528f585dc9a72/ethkey/src/signature.rs#L210
                     let mut temp = [4u8; 65];
                     temp[1..65].copy_from_slice(&public_key.0);
                     temp
                 };
-                libsecp256k1::verify(
-                    &libsecp256k1::Message::parse(data.try_into().unwrap()),
-                    &sig,
-                    &libsecp256k1::PublicKey::parse(&pdata).unwrap(),
-                )
+                match libsecp256k1::PublicKey::parse(&pdata) {
+                    Ok(public_key) => libsecp256k1::verify(
+                        &libsecp256k1::Message::parse(message),
+                        &sig,
+                        &public_key,
+                    ),
+                    Err(_) => false,
+                }
             }
             _ => false,
         }
@@ -778,7 +801,9 @@ impl Signature {
                 ed25519_dalek::Signature::from_bytes(&[0u8; ed25519_dalek::SIGNATURE_LENGTH])
                     .unwrap(),
             ),
-            _ => unimplemented!(),
+            KeyType::SECP256K1 => {
+                Signature::SECP256K1(Secp256K1Signature([0; SECP256K1_SIGNATURE_LENGTH]))
+            }
         }
     }
 }
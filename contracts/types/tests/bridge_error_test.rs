@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod bridge_error {
+    mod test {
+        use std::collections::HashSet;
+        use types::{BridgeError, ErrorClass};
+
+        #[test]
+        fn codes_are_unique() {
+            let codes: HashSet<u16> = BridgeError::ALL.iter().map(|err| err.code()).collect();
+            assert_eq!(codes.len(), BridgeError::ALL.len());
+            for err in BridgeError::ALL {
+                assert_eq!(BridgeError::from_code(err.code()), Some(*err));
+            }
+        }
+
+        #[test]
+        fn codes_are_stable() {
+            assert_eq!(BridgeError::AlreadyInitialized.code(), 100);
+            assert_eq!(BridgeError::Paused.code(), 103);
+            assert_eq!(BridgeError::EventReused.code(), 206);
+            assert_eq!(BridgeError::BlockProofInvalid.code(), 209);
+            assert_eq!(BridgeError::SignatureStake.code(), 307);
+            assert_eq!(BridgeError::TokenNotMapped.code(), 400);
+        }
+
+        #[test]
+        fn display() {
+            assert_eq!(
+                BridgeError::EventReused.to_string(),
+                "E206: Event cannot be reused for depositing."
+            );
+        }
+
+        #[test]
+        fn from_failure() {
+            let failure = r#"{"ActionError":{"index":0,"kind":{"FunctionCallError":{"ExecutionError":"Smart contract panicked: E202: Untrusted proof: lock receipt proof required: outcome has no logs"}}}}"#;
+            assert_eq!(BridgeError::from_failure(failure), Some(BridgeError::UntrustedEvent));
+            assert_eq!(
+                BridgeError::from_failure(&BridgeError::DepositTooLow.to_string()),
+                Some(BridgeError::DepositTooLow)
+            );
+            assert_eq!(BridgeError::from_failure("Exceeded the prepaid gas."), None);
+            assert_eq!(BridgeError::from_failure("E999: unknown"), None);
+            assert_eq!(BridgeError::from_failure("E20"), None);
+        }
+
+        #[test]
+        fn classes() {
+            assert_eq!(BridgeError::ProverFailed.class(), ErrorClass::Retryable);
+            assert_eq!(BridgeError::EventReused.class(), ErrorClass::Permanent);
+            assert_eq!(BridgeError::DepositTooLow.class(), ErrorClass::User);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod execution_status {
+    mod test {
+        use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+        use near_sdk::{env, serde_json};
+        use types::{
+            Action, ExecutionOutcome, ExecutionOutcomeWithId, ExecutionStatus, NonDelegateAction,
+        };
+        use utils::hashes::{decode_hex, encode_hex};
+        use utils::Hashable;
+
+        // Expected values are produced with nearcore's near-primitives 0.17
+        const ACTIONS: &str = include_str!("actions.json");
+        const ACTIONS_BORSH: &str = concat!(
+            "0800000000030500000000000000000000000000000005000f56a5f028dfc089ec7c39c1183b321b",
+            "4d8f89ba5bec9e1762803cc2491f6ef807000000000000000001000040683bb3f386f03400000000",
+            "00000b0000006272696467652e6e65617202000000040000006c6f636b06000000756e6c6f636b05",
+            "000f56a5f028dfc089ec7c39c1183b321b4d8f89ba5bec9e1762803cc2491f6ef800000000000000",
+            "000106000f56a5f028dfc089ec7c39c1183b321b4d8f89ba5bec9e1762803cc2491f6ef804640000",
+            "00000000000000000000000000000f56a5f028dfc089ec7c39c1183b321b4d8f89ba5bec9e176280",
+            "3cc2491f6ef80708000000626f622e6e656172080a000000616c6963652e6e6561720b0000006272",
+            "696467652e6e6561720700000000030500000000000000000000000000000005000f56a5f028dfc0",
+            "89ec7c39c1183b321b4d8f89ba5bec9e1762803cc2491f6ef807000000000000000001000040683b",
+            "b3f386f0340000000000000b0000006272696467652e6e65617202000000040000006c6f636b0600",
+            "0000756e6c6f636b05000f56a5f028dfc089ec7c39c1183b321b4d8f89ba5bec9e1762803cc2491f",
+            "6ef800000000000000000106000f56a5f028dfc089ec7c39c1183b321b4d8f89ba5bec9e1762803c",
+            "c2491f6ef80464000000000000000000000000000000000f56a5f028dfc089ec7c39c1183b321b4d",
+            "8f89ba5bec9e1762803cc2491f6ef80708000000626f622e6e6561720300000000000000e8030000",
+            "00000000000f56a5f028dfc089ec7c39c1183b321b4d8f89ba5bec9e1762803cc2491f6ef800d6e2",
+            "30047e1ae9b21ff20457e87e50439fcc1ae1115a2d1c6e4ce821d6574c1d6d10dbc66e2a52478892",
+            "388c2cfa9f3122c66c7ca76e08640a6089ce588aec0e",
+        );
+
+        fn outcome(status: ExecutionStatus) -> ExecutionOutcomeWithId {
+            ExecutionOutcomeWithId {
+                id: env::sha256(b"receipt").try_into().unwrap(),
+                outcome: ExecutionOutcome {
+                    logs: vec![b"CALIMERO_EVENT_LOCK_FT:foo".to_vec()],
+                    receipt_ids: vec![env::sha256(b"child").try_into().unwrap()],
+                    gas_burnt: 2428395018008,
+                    tokens_burnt: 242839501800800000000,
+                    executor_id: "alice.near".to_string(),
+                    status,
+                },
+            }
+        }
+
+        fn check_status(json: &str, borsh: &str, outcome_hash: &str) {
+            let status: ExecutionStatus = serde_json::from_str(json).unwrap();
+            assert_eq!(encode_hex(&status.try_to_vec().unwrap()), borsh);
+            assert_eq!(
+                ExecutionStatus::try_from_slice(&decode_hex(borsh)).unwrap(),
+                status
+            );
+            assert_eq!(
+                serde_json::to_value(&status).unwrap(),
+                serde_json::from_str::<serde_json::Value>(json).unwrap()
+            );
+            assert_eq!(encode_hex(&outcome(status).hash()), outcome_hash);
+        }
+
+        #[test]
+        fn function_call_failure() {
+            check_status(
+                r#"{"Failure":{"ActionError":{"index":0,"kind":{"FunctionCallError":{"ExecutionError":"Smart contract panicked: Token is paused"}}}}}"#,
+                concat!(
+                    "01000100000000000000000c0728000000536d61727420636f6e74726163742070616e69636b6564",
+                    "3a20546f6b656e20697320706175736564",
+                ),
+                "499fbe28566425fbaf86279c55b3ea7d3a316bd30b2d8aaa540c4d8895b815c7",
+            );
+        }
+
+        #[test]
+        fn invalid_tx_failure() {
+            check_status(
+                r#"{"Failure":{"InvalidTxError":{"NotEnoughBalance":{"signer_id":"alice.near","balance":"1","cost":"1000000000000000000000"}}}}"#,
+                concat!(
+                    "0101070a000000616c6963652e6e656172010000000000000000000000000000000000a0dec5adc9",
+                    "353600000000000000",
+                ),
+                "499fbe28566425fbaf86279c55b3ea7d3a316bd30b2d8aaa540c4d8895b815c7",
+            );
+        }
+
+        #[test]
+        fn delegate_access_key_failure() {
+            check_status(
+                r#"{"Failure":{"ActionError":{"index":null,"kind":{"DelegateActionAccessKeyError":{"AccessKeyNotFound":{"account_id":"alice.near","public_key":"ed25519:22skMptHjFWNyuEWY22ftn2AbLPSYpmYwGJRGwpNHbTV"}}}}}}"#,
+                concat!(
+                    "01000013000a000000616c6963652e6e656172000f56a5f028dfc089ec7c39c1183b321b4d8f89ba",
+                    "5bec9e1762803cc2491f6ef8",
+                ),
+                "499fbe28566425fbaf86279c55b3ea7d3a316bd30b2d8aaa540c4d8895b815c7",
+            );
+        }
+
+        #[test]
+        fn unknown() {
+            check_status(
+                r#""Unknown""#,
+                "00",
+                "f4dca269575453180ce09c303839931030e97a957caed4ed5b5cb74c890944ca",
+            );
+        }
+
+        #[test]
+        fn failure_details_are_not_merkelized() {
+            let failure = r#"{"Failure":{"ActionError":{"index":1,"kind":{"FunctionCallError":{"HostError":{"GuestPanic":{"panic_msg":"other"}}}}}}}"#;
+            let status: ExecutionStatus = serde_json::from_str(failure).unwrap();
+            assert_eq!(
+                encode_hex(&outcome(status).hash()),
+                "499fbe28566425fbaf86279c55b3ea7d3a316bd30b2d8aaa540c4d8895b815c7"
+            );
+        }
+
+        #[test]
+        fn actions_match_nearcore() {
+            let actions: Vec<Action> = serde_json::from_str(ACTIONS).unwrap();
+            assert_eq!(actions.len(), 8);
+            assert!(matches!(actions[7], Action::Delegate(_)));
+            assert_eq!(encode_hex(&actions.try_to_vec().unwrap()), ACTIONS_BORSH);
+            assert_eq!(
+                Vec::<Action>::try_from_slice(&decode_hex(ACTIONS_BORSH)).unwrap(),
+                actions
+            );
+            assert_eq!(
+                serde_json::to_value(&actions).unwrap(),
+                serde_json::from_str::<serde_json::Value>(ACTIONS).unwrap()
+            );
+        }
+
+        #[test]
+        fn nested_delegate_action_is_rejected() {
+            let actions: Vec<Action> = serde_json::from_str(ACTIONS).unwrap();
+            let delegate = actions.last().unwrap().clone();
+            assert!(NonDelegateAction::try_from(delegate.clone()).is_err());
+
+            let bytes = delegate.try_to_vec().unwrap();
+            assert!(NonDelegateAction::try_from_slice(&bytes).is_err());
+            assert!(Action::try_from_slice(&bytes).is_ok());
+        }
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod parsing {
+    mod test {
+        use near_sdk::borsh::BorshDeserialize;
+        use near_sdk::serde_json;
+        use proptest::prelude::*;
+        use types::signature::{ED25519SecretKey, KeyType, PublicKey, SecretKey, Signature};
+        use types::{EncodedOutcomeProof, FullOutcomeProof};
+
+        const LOCK_PROOF: &str =
+            include_str!("../../ft_connector/tests/destination_test_assets/lock_proof.json");
+
+        fn ed25519_secret_key(seed: [u8; 32]) -> SecretKey {
+            let secret = ed25519_dalek::SecretKey::from_bytes(&seed).unwrap();
+            let public = ed25519_dalek::PublicKey::from(&secret);
+            let keypair = ed25519_dalek::Keypair { secret, public };
+            SecretKey::ED25519(ED25519SecretKey(keypair.to_bytes()))
+        }
+
+        #[test]
+        fn mismatched_ed25519_keypair_is_rejected() {
+            let mut bytes = match ed25519_secret_key([7; 32]) {
+                SecretKey::ED25519(key) => key.0,
+                _ => unreachable!(),
+            };
+            bytes[40] ^= 1;
+            let encoded = format!("ed25519:{}", near_sdk::bs58::encode(&bytes[..]).into_string());
+            assert!(encoded.parse::<SecretKey>().is_err());
+        }
+
+        #[test]
+        fn secp256k1_verify_rejects_malformed_input() {
+            let signature = Signature::empty(KeyType::SECP256K1);
+            let public_key = PublicKey::empty(KeyType::SECP256K1);
+            assert!(!signature.verify(&[0; 32], &public_key));
+            // secp256k1 messages must be 32 byte hashes
+            assert!(!signature.verify(b"123", &public_key));
+        }
+
+        #[test]
+        fn hash_of_wrong_length_is_rejected() {
+            let mut proof: serde_json::Value = serde_json::from_str(LOCK_PROOF).unwrap();
+            proof["outcome_proof"]["block_hash"] = "1111".into();
+            assert!(serde_json::from_value::<FullOutcomeProof>(proof).is_err());
+        }
+
+        proptest! {
+            #[test]
+            fn key_parsing_never_panics(s in "\\PC*") {
+                let _ = s.parse::<PublicKey>();
+                let _ = s.parse::<SecretKey>();
+                let _ = s.parse::<Signature>();
+            }
+
+            #[test]
+            fn prefixed_key_parsing_never_panics(
+                key_type in prop_oneof![Just("ed25519:"), Just("secp256k1:")],
+                data in proptest::collection::vec(any::<u8>(), 0..100),
+            ) {
+                let s = format!("{}{}", key_type, near_sdk::bs58::encode(data).into_string());
+                let _ = s.parse::<PublicKey>();
+                let _ = s.parse::<SecretKey>();
+                let _ = s.parse::<Signature>();
+            }
+
+            #[test]
+            fn ed25519_keys_round_trip(seed in any::<[u8; 32]>(), data in proptest::collection::vec(any::<u8>(), 0..64)) {
+                let secret_key = ed25519_secret_key(seed);
+                let public_key = secret_key.public_key();
+                prop_assert_eq!(secret_key.to_string().parse::<SecretKey>().unwrap(), secret_key.clone());
+                prop_assert_eq!(public_key.to_string().parse::<PublicKey>().unwrap(), public_key.clone());
+
+                let signature = secret_key.sign(&data);
+                prop_assert_eq!(signature.to_string().parse::<Signature>().unwrap(), signature.clone());
+                prop_assert!(signature.verify(&data, &public_key));
+            }
+
+            #[test]
+            fn verify_never_panics(
+                signature in proptest::collection::vec(any::<u8>(), 65),
+                public_key in proptest::collection::vec(any::<u8>(), 64),
+                data in proptest::collection::vec(any::<u8>(), 0..64),
+            ) {
+                let public_key = PublicKey::try_from_slice(
+                    &[&[KeyType::SECP256K1 as u8][..], &public_key[..]].concat(),
+                ).unwrap();
+                if let Ok(signature) = Signature::from_parts(KeyType::SECP256K1, &signature) {
+                    let _ = signature.verify(&data, &public_key);
+                }
+                if let Ok(signature) = Signature::from_parts(KeyType::ED25519, &signature[..64]) {
+                    let _ = signature.verify(&data, &PublicKey::empty(KeyType::ED25519));
+                }
+            }
+
+            #[test]
+            fn proof_decoding_never_panics(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+                let _ = EncodedOutcomeProof::Borsh(bytes).decode();
+            }
+
+            #[test]
+            fn proof_json_never_panics(json in "\\PC*") {
+                let _ = serde_json::from_str::<EncodedOutcomeProof>(&json);
+            }
+
+            #[test]
+            fn truncated_proof_is_rejected(len in 0..LOCK_PROOF.trim_end().len()) {
+                prop_assert!(serde_json::from_str::<FullOutcomeProof>(&LOCK_PROOF[..len]).is_err());
+            }
+        }
+    }
+}
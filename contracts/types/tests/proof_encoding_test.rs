@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod proof_encoding {
+    mod test {
+        use near_sdk::borsh::BorshSerialize;
+        use near_sdk::serde_json;
+        use types::{EncodedOutcomeProof, FullOutcomeProof};
+
+        const LOCK_PROOF: &str =
+            include_str!("../../ft_connector/tests/destination_test_assets/lock_proof.json");
+
+        fn lock_proof() -> FullOutcomeProof {
+            serde_json::from_str(LOCK_PROOF).unwrap()
+        }
+
+        #[test]
+        fn json_proof_is_accepted() {
+            let encoded: EncodedOutcomeProof = serde_json::from_str(LOCK_PROOF).unwrap();
+            assert!(matches!(encoded, EncodedOutcomeProof::Json(_)));
+            assert_eq!(encoded.decode().unwrap(), lock_proof());
+        }
+
+        #[test]
+        fn borsh_proof_round_trip() {
+            let proof = lock_proof();
+            let encoded = EncodedOutcomeProof::borsh(&proof);
+            let json = serde_json::to_string(&encoded).unwrap();
+            assert!(json.starts_with('"'));
+
+            let decoded: EncodedOutcomeProof = serde_json::from_str(&json).unwrap();
+            assert!(matches!(decoded, EncodedOutcomeProof::Borsh(_)));
+            assert_eq!(decoded.decode().unwrap(), proof);
+        }
+
+        #[test]
+        fn borsh_proof_is_smaller() {
+            let proof = lock_proof();
+            let json = serde_json::to_vec(&proof).unwrap();
+            let borsh = serde_json::to_vec(&EncodedOutcomeProof::borsh(&proof)).unwrap();
+            assert!(borsh.len() < json.len());
+        }
+
+        #[test]
+        fn borsh_layout_is_stable() {
+            let proof = lock_proof();
+            let bytes = proof.try_to_vec().unwrap();
+            // outcome_proof.proof length prefix followed by its first item
+            assert_eq!(&bytes[..4], &2u32.to_le_bytes());
+            assert_eq!(&bytes[4..36], &proof.outcome_proof.proof.items[0].hash);
+            assert_eq!(bytes[36], proof.outcome_proof.proof.items[0].direction);
+            // block_proof is serialized last
+            let block_proof = proof.block_proof.try_to_vec().unwrap();
+            assert!(bytes.ends_with(&block_proof));
+        }
+
+        #[test]
+        fn malformed_borsh_is_rejected() {
+            let encoded: EncodedOutcomeProof = serde_json::from_str("\"AAAA\"").unwrap();
+            assert!(encoded.decode().is_err());
+        }
+    }
+}
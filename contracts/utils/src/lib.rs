@@ -1,3 +1,5 @@
+pub mod merkle;
+
 pub type Hash = [u8; 32];
 
 pub trait Hashable {
@@ -70,6 +72,30 @@ pub mod u128_dec_format_compatible {
     }
 }
 
+pub mod option_u128_dec_format {
+    use near_sdk::serde::de;
+    use near_sdk::serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(num: &Option<u128>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match num {
+            Some(num) => serializer.serialize_str(&format!("{}", num)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u128>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: Option<String> = Option::deserialize(deserializer)?;
+        s.map(|s| s.parse::<u128>().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
 pub mod u64_dec_format {
     use near_sdk::serde::de;
     use near_sdk::serde::{Deserialize, Deserializer, Serializer};
@@ -116,6 +142,7 @@ pub mod u64_dec_format_compatible {
 }
 
 pub mod merkle_u8_format {
+    use near_sdk::serde::de;
     use near_sdk::serde::{Deserialize, Deserializer, Serializer};
 
     pub fn serialize<S>(data: &u8, serializer: S) -> Result<S::Ok, S::Error>
@@ -136,10 +163,10 @@ pub mod merkle_u8_format {
     {
         let s = String::deserialize(deserializer)?;
 
-        if s == "Left" {
-            Ok(0)
-        } else {
-            Ok(1)
+        match s.as_str() {
+            "Left" => Ok(0),
+            "Right" => Ok(1),
+            _ => Err(de::Error::unknown_variant(&s, &["Left", "Right"])),
         }
     }
 }
@@ -291,10 +318,18 @@ pub mod base_hash_format {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
+        decode(&s).map_err(de::Error::custom)
+    }
+
+    /// Decodes a base58 hash, which must be exactly 32 bytes long.
+    pub(crate) fn decode(s: &str) -> Result<Hash, String> {
         let mut array = [0; 32];
-        let _length = near_sdk::bs58::decode(s)
+        let length = near_sdk::bs58::decode(s)
             .into(&mut array[..])
-            .map_err(|err| de::Error::custom(err.to_string()))?;
+            .map_err(|err| err.to_string())?;
+        if length != array.len() {
+            return Err(format!("expected a hash of 32 bytes, found {} bytes", length));
+        }
         Ok(array)
     }
 }
@@ -326,11 +361,7 @@ pub mod base_hash_format_many {
         
         let mut rec: Vec<Hash> = vec![];
         for s in vec {
-            let mut array = [0; 32];
-            let _length = near_sdk::bs58::decode(s)
-                .into(&mut array[..])
-                .map_err(|err| de::Error::custom(err.to_string()))?;
-            rec.push(array);
+            rec.push(super::base_hash_format::decode(&s).map_err(de::Error::custom)?);
         }
         Ok(rec)
     }
@@ -359,7 +390,7 @@ pub mod base_bytes_format {
 }
 
 pub mod string_bytes_format_many {
-    use near_sdk::serde::{Deserialize, Deserializer, Serializer};
+    use near_sdk::serde::{ser, Deserialize, Deserializer, Serializer};
     use near_sdk::serde::ser::SerializeSeq;
 
     pub fn serialize<S>(data: &Vec<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
@@ -368,7 +399,7 @@ pub mod string_bytes_format_many {
     {
         let mut seq = serializer.serialize_seq(Some(data.len()))?;
         for e in data {
-            seq.serialize_element(std::str::from_utf8(e).unwrap())?;
+            seq.serialize_element(std::str::from_utf8(e).map_err(ser::Error::custom)?)?;
         }
         seq.end()
     }
@@ -399,15 +430,54 @@ pub mod hashes {
             return None;
         }
         match bs58::decode(&s).into_vec() {
-            Ok(x) => Some(x.try_into().unwrap()),
+            Ok(x) => x.try_into().ok(),
             _ => None,
         }
     }
 
-    pub fn combine_hash2(x: Hash, y: Hash) -> Hash {
-        let combined: Vec<u8> = x.iter().copied().chain(y.iter().copied()).collect();
+    /// Source of sha256 for all hashing done by `utils` and `types`.
+    pub trait Hasher {
+        fn sha256(data: &[u8]) -> Hash;
+
+        fn combine_hash2(x: Hash, y: Hash) -> Hash {
+            let mut combined = [0u8; 64];
+            combined[..32].copy_from_slice(&x);
+            combined[32..].copy_from_slice(&y);
+            Self::sha256(&combined)
+        }
+    }
+
+    /// Uses the `sha256` host function, only usable inside the NEAR runtime or a mocked one.
+    pub struct NearHasher;
+
+    impl Hasher for NearHasher {
+        fn sha256(data: &[u8]) -> Hash {
+            env::sha256(data).try_into().unwrap()
+        }
+    }
+
+    /// Pure Rust sha256 for relayers, indexers and other off-chain code.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub struct Sha256Hasher;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    impl Hasher for Sha256Hasher {
+        fn sha256(data: &[u8]) -> Hash {
+            use sha2::Digest;
+            sha2::Sha256::digest(data).into()
+        }
+    }
 
-        env::sha256(&combined).try_into().unwrap()
+    #[cfg(target_arch = "wasm32")]
+    pub type DefaultHasher = NearHasher;
+    #[cfg(not(target_arch = "wasm32"))]
+    pub type DefaultHasher = Sha256Hasher;
+
+    pub fn sha256(data: &[u8]) -> Hash {
+        DefaultHasher::sha256(data)
+    }
+    pub fn combine_hash2(x: Hash, y: Hash) -> Hash {
+        DefaultHasher::combine_hash2(x, y)
     }
     pub fn combine_hash3(x: Hash, y: Hash, z: Hash) -> Hash {
         combine_hash2(combine_hash2(x, y), z)
@@ -430,3 +500,38 @@ pub mod hashes {
             .collect()
     }
 }
+
+/// Longest account id NEAR accepts.
+pub const MAX_ACCOUNT_ID_LENGTH: usize = 64;
+
+/// Account of the bridged token `BridgeTokenDeployer` at `deployer_account_id` creates for
+/// `source_address`.
+///
+/// The source master account suffix is stripped and the remaining dots replaced, so
+/// `wrap.testnet` becomes `wrap.<deployer>`. Names longer than an account id can be are
+/// replaced by a prefix of their hex encoded sha256.
+pub fn bridge_token_account_name(
+    source_address: &str,
+    source_master_account: &str,
+    deployer_account_id: &str,
+) -> String {
+    let name = format!(
+        "{}.{}",
+        source_address
+            .strip_suffix(&format!(".{}", source_master_account))
+            .unwrap_or(source_address)
+            .replace('.', "_"),
+        deployer_account_id
+    );
+    if name.len() <= MAX_ACCOUNT_ID_LENGTH {
+        return name;
+    }
+
+    let suffix_length = deployer_account_id.len() + 1;
+    let name_sha = hashes::encode_hex(&hashes::sha256(name.as_bytes()));
+    format!(
+        "{}.{}",
+        &name_sha[..MAX_ACCOUNT_ID_LENGTH - suffix_length],
+        deployer_account_id
+    )
+}
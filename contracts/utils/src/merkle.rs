@@ -0,0 +1,158 @@
+//! Merkle trees as built by nearcore, for outcome roots and the block merkle tree.
+
+use crate::hashes::{self, combine_hash2};
+use crate::{base_hash_format, merkle_u8_format, Hash, Hashable};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub const MERKLE_PATH_LEFT: u8 = 0;
+pub const MERKLE_PATH_RIGHT: u8 = 1;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MerkleError {
+    #[error("unknown merkle path direction {0}")]
+    UnknownDirection(u8),
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct MerklePath {
+    #[serde(flatten)]
+    pub items: Vec<MerklePathItem>,
+}
+
+impl MerklePath {
+    /// Walks the path from `node` up to the root.
+    pub fn compute_root(&self, node: &Hash) -> Result<Hash, MerkleError> {
+        let mut hash: Hash = *node;
+        for item in &self.items {
+            hash = match item.direction {
+                MERKLE_PATH_LEFT => combine_hash2(item.hash(), hash),
+                MERKLE_PATH_RIGHT => combine_hash2(hash, item.hash()),
+                direction => return Err(MerkleError::UnknownDirection(direction)),
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Whether the path leads from `node` to `root`, paths with unknown directions never do.
+    pub fn verify(&self, root: &Hash, node: &Hash) -> bool {
+        self.compute_root(node).as_ref() == Ok(root)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MerklePathItem {
+    #[serde(with = "base_hash_format")]
+    pub hash: Hash,
+    #[serde(with = "merkle_u8_format")]
+    pub direction: u8, // 0 = left, 1 = right
+}
+
+impl Hashable for MerklePathItem {
+    fn hash(&self) -> Hash {
+        self.hash
+    }
+}
+
+/// Merklizes borsh serializable items, leaves are the sha256 of their serialization.
+pub fn merklize<T: BorshSerialize>(items: &[T]) -> (Hash, Vec<MerklePath>) {
+    let leaves: Vec<Hash> = items
+        .iter()
+        .map(|item| hashes::sha256(&item.try_to_vec().expect("Failed to serialize")))
+        .collect();
+    merklize_hashes(&leaves)
+}
+
+/// Computes the root and the path of every leaf. An odd node at the end of a level is moved
+/// up unchanged, so the root matches `PartialMerkleTree` built from the same leaves.
+/// The root of an empty tree is all zeros.
+pub fn merklize_hashes(leaves: &[Hash]) -> (Hash, Vec<MerklePath>) {
+    let mut paths = vec![MerklePath { items: vec![] }; leaves.len()];
+    if leaves.is_empty() {
+        return (Hash::default(), paths);
+    }
+
+    // indices of the leaves below each node of the current level
+    let mut level: Vec<(Hash, std::ops::Range<usize>)> = leaves
+        .iter()
+        .enumerate()
+        .map(|(i, leaf)| (*leaf, i..i + 1))
+        .collect();
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity((level.len() + 1) / 2);
+        for pair in level.chunks(2) {
+            match pair {
+                [(left, left_leaves), (right, right_leaves)] => {
+                    for i in left_leaves.clone() {
+                        paths[i].items.push(MerklePathItem {
+                            hash: *right,
+                            direction: MERKLE_PATH_RIGHT,
+                        });
+                    }
+                    for i in right_leaves.clone() {
+                        paths[i].items.push(MerklePathItem {
+                            hash: *left,
+                            direction: MERKLE_PATH_LEFT,
+                        });
+                    }
+                    let hash = combine_hash2(*left, *right);
+                    next.push((hash, left_leaves.start..right_leaves.end));
+                }
+                [single] => next.push(single.clone()),
+                _ => unreachable!(),
+            }
+        }
+        level = next;
+    }
+    (level[0].0, paths)
+}
+
+/// Merkle tree that only keeps the path for the next leaf, this is how nearcore maintains
+/// the tree of all block hashes whose root is `block_merkle_root`. A `block_proof` for the
+/// i-th block is the i-th path of `merklize_hashes` over the same block hashes.
+#[derive(
+    BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PartialMerkleTree {
+    /// Roots of the complete subtrees, from the largest to the smallest.
+    path: Vec<Hash>,
+    /// Number of leaves in the tree.
+    size: u64,
+}
+
+impl PartialMerkleTree {
+    pub fn root(&self) -> Hash {
+        match self.path.split_last() {
+            None => Hash::default(),
+            Some((last, rest)) => rest
+                .iter()
+                .rev()
+                .fold(*last, |hash, node| combine_hash2(*node, hash)),
+        }
+    }
+
+    pub fn insert(&mut self, leaf: Hash) {
+        let mut size = self.size;
+        let mut node = leaf;
+        while size % 2 == 1 {
+            let sibling = self.path.pop().unwrap();
+            node = combine_hash2(sibling, node);
+            size /= 2;
+        }
+        self.path.push(node);
+        self.size += 1;
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn get_path(&self) -> &[Hash] {
+        &self.path
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod account {
+    mod test {
+        use utils::{bridge_token_account_name, MAX_ACCOUNT_ID_LENGTH};
+
+        // same accounts as the bridge_token_deployer sandbox tests
+        const DEPLOYER_ACCOUNT_ID: &str = "dev-1668507284663-45605813374523";
+        const MASTER_ACCOUNT: &str = "testnet";
+
+        fn name(source_address: &str) -> String {
+            bridge_token_account_name(source_address, MASTER_ACCOUNT, DEPLOYER_ACCOUNT_ID)
+        }
+
+        #[test]
+        fn short_name() {
+            assert_eq!(
+                name("dev-0.testnet"),
+                format!("dev-0.{}", DEPLOYER_ACCOUNT_ID)
+            );
+        }
+
+        #[test]
+        fn short_name_different_suffix() {
+            assert_eq!(
+                name("dev-0.calimero"),
+                format!("dev-0_calimero.{}", DEPLOYER_ACCOUNT_ID)
+            );
+        }
+
+        #[test]
+        fn long_name() {
+            let expected = format!("753ee4393ef6cfddcdcf6fff53188f6.{}", DEPLOYER_ACCOUNT_ID);
+            assert_eq!(name("dev-1111111111111-11111111111111.testnet"), expected);
+            assert_eq!(name("dev-1111111111111-11111111111111"), expected);
+            assert_eq!(expected.len(), MAX_ACCOUNT_ID_LENGTH);
+        }
+
+        #[test]
+        fn long_name_different_suffix() {
+            assert_eq!(
+                name("dev-1111111111111-11111111111111.calimero"),
+                format!("2d39e1f9245ca96d18551c93efb233f.{}", DEPLOYER_ACCOUNT_ID)
+            );
+        }
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod hasher {
+    mod test {
+        use utils::hashes::{
+            combine_hash2, decode_hex, encode_hex, sha256, Hasher, NearHasher, Sha256Hasher,
+        };
+        use utils::Hash;
+
+        const INPUTS: &[&[u8]] = &[b"", b"abc", &[0u8; 64], &[0xffu8; 1000]];
+
+        #[test]
+        fn sha256_known_values() {
+            assert_eq!(
+                encode_hex(&sha256(b"")),
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            );
+            assert_eq!(
+                encode_hex(&sha256(b"abc")),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            );
+        }
+
+        #[test]
+        fn native_hasher_matches_host_function() {
+            for input in INPUTS {
+                assert_eq!(Sha256Hasher::sha256(input), NearHasher::sha256(input));
+            }
+        }
+
+        #[test]
+        fn combine_hash2_matches_host_function() {
+            let x: Hash = decode_hex("c4770276d5e782d847ea3ce0674894a572df3ea75b960ff57d66395df0eb2a34")
+                .try_into()
+                .unwrap();
+            let y: Hash = decode_hex("f28629da269e59f2494c6bf283e9e67dadaa1c1f753607650d21e5e5b916a0dc")
+                .try_into()
+                .unwrap();
+            let expected = NearHasher::sha256(&[x, y].concat());
+            assert_eq!(combine_hash2(x, y), expected);
+            assert_eq!(NearHasher::combine_hash2(x, y), expected);
+            assert_ne!(combine_hash2(y, x), expected);
+        }
+    }
+}
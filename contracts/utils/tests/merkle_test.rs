@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod merkle {
+    mod test {
+        use near_sdk::serde_json;
+        use proptest::prelude::*;
+        use utils::hashes::{combine_hash2, sha256};
+        use utils::merkle::{
+            merklize, merklize_hashes, MerkleError, MerklePath, MerklePathItem, PartialMerkleTree,
+            MERKLE_PATH_LEFT, MERKLE_PATH_RIGHT,
+        };
+        use utils::Hash;
+
+        fn leaves(n: u8) -> Vec<Hash> {
+            (0..n).map(|i| sha256(&[i])).collect()
+        }
+
+        /// Root as defined by nearcore for the block merkle tree.
+        fn reference_root(hashes: &[Hash]) -> Hash {
+            match hashes.len() {
+                0 => Hash::default(),
+                1 => hashes[0],
+                len => {
+                    let subtree_len = len.next_power_of_two() / 2;
+                    combine_hash2(
+                        reference_root(&hashes[..subtree_len]),
+                        reference_root(&hashes[subtree_len..]),
+                    )
+                }
+            }
+        }
+
+        #[test]
+        fn combine_hash_stability() {
+            // value from nearcore's merkle tests
+            assert_eq!(
+                combine_hash2(Hash::default(), Hash::default()),
+                [
+                    245, 165, 253, 66, 209, 106, 32, 48, 39, 152, 239, 110, 211, 9, 151, 155, 67,
+                    0, 61, 35, 32, 217, 240, 232, 234, 152, 49, 169, 39, 89, 251, 75
+                ]
+            );
+        }
+
+        #[test]
+        fn paths_lead_to_root() {
+            for n in 0..40 {
+                let leaves = leaves(n);
+                let (root, paths) = merklize_hashes(&leaves);
+                assert_eq!(root, reference_root(&leaves), "{} leaves", n);
+                assert_eq!(paths.len(), leaves.len());
+                for (leaf, path) in leaves.iter().zip(&paths) {
+                    assert!(path.verify(&root, leaf));
+                }
+            }
+        }
+
+        #[test]
+        fn wrong_path_is_rejected() {
+            let leaves = leaves(3);
+            let (root, paths) = merklize_hashes(&leaves);
+            for i in 0..leaves.len() {
+                assert!(!paths[(i + 1) % 3].verify(&root, &leaves[i]));
+            }
+        }
+
+        #[test]
+        fn path_directions() {
+            let leaves = leaves(3);
+            let (root, paths) = merklize_hashes(&leaves);
+            assert_eq!(root, combine_hash2(combine_hash2(leaves[0], leaves[1]), leaves[2]));
+            let directions = |path: &MerklePath| {
+                path.items.iter().map(|item| item.direction).collect::<Vec<_>>()
+            };
+            assert_eq!(directions(&paths[0]), [MERKLE_PATH_RIGHT, MERKLE_PATH_RIGHT]);
+            assert_eq!(directions(&paths[1]), [MERKLE_PATH_LEFT, MERKLE_PATH_RIGHT]);
+            // the odd leaf is moved up and only gets a sibling at the top level
+            assert_eq!(directions(&paths[2]), [MERKLE_PATH_LEFT]);
+        }
+
+        #[test]
+        fn merklize_hashes_items() {
+            let items: Vec<u32> = vec![111, 222, 333];
+            let (root, paths) = merklize(&items);
+            let leaves: Vec<Hash> = items.iter().map(|item| sha256(&item.to_le_bytes())).collect();
+            assert_eq!(merklize_hashes(&leaves), (root, paths));
+        }
+
+        #[test]
+        fn partial_merkle_tree_root() {
+            let mut tree = PartialMerkleTree::default();
+            let mut hashes = vec![];
+            for leaf in leaves(50) {
+                assert_eq!(tree.root(), reference_root(&hashes));
+                assert_eq!(tree.size(), hashes.len() as u64);
+                hashes.push(leaf);
+                tree.insert(leaf);
+            }
+        }
+
+        #[test]
+        fn block_proofs_from_partial_merkle_tree() {
+            let blocks = leaves(21);
+            let mut tree = PartialMerkleTree::default();
+            for block in &blocks {
+                tree.insert(*block);
+            }
+
+            let (root, paths) = merklize_hashes(&blocks);
+            assert_eq!(root, tree.root());
+            for (block, path) in blocks.iter().zip(&paths) {
+                assert_eq!(path.compute_root(block).unwrap(), tree.root());
+            }
+        }
+
+        #[test]
+        fn path_json_format() {
+            let (_, paths) = merklize_hashes(&leaves(2));
+            let json = serde_json::to_value(&paths[0]).unwrap();
+            assert_eq!(json[0]["direction"], "Right");
+            assert_eq!(serde_json::from_value::<MerklePath>(json).unwrap(), paths[0]);
+        }
+
+        #[test]
+        fn unknown_direction_is_rejected() {
+            let path =
+                MerklePath { items: vec![MerklePathItem { hash: Hash::default(), direction: 2 }] };
+            assert_eq!(path.compute_root(&Hash::default()), Err(MerkleError::UnknownDirection(2)));
+            assert!(!path.verify(&Hash::default(), &Hash::default()));
+            let json = serde_json::json!([{ "hash": "11111111111111111111111111111111", "direction": "Up" }]);
+            assert!(serde_json::from_value::<MerklePath>(json).is_err());
+        }
+
+        fn arbitrary_path() -> impl Strategy<Value = MerklePath> {
+            proptest::collection::vec((any::<Hash>(), any::<u8>()), 0..8).prop_map(|items| {
+                MerklePath {
+                    items: items
+                        .into_iter()
+                        .map(|(hash, direction)| MerklePathItem { hash, direction })
+                        .collect(),
+                }
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn compute_root_never_panics(path in arbitrary_path(), node in any::<Hash>()) {
+                let known = path.items.iter().all(|item| item.direction <= MERKLE_PATH_RIGHT);
+                prop_assert_eq!(path.compute_root(&node).is_ok(), known);
+            }
+
+            #[test]
+            fn merklized_paths_verify(leaves in proptest::collection::vec(any::<Hash>(), 1..40)) {
+                let (root, paths) = merklize_hashes(&leaves);
+                prop_assert_eq!(root, reference_root(&leaves));
+                for (leaf, path) in leaves.iter().zip(&paths) {
+                    prop_assert!(path.verify(&root, leaf));
+                }
+            }
+
+            #[test]
+            fn path_json_round_trip(path in arbitrary_path()) {
+                let path = MerklePath {
+                    items: path
+                        .items
+                        .into_iter()
+                        .map(|item| MerklePathItem { direction: item.direction % 2, ..item })
+                        .collect(),
+                };
+                let json = serde_json::to_string(&path).unwrap();
+                prop_assert_eq!(serde_json::from_str::<MerklePath>(&json).unwrap(), path);
+            }
+
+            #[test]
+            fn path_json_never_panics(json in "\\PC*") {
+                let _ = serde_json::from_str::<MerklePath>(&json);
+            }
+        }
+    }
+}
@@ -1,14 +1,14 @@
 use admin_controlled::Mask;
-use connector_base::OtherNetworkAware;
+use connector_base::events::{self, ChainDomain};
+use connector_base::used_events::UsedEvents;
+use connector_base::{require_domain, require_event, OtherNetworkAware};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupSet;
 use near_sdk::serde_json;
-use near_sdk::{
-    env, near_bindgen, require, AccountId, Balance, Gas, PanicOnDefault, PromiseResult,
-};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Gas, PanicOnDefault, PromiseResult};
 
-use types::FullOutcomeProof;
-use utils::{hashes, Hash};
+use types::errors::panic_err;
+use types::{require_err, BridgeError, EncodedOutcomeProof, FullOutcomeProof};
+use utils::hashes;
 
 const NO_DEPOSIT: Balance = 0;
 
@@ -28,6 +28,9 @@ pub const PAUSE_CROSS_CALL: Mask = 1 << 0;
 const CALIMERO_EVENT_CROSS_CALL: &str = "CALIMERO_EVENT_CROSS_CALL";
 const CALIMERO_EVENT_CROSS_RESPONSE: &str = "CALIMERO_EVENT_CROSS_RESPONSE";
 
+/// Response logged in place of the base64 result when the destination call fails.
+const CROSS_CALL_FAILED: &str = "FAILED!";
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct CrossShardConnector {
@@ -37,8 +40,10 @@ pub struct CrossShardConnector {
     pub connector_permissions_account: AccountId,
     /// The account of the locker on other network that is used to burn NFT
     pub locker_account: Option<AccountId>,
+    /// Chain ids of this network and of the locker network
+    pub chain_domain: Option<ChainDomain>,
     /// Hashes of the events that were already used.
-    pub used_events: LookupSet<Hash>,
+    pub used_events: UsedEvents,
     /// Mask determining all paused functions
     paused: Mask,
     /// duration in nanoseconds for which proof is considered valid
@@ -58,12 +63,13 @@ impl CrossShardConnector {
         connector_permissions_account: AccountId,
         proof_validity_ns: Option<u64>,
     ) -> Self {
-        require!(!env::state_exists(), "Already initialized");
+        require_err!(!env::state_exists(), BridgeError::AlreadyInitialized);
         Self {
             prover_account,
             connector_permissions_account,
-            used_events: LookupSet::new(b"u".to_vec()),
+            used_events: UsedEvents::new(b"u", b"b"),
             locker_account: None,
+            chain_domain: None,
             paused: Mask::default(),
             proof_validity_ns,
         }
@@ -81,6 +87,7 @@ impl CrossShardConnector {
         source_callback_method: String,
     ) {
         self.assert_not_paused(PAUSE_CROSS_CALL);
+        require_domain(&self.chain_domain);
 
         let permission_promise = env::promise_create(
             self.connector_permissions_account.clone(),
@@ -121,7 +128,7 @@ impl CrossShardConnector {
         source_callback_method: String,
         cross_call_initiator_account_id: String,
     ) -> bool {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         let verification_success = match env::promise_result(0) {
             PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x).unwrap(),
@@ -130,7 +137,7 @@ impl CrossShardConnector {
 
         if verification_success {
             env::log_str(&format!(
-                "{}:{}:{}:{}:{}:{}:{}:{}",
+                "{}:{}:{}:{}:{}:{}:{}:{}:{}",
                 CALIMERO_EVENT_CROSS_CALL,
                 destination_contract_id,
                 destination_contract_method,
@@ -139,6 +146,7 @@ impl CrossShardConnector {
                 destination_deposit,
                 cross_call_initiator_account_id,
                 source_callback_method,
+                require_domain(&self.chain_domain).event_suffix(),
             ));
         }
 
@@ -146,47 +154,61 @@ impl CrossShardConnector {
     }
 
     #[payable]
-    pub fn cross_call_execute(&mut self, proof: FullOutcomeProof, height: u64) {
-        require!(self.locker_account.is_some(), "Locker account should be set");
-        require!(
+    pub fn cross_call_execute(&mut self, proof: EncodedOutcomeProof, height: u64) {
+        let proof = connector_base::decode_proof(proof);
+        require_err!(self.locker_account.is_some(), BridgeError::LockerNotSet);
+        let required = "cross_call receipt proof required";
+        require_err!(
             proof.outcome_proof.outcome_with_id.outcome.executor_id
                 == self.locker_account.as_ref().unwrap().to_string(),
-            "Untrusted prover account, cross_call receipt proof required"
+            BridgeError::UntrustedExecutor,
+            "{}",
+            required
+        );
+        let parts = require_event(
+            events::domain_event_params(
+                &proof.outcome_proof.outcome_with_id.outcome.logs,
+                CALIMERO_EVENT_CROSS_CALL,
+                8,
+                require_domain(&self.chain_domain),
+            ),
+            required,
         );
-        let event_log = proof.outcome_proof.outcome_with_id.outcome.logs[0].clone();
-        let parts: Vec<&str> = std::str::from_utf8(&event_log)
-            .unwrap()
-            .split(':')
-            .collect();
-        require!(
-            parts.len() == 8 && parts[0] == CALIMERO_EVENT_CROSS_CALL,
-            "Untrusted proof, cross_call receipt proof required"
+        let destination_contract = require_event(
+            events::parse_account("destination contract", &parts[1]),
+            required,
         );
-        let destination_contract = parts[1];
-        let destination_contract_method = parts[2];
-        let destination_contract_args = base64::decode(parts[3]).unwrap();
-        let destination_gas = Gas(parts[4].parse::<u64>().unwrap());
-        let destination_deposit = parts[5].parse::<u128>().unwrap();
-
-        require!(
-            destination_deposit == NO_DEPOSIT,
-            "Cross Shard Method called can not currently be payable"
+        let destination_contract_method = &parts[2];
+        let destination_contract_args = require_event(
+            events::decode_base64("destination args", &parts[3]),
+            required,
         );
-        require!(
+        let destination_gas = Gas(require_event(
+            events::parse_number("destination gas", &parts[4]),
+            required,
+        ));
+        let destination_deposit: Balance = require_event(
+            events::parse_number("destination deposit", &parts[5]),
+            required,
+        );
+
+        require_err!(destination_deposit == NO_DEPOSIT, BridgeError::CrossCallPayable);
+        require_err!(
             destination_gas <= MAX_DESTINATION_GAS,
-            &format!(
-                "Cross Shard Method called can currently use max of {} gas",
-                MAX_DESTINATION_GAS.0
-            )
+            BridgeError::CrossCallGas,
+            "max of {} gas",
+            MAX_DESTINATION_GAS.0
         );
 
-        let source_contract = parts[6];
-        let source_contract_method = parts[7];
+        let source_contract =
+            require_event(events::parse_account("source contract", &parts[6]), required);
+        let source_contract_method = &parts[7];
 
+        let encoded_proof = EncodedOutcomeProof::borsh(&proof);
         let promise_prover = env::promise_create(
             self.prover_account.clone(),
             "prove_outcome",
-            &serde_json::to_vec(&(proof.clone(), height)).unwrap(),
+            &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
             NO_DEPOSIT,
             VERIFY_LOG_ENTRY_GAS,
         );
@@ -204,7 +226,7 @@ impl CrossShardConnector {
                 destination_deposit,
                 source_contract,
                 source_contract_method,
-                proof,
+                encoded_proof,
             ))
             .unwrap(),
             env::attached_deposit(),
@@ -226,15 +248,17 @@ impl CrossShardConnector {
         destination_deposit: Balance,
         source_contract: AccountId,
         source_contract_method: String,
-        proof: FullOutcomeProof,
+        proof: EncodedOutcomeProof,
     ) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
+        let proof = connector_base::decode_proof(proof);
 
         let verification_success = match env::promise_result(0) {
-            PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x).unwrap(),
-            _ => env::panic_str("Prover failed"),
+            PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x)
+                .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed)),
+            _ => panic_err(BridgeError::ProverFailed),
         };
-        require!(verification_success, "Failed to verify the proof");
+        require_err!(verification_success, BridgeError::ProofNotVerified);
 
         let remaining_deposit = self.record_proof(&proof);
 
@@ -274,49 +298,62 @@ impl CrossShardConnector {
         source_contract_method: String,
         destination_contract: AccountId,
     ) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
 
         let execution_result = match env::promise_result(0) {
             PromiseResult::Successful(x) => base64::encode(x),
-            _ => "FAILED!".to_string(),
+            _ => CROSS_CALL_FAILED.to_string(),
         };
 
         env::log_str(&format!(
-            "{}:{}:{}:{}:{}",
+            "{}:{}:{}:{}:{}:{}",
             CALIMERO_EVENT_CROSS_RESPONSE,
             source_contract,
             source_contract_method,
             execution_result,
-            destination_contract
+            destination_contract,
+            require_domain(&self.chain_domain).event_suffix()
         ));
     }
 
     #[payable]
-    pub fn cross_call_receive_response(&mut self, proof: FullOutcomeProof, height: u64) {
-        require!(self.locker_account.is_some(), "Locker account should be set");
-        require!(
+    pub fn cross_call_receive_response(&mut self, proof: EncodedOutcomeProof, height: u64) {
+        let proof = connector_base::decode_proof(proof);
+        require_err!(self.locker_account.is_some(), BridgeError::LockerNotSet);
+        let required = "calimero_response receipt proof required";
+        require_err!(
             proof.outcome_proof.outcome_with_id.outcome.executor_id
                 == self.locker_account.as_ref().unwrap().to_string(),
-            "Untrusted prover account, calimero_response receipt proof required"
+            BridgeError::UntrustedExecutor,
+            "{}",
+            required
+        );
+        let parts = require_event(
+            events::domain_event_params(
+                &proof.outcome_proof.outcome_with_id.outcome.logs,
+                CALIMERO_EVENT_CROSS_RESPONSE,
+                5,
+                require_domain(&self.chain_domain),
+            ),
+            required,
         );
-        let event_log = proof.outcome_proof.outcome_with_id.outcome.logs[0].clone();
-        let parts: Vec<&str> = std::str::from_utf8(&event_log)
-            .unwrap()
-            .split(':')
-            .collect();
-        require!(
-            parts.len() == 5 && parts[0] == CALIMERO_EVENT_CROSS_RESPONSE,
-            "Untrusted proof, calimero_response receipt proof required"
+        let source_contract =
+            require_event(events::parse_account("source contract", &parts[1]), required);
+        let source_contract_method = &parts[2];
+        let response = &parts[3];
+        if response != CROSS_CALL_FAILED {
+            require_event(events::decode_base64("response", response), required);
+        }
+        let destination_predecessor = require_event(
+            events::parse_account("destination predecessor", &parts[4]),
+            required,
         );
-        let source_contract = parts[1];
-        let source_contract_method = parts[2];
-        let response = parts[3];
-        let destination_predecessor = parts[4];
 
+        let encoded_proof = EncodedOutcomeProof::borsh(&proof);
         let promise_prover = env::promise_create(
             self.prover_account.clone(),
             "prove_outcome",
-            &serde_json::to_vec(&(proof.clone(), height)).unwrap(),
+            &serde_json::to_vec(&(encoded_proof.clone(), height)).unwrap(),
             NO_DEPOSIT,
             VERIFY_LOG_ENTRY_GAS,
         );
@@ -331,7 +368,7 @@ impl CrossShardConnector {
                 source_contract_method,
                 destination_predecessor,
                 response,
-                proof,
+                encoded_proof,
             ))
             .unwrap(),
             env::attached_deposit(),
@@ -350,25 +387,30 @@ impl CrossShardConnector {
         source_contract_method: String,
         destination_predecessor: AccountId,
         response: String,
-        proof: FullOutcomeProof,
+        proof: EncodedOutcomeProof,
     ) {
-        require!(env::promise_results_count() == 1, "One and only one result was expected");
+        require_err!(env::promise_results_count() == 1, BridgeError::UnexpectedPromiseResults);
+        let proof = connector_base::decode_proof(proof);
 
         let verification_success = match env::promise_result(0) {
-            PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x).unwrap(),
-            _ => env::panic_str("Prover failed"),
+            PromiseResult::Successful(x) => serde_json::from_slice::<bool>(&x)
+                .unwrap_or_else(|_| panic_err(BridgeError::ProverFailed)),
+            _ => panic_err(BridgeError::ProverFailed),
         };
-        require!(verification_success, "Failed to verify the proof");
+        require_err!(verification_success, BridgeError::ProofNotVerified);
 
         let remaining_deposit = self.record_proof(&proof);
 
         let refund_promise = env::promise_batch_create(&caller_id);
         env::promise_batch_action_transfer(refund_promise, remaining_deposit);
 
-        let args = if response == "FAILED!" {
+        let args = if response == CROSS_CALL_FAILED {
             None
         } else {
-            Some(base64::decode(response).unwrap())
+            Some(
+                base64::decode(response)
+                    .unwrap_or_else(|_| panic_err(BridgeError::InvalidResponse)),
+            )
         };
 
         env::promise_return(env::promise_then(
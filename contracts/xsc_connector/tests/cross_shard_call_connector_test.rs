@@ -5,7 +5,7 @@ mod connector {
         use near_sdk::serde_json::json;
         use near_sdk::Gas;
         use near_units::{parse_gas, parse_near};
-        use test_utils::file_as_json;
+        use test_utils::{file_as_json, with_chain_ids};
         use types::ConnectorType;
         use utils::hashes::{decode_hex, encode_hex};
         use utils::Hash;
@@ -109,6 +109,17 @@ mod connector {
                 .await
                 .unwrap();
 
+            connector_contract
+                .call(&worker, "set_chain_domain")
+                .args_json(json!({
+                    "chain_id": "calimero",
+                    "peer_chain_id": "testnet",
+                }))
+                .unwrap()
+                .transact()
+                .await
+                .unwrap();
+
             tic_tac_toe_contract
                 .call(&worker, "new")
                 .transact()
@@ -158,7 +169,7 @@ mod connector {
             assert!(cross_call_execute_result.logs().len() == 3);
             assert!(cross_call_execute_result.logs()[0] == format!("RecordProof:{}", encode_hex(cross_call_execute_proof.outcome_proof.outcome_with_id.id.as_slice())));
             assert!(cross_call_execute_result.logs()[1] == format!("game ended, called by the connector {}", connector.id().to_string()));
-            assert!(cross_call_execute_result.logs()[2] == format!("CALIMERO_EVENT_CROSS_RESPONSE:testtictactoe.90.calimero.testnet:callback_game_ended::{}", TIC_TAC_TOE_ACCOUNT_90));
+            assert!(cross_call_execute_result.logs()[2] == format!("CALIMERO_EVENT_CROSS_RESPONSE:testtictactoe.90.calimero.testnet:callback_game_ended::{}:calimero:testnet", TIC_TAC_TOE_ACCOUNT_90));
 
             let ended_game_after_cross_call: serde_json::Value = worker.view(
                 contract_making_cross_shard_calls.id(),
@@ -218,14 +229,14 @@ mod connector {
         #[tokio::test]
         async fn test_cross_call_execute() {
             let (worker, prover, connector, tic_tac_toe_contract, _connector_permissions) = init(TIC_TAC_TOE_ACCOUNT_90, LOCKER_ACCOUNT_90).await;
-            let cross_call_execute_proof = &file_as_json::<FullOutcomeProof>("test_assets/cross_call_execute_proof.json").unwrap();
+            let cross_call_execute_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("test_assets/cross_call_execute_proof.json").unwrap(), "testnet", "calimero");
             cross_call_execute(&worker, &prover, &connector, &tic_tac_toe_contract, &cross_call_execute_proof).await;
         }
 
         #[tokio::test]
         async fn test_cross_call_receive_response() {
             let (worker, prover, connector, tic_tac_toe_contract, _connector_permissions) = init(TIC_TAC_TOE_ACCOUNT_REL42, LOCKER_ACCOUNT_REL42).await;
-            let cross_call_receive_response_proof = &file_as_json::<FullOutcomeProof>("test_assets/cross_call_receive_response_proof.json").unwrap();
+            let cross_call_receive_response_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("test_assets/cross_call_receive_response_proof.json").unwrap(), "testnet", "calimero");
             cross_call_receive_response(&worker, &prover, &connector, &tic_tac_toe_contract, &cross_call_receive_response_proof).await;
         }
 
@@ -233,7 +244,7 @@ mod connector {
         #[should_panic(expected = "Event cannot be reused for depositing")]
         async fn test_proof_reuse_panics() {
             let (worker, prover, connector, tic_tac_toe_contract, _connector_permissions) = init(TIC_TAC_TOE_ACCOUNT_REL42, LOCKER_ACCOUNT_REL42).await;
-            let cross_call_receive_response_proof = &file_as_json::<FullOutcomeProof>("test_assets/cross_call_receive_response_proof.json").unwrap();
+            let cross_call_receive_response_proof = &with_chain_ids(file_as_json::<FullOutcomeProof>("test_assets/cross_call_receive_response_proof.json").unwrap(), "testnet", "calimero");
             cross_call_receive_response(&worker, &prover, &connector, &tic_tac_toe_contract, &cross_call_receive_response_proof).await;
 
             // should panic since reusing proof
@@ -324,7 +335,7 @@ mod connector {
 
             // verify CALIMERO_EVENT_CROSS_CALL event was emitted
             let parts: Vec<&str> = cross_call_result_for_allowed_account.logs()[0].split(":").collect();
-            assert_eq!(parts.len(), 8);
+            assert_eq!(parts.len(), 10);
             assert!(parts[0] == "CALIMERO_EVENT_CROSS_CALL");
             assert!(parts[1] == TIC_TAC_TOE_ACCOUNT_90);
             assert!(parts[2] == "start_game");
@@ -333,6 +344,8 @@ mod connector {
             assert!(parts[5] == "0");
             assert!(parts[6] == ALICE_ACCOUNT_ID);
             assert!(parts[7] == "game_started");
+            assert!(parts[8] == "calimero");
+            assert!(parts[9] == "testnet");
         }
 
         #[tokio::test]
@@ -25,6 +25,16 @@ cd ../types
 cargo test -- --nocapture
 echo "Types tests done."
 
+echo "Utils tests start..."
+cd ../utils
+cargo test -- --nocapture
+echo "Utils tests done."
+
+echo "Connector base tests start..."
+cd ../connector_base
+cargo test -- --nocapture
+echo "Connector base tests done."
+
 echo "Connector Permissions tests start..."
 cd ../connector_permissions
 ./build.sh
@@ -71,6 +81,26 @@ cd ../nft_connector
 cargo test -- --nocapture
 echo "NFT Connector tests done."
 
+echo "Bridge simulator tests start..."
+cd ../simulator
+./test.sh
+echo "Bridge simulator tests done."
+
+echo "Relayer tests start..."
+cd ../relayer
+cargo test -- --nocapture
+echo "Relayer tests done."
+
+echo "Bridge service tests start..."
+cd ../bridge_service
+BRIDGE_TOKEN=../ft_bridge_token.wasm cargo test -- --nocapture
+echo "Bridge service tests done."
+
+echo "Bridge CLI tests start..."
+cd ../bridge_cli
+cargo test -- --nocapture
+echo "Bridge CLI tests done."
+
 echo "XSC Connector tests start..."
 cd ../xsc_connector
 ./build.sh